@@ -20,12 +20,30 @@ fn export_test_vector_paths()
     }
     for entry in fs::read_dir(&cavp).unwrap() {
         let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_dir() {
+            continue;
+        }
         println!(
             "cargo:rustc-env=OXI_CAVP_{}={}",
             entry.path().file_name().unwrap().to_str().unwrap(),
             entry.path().display()
         );
     }
+
+    // The bit-oriented CAVP files are tested only once they have been added.
+    println!("cargo:rustc-check-cfg=cfg(oxi_cavp_bits)");
+    let bits = cavp.join("bits");
+    if bits.is_dir() {
+        println!("cargo:rustc-cfg=oxi_cavp_bits");
+        for entry in fs::read_dir(&bits).unwrap() {
+            let entry = entry.unwrap();
+            println!(
+                "cargo:rustc-env=OXI_CAVP_BITS_{}={}",
+                entry.path().file_name().unwrap().to_str().unwrap(),
+                entry.path().display()
+            );
+        }
+    }
 }
 
 fn main() { export_test_vector_paths(); }
@@ -5,9 +5,8 @@ fn main()
 {
     let mut ctx = Sha256::new();
     ctx.update(b"Hello, World!");
-    let d = ctx.finish_internal();
+    let d = ctx.finalize_reset();
     println!(" d = {}", hex::encode(d));
-    ctx.reset();
     ctx.update(b"Hei, Werden!");
     let dd = ctx.finish_boxed();
     println!("dd = {}", hex::encode(dd));
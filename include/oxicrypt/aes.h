@@ -59,6 +59,18 @@ void oxi_aes128_decrypt(const oxi_aes128_key_t* ctx, uint8_t* block, size_t bloc
 void oxi_aes192_decrypt(const oxi_aes128_key_t* ctx, uint8_t* block, size_t blocklen);
 void oxi_aes256_decrypt(const oxi_aes128_key_t* ctx, uint8_t* block, size_t blocklen);
 
+/* AES ENCRYPT/DECRYPT BLOCKS
+ *
+ * `input` and `output` must either be the same pointer, to work in place, or
+ * point to buffers that don't overlap at all. Partially overlapping buffers are
+ * undefined behaviour. */
+void oxi_aes128_encrypt_blocks(const oxi_aes128_key_t* ctx, const uint8_t* input, uint8_t* output, size_t blocklen);
+void oxi_aes192_encrypt_blocks(const oxi_aes192_key_t* ctx, const uint8_t* input, uint8_t* output, size_t blocklen);
+void oxi_aes256_encrypt_blocks(const oxi_aes256_key_t* ctx, const uint8_t* input, uint8_t* output, size_t blocklen);
+void oxi_aes128_decrypt_blocks(const oxi_aes128_key_t* ctx, const uint8_t* input, uint8_t* output, size_t blocklen);
+void oxi_aes192_decrypt_blocks(const oxi_aes192_key_t* ctx, const uint8_t* input, uint8_t* output, size_t blocklen);
+void oxi_aes256_decrypt_blocks(const oxi_aes256_key_t* ctx, const uint8_t* input, uint8_t* output, size_t blocklen);
+
 #ifdef __cplusplus
 }
 #endif
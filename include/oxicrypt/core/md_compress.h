@@ -1,16 +1,22 @@
 #ifndef OXICRYPT_CORE_MD_COMPRESS_H_
 #define OXICRYPT_CORE_MD_COMPRESS_H_
 
+#include <stdbool.h>
+#include <stddef.h>
 #include <stdint.h>
 
 #ifdef __cplusplus
 extern "C" {
 #endif
 
-void oxi_digest_compress_md5(uint32_t* state, const uint8_t* block);
-void oxi_digest_compress_sha1(uint32_t* state, const uint8_t* block);
-void oxi_digest_compress_sha256(uint32_t* state, const uint8_t* block);
-void oxi_digest_compress_sha512(uint64_t* state, const uint8_t* block);
+void oxi_digest_compress_md4(uint32_t* state, const uint8_t* block, size_t blocks);
+void oxi_digest_compress_md5(uint32_t* state, const uint8_t* block, size_t blocks);
+void oxi_digest_compress_ripemd160(uint32_t* state, const uint8_t* block, size_t blocks);
+void oxi_digest_compress_sha1(uint32_t* state, const uint8_t* block, size_t blocks);
+bool oxi_digest_compress_sha1dc(uint32_t* state, const uint8_t* block, size_t blocks, bool safe_hash);
+void oxi_digest_compress_sha256(uint32_t* state, const uint8_t* block, size_t blocks);
+void oxi_digest_compress_sha512(uint64_t* state, const uint8_t* block, size_t blocks);
+void oxi_digest_compress_sm3(uint32_t* state, const uint8_t* block, size_t blocks);
 
 #ifdef __cplusplus
 }
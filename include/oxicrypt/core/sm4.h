@@ -0,0 +1,27 @@
+#ifndef OXICRYPT_CORE_SM4_H_
+#define OXICRYPT_CORE_SM4_H_
+
+#include <stdint.h>
+#include <oxicrypt/oxicrypt.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* SM4 ENCRYPT */
+void oxi_core_sm4_encrypt1(uint8_t* block, const uint32_t* key_schedule);
+
+/* SM4 DECRYPT */
+void oxi_core_sm4_decrypt1(uint8_t* block, const uint32_t* key_schedule);
+
+/* SM4 EXPAND KEY */
+void oxi_core_sm4_expand_key(const uint8_t* key, uint32_t* key_schedule);
+
+/* SM4 INVERSE KEY */
+void oxi_core_sm4_inverse_key(uint32_t* key_schedule);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // OXICRYPT_CORE_SM4_H_
@@ -2,6 +2,7 @@
 
 use core::marker::ConstParamTy;
 use core::mem::MaybeUninit;
+use core::ptr;
 
 #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
 use oxicrypt_core::aes_arm;
@@ -334,90 +335,184 @@ where
     /// # Safety
     ///
     /// * Length of `block` must be a multiple of 16.
-    pub unsafe fn encrypt_unchecked(&self, mut block: &mut [u8])
+    pub unsafe fn encrypt_unchecked(&self, block: &mut [u8])
+    {
+        let block_ptr = block.as_mut_ptr();
+        self.encrypt_raw(block_ptr, block_ptr, block.len() / 16);
+    }
+
+    /// Encrypts a single block in-place.
+    pub fn encrypt_block(&self, block: &mut [u8; 16])
+    {
+        let block_ptr = block.as_mut_ptr();
+        unsafe { self.encrypt_raw(block_ptr, block_ptr, 1) };
+    }
+
+    /// Encrypts the `input` block and writes the result to `output`.
+    pub fn encrypt_block_to(&self, input: &[u8; 16], output: &mut [u8; 16])
+    {
+        unsafe { self.encrypt_raw(input.as_ptr(), output.as_mut_ptr(), 1) };
+    }
+
+    /// Encrypts the given blocks in-place.
+    pub fn encrypt_chunks(&self, blocks: &mut [[u8; 16]])
+    {
+        let blocks_ptr = blocks.as_mut_ptr().cast::<u8>();
+        unsafe { self.encrypt_raw(blocks_ptr, blocks_ptr, blocks.len()) };
+    }
+
+    /// Encrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// Unlike [`encrypt`](`Self::encrypt`), the input buffer is only read from,
+    /// so it does not have to be copied into a mutable buffer first.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when the length of `input` is not a
+    /// multiple of 16, or when the length of `output` is not equal to the
+    /// length of `input`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::aes::*;
+    /// let key: Vec<u8> = (0u8..Key128::KEY_LEN as u8).collect();
+    /// let keysched = Key128::with_encrypt_key(&key).unwrap();
+    /// let plaintext = [0x42; 64];
+    ///
+    /// let mut ciphertext = [0; 64];
+    /// keysched
+    ///     .encrypt_blocks(&plaintext, &mut ciphertext)
+    ///     .unwrap();
+    ///
+    /// let mut block = plaintext;
+    /// keysched.encrypt(&mut block).unwrap();
+    /// assert_eq!(block, ciphertext);
+    /// ```
+    pub fn encrypt_blocks(&self, input: &[u8], output: &mut [u8]) -> Result<(), LenError>
+    {
+        if input.len() % 16 != 0 {
+            return Err(LenError {
+                field:    "input",
+                expected: input.len() / 16 * 16,
+                got:      input.len(),
+            });
+        }
+        if output.len() != input.len() {
+            return Err(LenError {
+                field:    "output",
+                expected: input.len(),
+                got:      output.len(),
+            });
+        }
+        unsafe { self.encrypt_blocks_unchecked(input, output) };
+        Ok(())
+    }
+
+    /// Encrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `input` must be a multiple of 16.
+    /// * Length of `output` must be at least the length of `input`.
+    pub unsafe fn encrypt_blocks_unchecked(&self, input: &[u8], output: &mut [u8])
+    {
+        self.encrypt_raw(input.as_ptr(), output.as_mut_ptr(), input.len() / 16);
+    }
+
+    /// Encrypts `n` blocks read from `input` and writes them to `output`.
+    ///
+    /// `input` and `output` may point to the same buffer, but they must not
+    /// overlap otherwise.
+    unsafe fn encrypt_raw(&self, mut input: *const u8, mut output: *mut u8, mut n: usize)
     {
         if Feature::Aesni.is_available() {
-            while !block.is_empty() {
-                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-                match block.len() / 16 {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            while n != 0 {
+                let step = match n {
                     | n if n >= 8 => {
                         match V {
-                            | Aes128 => aesni::aes128_encrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_encrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_encrypt8(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_encrypt8_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_encrypt8_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_encrypt8_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[8 * 16..];
+                        8
                     },
                     | n if n >= 4 => {
                         match V {
-                            | Aes128 => aesni::aes128_encrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_encrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_encrypt4(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_encrypt4_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_encrypt4_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_encrypt4_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[4 * 16..];
+                        4
                     },
                     | n if n >= 2 => {
                         match V {
-                            | Aes128 => aesni::aes128_encrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_encrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_encrypt2(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_encrypt2_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_encrypt2_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_encrypt2_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[2 * 16..];
+                        2
                     },
                     | _ => {
                         match V {
-                            | Aes128 => aesni::aes128_encrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_encrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_encrypt1(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_encrypt1_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_encrypt1_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_encrypt1_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[1 * 16..];
+                        1
                     },
-                }
+                };
+                input = input.add(step * 16);
+                output = output.add(step * 16);
+                n -= step;
             }
         } else if Feature::ArmAes.is_available() {
-            while !block.is_empty() {
-                #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
-                match block.len() / 16 {
+            #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+            while n != 0 {
+                let step = match n {
                     | n if n >= 8 => {
                         match V {
-                            | Aes128 => aes_arm::aes128_encrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_encrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_encrypt8(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_encrypt8_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_encrypt8_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_encrypt8_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[8 * 16..];
+                        8
                     },
                     | n if n >= 4 => {
                         match V {
-                            | Aes128 => aes_arm::aes128_encrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_encrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_encrypt4(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_encrypt4_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_encrypt4_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_encrypt4_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[4 * 16..];
+                        4
                     },
                     | n if n >= 2 => {
                         match V {
-                            | Aes128 => aes_arm::aes128_encrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_encrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_encrypt2(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_encrypt2_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_encrypt2_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_encrypt2_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[2 * 16..];
+                        2
                     },
                     | _ => {
                         match V {
-                            | Aes128 => aes_arm::aes128_encrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_encrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_encrypt1(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_encrypt1_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_encrypt1_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_encrypt1_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[1 * 16..];
+                        1
                     },
-                }
+                };
+                input = input.add(step * 16);
+                output = output.add(step * 16);
+                n -= step;
             }
         } else {
-            for block in block.as_chunks_unchecked_mut::<16>() {
+            ptr::copy(input, output, n * 16);
+            for i in 0..n {
                 match V {
-                    | Aes128 => aes_lut::aes128_encrypt1(block.as_mut_ptr(), self.as_ptr()),
-                    | Aes192 => aes_lut::aes192_encrypt1(block.as_mut_ptr(), self.as_ptr()),
-                    | Aes256 => aes_lut::aes256_encrypt1(block.as_mut_ptr(), self.as_ptr()),
+                    | Aes128 => aes_lut::aes128_encrypt1(output.add(i * 16), self.as_ptr()),
+                    | Aes192 => aes_lut::aes192_encrypt1(output.add(i * 16), self.as_ptr()),
+                    | Aes256 => aes_lut::aes256_encrypt1(output.add(i * 16), self.as_ptr()),
                 }
             }
         }
@@ -428,90 +523,166 @@ where
     /// # Safety
     ///
     /// * Length of `block` must be a multiple of 16.
-    pub unsafe fn decrypt_unchecked(&self, mut block: &mut [u8])
+    pub unsafe fn decrypt_unchecked(&self, block: &mut [u8])
+    {
+        let block_ptr = block.as_mut_ptr();
+        self.decrypt_raw(block_ptr, block_ptr, block.len() / 16);
+    }
+
+    /// Decrypts a single block in-place.
+    pub fn decrypt_block(&self, block: &mut [u8; 16])
+    {
+        let block_ptr = block.as_mut_ptr();
+        unsafe { self.decrypt_raw(block_ptr, block_ptr, 1) };
+    }
+
+    /// Decrypts the `input` block and writes the result to `output`.
+    pub fn decrypt_block_to(&self, input: &[u8; 16], output: &mut [u8; 16])
+    {
+        unsafe { self.decrypt_raw(input.as_ptr(), output.as_mut_ptr(), 1) };
+    }
+
+    /// Decrypts the given blocks in-place.
+    pub fn decrypt_chunks(&self, blocks: &mut [[u8; 16]])
+    {
+        let blocks_ptr = blocks.as_mut_ptr().cast::<u8>();
+        unsafe { self.decrypt_raw(blocks_ptr, blocks_ptr, blocks.len()) };
+    }
+
+    /// Decrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// Unlike [`decrypt`](`Self::decrypt`), the input buffer is only read from,
+    /// so it does not have to be copied into a mutable buffer first.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when the length of `input` is not a
+    /// multiple of 16, or when the length of `output` is not equal to the
+    /// length of `input`.
+    pub fn decrypt_blocks(&self, input: &[u8], output: &mut [u8]) -> Result<(), LenError>
+    {
+        if input.len() % 16 != 0 {
+            return Err(LenError {
+                field:    "input",
+                expected: input.len() / 16 * 16,
+                got:      input.len(),
+            });
+        }
+        if output.len() != input.len() {
+            return Err(LenError {
+                field:    "output",
+                expected: input.len(),
+                got:      output.len(),
+            });
+        }
+        unsafe { self.decrypt_blocks_unchecked(input, output) };
+        Ok(())
+    }
+
+    /// Decrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `input` must be a multiple of 16.
+    /// * Length of `output` must be at least the length of `input`.
+    pub unsafe fn decrypt_blocks_unchecked(&self, input: &[u8], output: &mut [u8])
+    {
+        self.decrypt_raw(input.as_ptr(), output.as_mut_ptr(), input.len() / 16);
+    }
+
+    /// Decrypts `n` blocks read from `input` and writes them to `output`.
+    ///
+    /// `input` and `output` may point to the same buffer, but they must not
+    /// overlap otherwise.
+    unsafe fn decrypt_raw(&self, mut input: *const u8, mut output: *mut u8, mut n: usize)
     {
         if Feature::Aesni.is_available() {
-            while !block.is_empty() {
-                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-                match block.len() / 16 {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            while n != 0 {
+                let step = match n {
                     | n if n >= 8 => {
                         match V {
-                            | Aes128 => aesni::aes128_decrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_decrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_decrypt8(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_decrypt8_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_decrypt8_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_decrypt8_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[8 * 16..];
+                        8
                     },
                     | n if n >= 4 => {
                         match V {
-                            | Aes128 => aesni::aes128_decrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_decrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_decrypt4(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_decrypt4_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_decrypt4_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_decrypt4_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[4 * 16..];
+                        4
                     },
                     | n if n >= 2 => {
                         match V {
-                            | Aes128 => aesni::aes128_decrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_decrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_decrypt2(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_decrypt2_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_decrypt2_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_decrypt2_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[2 * 16..];
+                        2
                     },
                     | _ => {
                         match V {
-                            | Aes128 => aesni::aes128_decrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aesni::aes192_decrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aesni::aes256_decrypt1(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aesni::aes128_decrypt1_to(input, output, self.as_ptr()),
+                            | Aes192 => aesni::aes192_decrypt1_to(input, output, self.as_ptr()),
+                            | Aes256 => aesni::aes256_decrypt1_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[1 * 16..];
+                        1
                     },
-                }
+                };
+                input = input.add(step * 16);
+                output = output.add(step * 16);
+                n -= step;
             }
         } else if Feature::ArmAes.is_available() {
-            while !block.is_empty() {
-                #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
-                match block.len() / 16 {
+            #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+            while n != 0 {
+                let step = match n {
                     | n if n >= 8 => {
                         match V {
-                            | Aes128 => aes_arm::aes128_decrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_decrypt8(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_decrypt8(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_decrypt8_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_decrypt8_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_decrypt8_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[8 * 16..];
+                        8
                     },
                     | n if n >= 4 => {
                         match V {
-                            | Aes128 => aes_arm::aes128_decrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_decrypt4(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_decrypt4(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_decrypt4_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_decrypt4_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_decrypt4_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[4 * 16..];
+                        4
                     },
                     | n if n >= 2 => {
                         match V {
-                            | Aes128 => aes_arm::aes128_decrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_decrypt2(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_decrypt2(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_decrypt2_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_decrypt2_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_decrypt2_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[2 * 16..];
+                        2
                     },
                     | _ => {
                         match V {
-                            | Aes128 => aes_arm::aes128_decrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes192 => aes_arm::aes192_decrypt1(block.as_mut_ptr(), self.as_ptr()),
-                            | Aes256 => aes_arm::aes256_decrypt1(block.as_mut_ptr(), self.as_ptr()),
+                            | Aes128 => aes_arm::aes128_decrypt1_to(input, output, self.as_ptr()),
+                            | Aes192 => aes_arm::aes192_decrypt1_to(input, output, self.as_ptr()),
+                            | Aes256 => aes_arm::aes256_decrypt1_to(input, output, self.as_ptr()),
                         }
-                        block = &mut block[1 * 16..];
+                        1
                     },
-                }
+                };
+                input = input.add(step * 16);
+                output = output.add(step * 16);
+                n -= step;
             }
         } else {
-            for block in block.as_chunks_unchecked_mut::<16>() {
+            ptr::copy(input, output, n * 16);
+            for i in 0..n {
                 match V {
-                    | Aes128 => aes_lut::aes128_decrypt1(block.as_mut_ptr(), self.as_ptr()),
-                    | Aes192 => aes_lut::aes192_decrypt1(block.as_mut_ptr(), self.as_ptr()),
-                    | Aes256 => aes_lut::aes256_decrypt1(block.as_mut_ptr(), self.as_ptr()),
+                    | Aes128 => aes_lut::aes128_decrypt1(output.add(i * 16), self.as_ptr()),
+                    | Aes192 => aes_lut::aes192_decrypt1(output.add(i * 16), self.as_ptr()),
+                    | Aes256 => aes_lut::aes256_decrypt1(output.add(i * 16), self.as_ptr()),
                 }
             }
         }
@@ -536,6 +707,15 @@ pub struct LenError
 
 impl LenError
 {
+    pub(crate) const fn new(field: &'static str, expected: usize, got: usize) -> Self
+    {
+        Self {
+            field,
+            expected,
+            got,
+        }
+    }
+
     pub const fn field(&self) -> &str { self.field }
 
     pub const fn expected(&self) -> usize { self.expected }
@@ -0,0 +1,781 @@
+//! # [Argon2]
+//!
+//! Memory-hard password hashing function from RFC 9106, version 1.3. The
+//! memory is made of `p_cost` lanes of 1 KiB blocks that are filled `t_cost`
+//! times with a compression function built on BLAKE2b. With the `std` feature,
+//! the lanes are filled on separate threads, up to the available parallelism.
+//!
+//! There are three variants:
+//!
+//! * [`Argon2d`](`Variant::Argon2d`) picks blocks depending on the password,
+//!   which makes it the most resistant to GPU cracking, but also vulnerable to
+//!   side-channel attacks.
+//! * [`Argon2i`](`Variant::Argon2i`) picks blocks independently of the
+//!   password.
+//! * [`Argon2id`](`Variant::Argon2id`) picks blocks like Argon2i for the first
+//!   half of the first pass and like Argon2d for the rest. It is the variant to
+//!   use for password hashing.
+//!
+//! ```
+//! use oxicrypt::argon2::*;
+//!
+//! let params = Params {
+//!     m_cost: 1024,
+//!     t_cost: 2,
+//!     p_cost: 1,
+//!     ..Default::default()
+//! };
+//! let mut key = [0; 32];
+//! derive(
+//!     Variant::Argon2id,
+//!     b"password",
+//!     b"somesalt",
+//!     &params,
+//!     &mut key,
+//! )
+//! .unwrap();
+//! ```
+//!
+//! # PHC strings
+//!
+//! [`hash`] stores the variant, the parameters, the salt and the tag in a PHC
+//! string, such as `$argon2id$v=19$m=65536,t=3,p=4$c29tZXNhbHQ$...`, which
+//! [`verify`] checks a password against. The associated data is stored as the
+//! `data` parameter, but the secret is not stored. See [`SALT_LEN`] for the
+//! salt.
+//!
+//! ```
+//! use oxicrypt::argon2::*;
+//!
+//! let params = Params {
+//!     m_cost: 1024,
+//!     t_cost: 2,
+//!     p_cost: 1,
+//!     ..Default::default()
+//! };
+//! let phc = hash(Variant::Argon2id, b"hunter2", b"saltysaltysalty!", &params).unwrap();
+//! assert!(verify(b"hunter2", &phc).is_ok());
+//! assert_eq!(verify(b"hunter3", &phc), Err(VerifyError::Password));
+//! ```
+//!
+//! [Argon2]: https://www.rfc-editor.org/rfc/rfc9106
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::fmt::Write;
+
+use oxicrypt_core::argon2;
+
+use crate::blake2::Blake2b512;
+use crate::blake2::Blake2bVar;
+use crate::ct;
+use crate::digest::FinishToSlice;
+use crate::digest::Oneshot;
+use crate::digest::Update;
+use crate::phc;
+pub use crate::phc::DEFAULT_MAX_MEMORY;
+pub use crate::phc::MemoryError;
+use crate::phc::Phc;
+pub use crate::phc::SALT_LEN;
+pub use crate::phc::VerifyError;
+use crate::traits::New;
+
+/// Version of Argon2 that is implemented, which is 1.3.
+pub const VERSION: u32 = 0x13;
+
+/// Length of the tag in the PHC strings created by [`hash`].
+pub const HASH_LEN: usize = 32;
+
+/// Number of slices that a pass is split into. Lanes are synchronized after
+/// every slice.
+const SYNC_POINTS: usize = 4;
+
+/// Block of 1 KiB.
+type Block = [u64; 128];
+
+/// Variant of Argon2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variant
+{
+    /// Data-dependent memory access.
+    Argon2d  = 0,
+    /// Data-independent memory access.
+    Argon2i  = 1,
+    /// Data-independent memory access for the first half of the first pass,
+    /// then data-dependent memory access.
+    Argon2id = 2,
+}
+
+impl Variant
+{
+    /// Name of the variant, which is its identifier in PHC strings.
+    pub const fn name(self) -> &'static str
+    {
+        match self {
+            | Self::Argon2d => "argon2d",
+            | Self::Argon2i => "argon2i",
+            | Self::Argon2id => "argon2id",
+        }
+    }
+
+    /// Look up a variant by its [`name`](`Self::name`).
+    pub fn from_name(name: &str) -> Option<Self>
+    {
+        [Self::Argon2d, Self::Argon2i, Self::Argon2id]
+            .into_iter()
+            .find(|v| v.name() == name)
+    }
+}
+
+impl Display for Variant
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parameters of Argon2.
+///
+/// The default parameters are the second recommended option of RFC 9106, which
+/// needs 64 MiB of memory.
+#[derive(Debug, Clone, Copy)]
+pub struct Params<'a>
+{
+    /// Memory size in KiB, which must be at least `8 * p_cost`. It is rounded
+    /// down to a multiple of `4 * p_cost`.
+    pub m_cost: u32,
+    /// Number of passes over the memory.
+    pub t_cost: u32,
+    /// Number of lanes, which must be less than `2^24`.
+    pub p_cost: u32,
+    /// Secret value, such as a key that isn't stored with the hashes.
+    pub secret: &'a [u8],
+    /// Associated data.
+    pub ad:     &'a [u8],
+}
+
+impl Default for Params<'_>
+{
+    fn default() -> Self
+    {
+        Self {
+            m_cost: 1 << 16,
+            t_cost: 3,
+            p_cost: 4,
+            secret: &[],
+            ad:     &[],
+        }
+    }
+}
+
+impl Params<'_>
+{
+    /// Number of bytes of memory needed to derive a key with these parameters.
+    pub const fn memory_len(&self) -> u64
+    {
+        if self.p_cost == 0 {
+            return 0;
+        }
+        let lanes = self.p_cost as u64;
+        let lane_len = self.m_cost as u64 / (SYNC_POINTS as u64 * lanes) * SYNC_POINTS as u64;
+        lane_len * lanes * 1024
+    }
+
+    /// Check whether the parameters are valid.
+    pub fn check(&self) -> Result<(), ParamError>
+    {
+        if self.p_cost == 0 || self.p_cost >= 1 << 24 {
+            return Err(ParamError::PCost);
+        }
+        if (self.m_cost as u64) < 8 * self.p_cost as u64 {
+            return Err(ParamError::MCost);
+        }
+        if self.t_cost == 0 {
+            return Err(ParamError::TCost);
+        }
+        check_len(self.secret)?;
+        check_len(self.ad)?;
+        Ok(())
+    }
+}
+
+/// Derive a key from `password` and `salt`, filling `dk`.
+///
+/// `salt` must be at least 8 bytes long and `dk` at least 4 bytes long. The
+/// memory is allocated up front, and a failed allocation is returned as
+/// [`MemoryError::Alloc`].
+pub fn derive(
+    variant: Variant,
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    dk: &mut [u8],
+) -> Result<(), ParamError>
+{
+    #[cfg(feature = "std")]
+    let threads: usize = std::thread::available_parallelism().map_or(1, |n| n.get());
+    #[cfg(not(feature = "std"))]
+    let threads: usize = 1;
+    derive_threads(variant, password, salt, params, dk, threads)
+}
+
+/// Same as [`derive`], with the lanes filled by up to `threads` threads.
+fn derive_threads(
+    variant: Variant,
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    dk: &mut [u8],
+    threads: usize,
+) -> Result<(), ParamError>
+{
+    params.check()?;
+    check_len(password)?;
+    if salt.len() < 8 || salt.len() as u64 > u32::MAX as u64 {
+        return Err(ParamError::Salt);
+    }
+    if dk.len() < 4 || dk.len() as u64 > u32::MAX as u64 {
+        return Err(ParamError::DkLen);
+    }
+
+    let lanes: usize = params.p_cost as usize;
+    let lane_len: usize = params.m_cost as usize / (SYNC_POINTS * lanes) * SYNC_POINTS;
+    let mut memory: Vec<Block> = phc::try_alloc([0; 128], lanes * lane_len)?;
+
+    let mut h0: [u8; 72] = [0; 72];
+    let mut ctx = Blake2b512::new();
+    for input in [
+        &params.p_cost.to_le_bytes(),
+        &(dk.len() as u32).to_le_bytes(),
+        &params.m_cost.to_le_bytes(),
+        &params.t_cost.to_le_bytes(),
+        &VERSION.to_le_bytes(),
+        &(variant as u32).to_le_bytes(),
+        &(password.len() as u32).to_le_bytes(),
+        password,
+        &(salt.len() as u32).to_le_bytes(),
+        salt,
+        &(params.secret.len() as u32).to_le_bytes(),
+        params.secret,
+        &(params.ad.len() as u32).to_le_bytes(),
+        params.ad,
+    ] {
+        ctx.update(input);
+    }
+    ctx.finish_to_slice(&mut h0[0..64]);
+    for (lane, blocks) in memory.chunks_exact_mut(lane_len).enumerate() {
+        h0[68..72].copy_from_slice(&(lane as u32).to_le_bytes());
+        for (i, block) in blocks[0..2].iter_mut().enumerate() {
+            h0[64..68].copy_from_slice(&(i as u32).to_le_bytes());
+            let mut bytes: [u8; 1024] = [0; 1024];
+            h_prime(&mut bytes, &[&h0]);
+            block
+                .iter_mut()
+                .zip(bytes.chunks_exact(8))
+                .for_each(|(w, b)| *w = u64::from_le_bytes(b.try_into().unwrap()));
+        }
+    }
+
+    let instance = Instance {
+        variant,
+        lanes,
+        lane_len,
+        segment_len: lane_len / SYNC_POINTS,
+        passes: params.t_cost,
+    };
+    let blocks = Blocks(memory.as_mut_ptr());
+    for pass in 0..params.t_cost {
+        for slice in 0..SYNC_POINTS {
+            instance.fill_slice(blocks, pass, slice, threads);
+        }
+    }
+
+    let mut last: Block = memory[lane_len - 1];
+    for blocks in memory.chunks_exact(lane_len).skip(1) {
+        last.iter_mut()
+            .zip(&blocks[lane_len - 1])
+            .for_each(|(l, b)| *l ^= b);
+    }
+    let mut bytes: [u8; 1024] = [0; 1024];
+    bytes
+        .chunks_exact_mut(8)
+        .zip(&last)
+        .for_each(|(b, w)| b.copy_from_slice(&w.to_le_bytes()));
+    h_prime(dk, &[&bytes]);
+    Ok(())
+}
+
+/// Hash `password` into a PHC string, with a tag of [`HASH_LEN`] bytes.
+pub fn hash(
+    variant: Variant,
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+) -> Result<String, ParamError>
+{
+    let mut dk: [u8; HASH_LEN] = [0; HASH_LEN];
+    derive(variant, password, salt, params, &mut dk)?;
+
+    let mut s = String::new();
+    write!(
+        s,
+        "${variant}$v={VERSION}$m={},t={},p={}",
+        params.m_cost, params.t_cost, params.p_cost
+    )
+    .unwrap();
+    if !params.ad.is_empty() {
+        s.push_str(",data=");
+        phc::encode_b64(params.ad, &mut s);
+    }
+    s.push('$');
+    phc::encode_b64(salt, &mut s);
+    s.push('$');
+    phc::encode_b64(&dk, &mut s);
+    Ok(s)
+}
+
+/// Check `password` against a PHC string created by [`hash`] without a secret.
+///
+/// The parameters in the string may need at most [`DEFAULT_MAX_MEMORY`] bytes
+/// of memory.
+pub fn verify(password: &[u8], phc: &str) -> Result<(), VerifyError<ParamError>>
+{
+    verify_with_secret(password, phc, &[], DEFAULT_MAX_MEMORY)
+}
+
+/// Same as [`verify`], but the parameters in the string may need at most
+/// `max_memory` bytes of memory.
+pub fn verify_with_max_memory(
+    password: &[u8],
+    phc: &str,
+    max_memory: usize,
+) -> Result<(), VerifyError<ParamError>>
+{
+    verify_with_secret(password, phc, &[], max_memory)
+}
+
+/// Same as [`verify_with_max_memory`], but for a PHC string created by
+/// [`hash`] with `secret`.
+pub fn verify_with_secret(
+    password: &[u8],
+    phc: &str,
+    secret: &[u8],
+    max_memory: usize,
+) -> Result<(), VerifyError<ParamError>>
+{
+    let phc = Phc::parse(phc).ok_or(VerifyError::Phc)?;
+    let variant = Variant::from_name(phc.id).ok_or(VerifyError::Phc)?;
+    if phc.version != Some("19") {
+        return Err(VerifyError::Phc);
+    }
+    let ad = match phc.param("data") {
+        | Some(data) => phc::decode_b64(data).ok_or(VerifyError::Phc)?,
+        | None => Vec::new(),
+    };
+    if phc.param_count() != 3 + phc.param("data").is_some() as usize {
+        return Err(VerifyError::Phc);
+    }
+    let params = Params {
+        m_cost: phc.param_u32("m").ok_or(VerifyError::Phc)?,
+        t_cost: phc.param_u32("t").ok_or(VerifyError::Phc)?,
+        p_cost: phc.param_u32("p").ok_or(VerifyError::Phc)?,
+        secret,
+        ad: &ad,
+    };
+    let salt = phc::decode_b64(phc.salt).ok_or(VerifyError::Phc)?;
+    let expected = phc::decode_b64(phc.hash).ok_or(VerifyError::Phc)?;
+
+    params.check()?;
+    MemoryError::check(params.memory_len() as u128, max_memory).map_err(ParamError::from)?;
+    let mut dk: Vec<u8> = vec![0; expected.len()];
+    derive(variant, password, &salt, &params, &mut dk)?;
+    if !ct::eq(&dk, &expected) {
+        return Err(VerifyError::Password);
+    }
+    Ok(())
+}
+
+fn check_len(input: &[u8]) -> Result<(), ParamError>
+{
+    if input.len() as u64 > u32::MAX as u64 {
+        return Err(ParamError::InputLen);
+    }
+    Ok(())
+}
+
+/// Variable-length hash function H' over the concatenation of `data`, filling
+/// `out`.
+fn h_prime(mut out: &mut [u8], data: &[&[u8]])
+{
+    let mut ctx = Blake2bVar::new(out.len().min(64)).unwrap();
+    ctx.update(&(out.len() as u32).to_le_bytes());
+    for input in data {
+        ctx.update(input);
+    }
+    if out.len() <= 64 {
+        ctx.finish_to_slice(out);
+        return;
+    }
+
+    // Every 64-byte hash is the input of the next one, and only its first half
+    // goes to the output, except for the last hash.
+    let mut v: [u8; 64] = [0; 64];
+    ctx.finish_to_slice(&mut v);
+    loop {
+        out[0..32].copy_from_slice(&v[0..32]);
+        out = &mut out[32..];
+        if out.len() <= 64 {
+            let mut ctx = Blake2bVar::new(out.len()).unwrap();
+            ctx.update(&v);
+            ctx.finish_to_slice(out);
+            return;
+        }
+        v = Blake2b512::oneshot(&v);
+    }
+}
+
+/// Memory shared by the threads that fill the lanes.
+#[derive(Clone, Copy)]
+struct Blocks(*mut Block);
+
+// In a slice, every lane only writes to its own segment, and it only reads
+// blocks outside of the segments written by the other lanes.
+unsafe impl Send for Blocks {}
+unsafe impl Sync for Blocks {}
+
+/// Layout of the memory and the parameters needed to fill it.
+struct Instance
+{
+    variant:     Variant,
+    lanes:       usize,
+    lane_len:    usize,
+    segment_len: usize,
+    passes:      u32,
+}
+
+impl Instance
+{
+    /// Fill the segments of every lane in a slice, with up to `threads`
+    /// threads.
+    fn fill_slice(&self, blocks: Blocks, pass: u32, slice: usize, threads: usize)
+    {
+        #[cfg(not(feature = "std"))]
+        let _ = threads;
+        #[cfg(feature = "std")]
+        {
+            let threads: usize = threads.min(self.lanes);
+            if threads > 1 {
+                std::thread::scope(|s| {
+                    for first in 0..threads {
+                        s.spawn(move || {
+                            for lane in (first..self.lanes).step_by(threads) {
+                                unsafe { self.fill_segment(blocks, pass, slice, lane) };
+                            }
+                        });
+                    }
+                });
+                return;
+            }
+        }
+
+        for lane in 0..self.lanes {
+            unsafe { self.fill_segment(blocks, pass, slice, lane) };
+        }
+    }
+
+    /// Fill the segment of `lane` in a slice.
+    ///
+    /// # Safety
+    ///
+    /// No other segment of the same slice may be filled at the same time.
+    unsafe fn fill_segment(&self, blocks: Blocks, pass: u32, slice: usize, lane: usize)
+    {
+        let independent = match self.variant {
+            | Variant::Argon2d => false,
+            | Variant::Argon2i => true,
+            | Variant::Argon2id => pass == 0 && slice < SYNC_POINTS / 2,
+        };
+
+        // Data-independent addressing takes the indices from address blocks,
+        // which are generated from a counter.
+        let zero: Block = [0; 128];
+        let mut input: Block = [0; 128];
+        let mut address: Block = [0; 128];
+        input[0..6].copy_from_slice(&[
+            pass as u64,
+            lane as u64,
+            slice as u64,
+            (self.lanes * self.lane_len) as u64,
+            self.passes as u64,
+            self.variant as u64,
+        ]);
+        let mut next_address = |address: &mut Block| {
+            input[6] += 1;
+            argon2::argon2_compress(address.as_mut_ptr(), zero.as_ptr(), input.as_ptr(), false);
+            argon2::argon2_compress(address.as_mut_ptr(), zero.as_ptr(), address.as_ptr(), false);
+        };
+
+        // The first two blocks of each lane come from the initial hash.
+        let mut start: usize = 0;
+        if pass == 0 && slice == 0 {
+            start = 2;
+            if independent {
+                next_address(&mut address);
+            }
+        }
+
+        let lane_start: *mut Block = blocks.0.add(lane * self.lane_len);
+        for i in start..self.segment_len {
+            let index: usize = slice * self.segment_len + i;
+            let prev: *mut Block =
+                lane_start.add(if index == 0 { self.lane_len } else { index } - 1);
+
+            let rand: u64 = if independent {
+                if i % 128 == 0 {
+                    next_address(&mut address);
+                }
+                address[i % 128]
+            } else {
+                (*prev)[0]
+            };
+            let ref_lane: usize = if pass == 0 && slice == 0 {
+                lane
+            } else {
+                (rand >> 32) as usize % self.lanes
+            };
+            let ref_index = self.ref_index(pass, slice, i, rand as u32, ref_lane == lane);
+            let reference: *const Block = blocks.0.add(ref_lane * self.lane_len + ref_index);
+
+            argon2::argon2_compress(
+                lane_start.add(index).cast(),
+                prev.cast(),
+                reference.cast(),
+                pass > 0,
+            );
+        }
+    }
+
+    /// Index in the reference lane of the block that the block at index `i` of
+    /// the segment is compressed with.
+    fn ref_index(&self, pass: u32, slice: usize, i: usize, rand: u32, same_lane: bool) -> usize
+    {
+        // The reference set is the blocks that are already filled, except for
+        // the previous block and, in other lanes, the current segment.
+        let finished: usize = if pass == 0 {
+            slice * self.segment_len
+        } else {
+            self.lane_len - self.segment_len
+        };
+        let area: usize = if same_lane {
+            finished + i - 1
+        } else {
+            finished - (i == 0) as usize
+        };
+
+        let x: u64 = (rand as u64 * rand as u64) >> 32;
+        let y: u64 = (area as u64 * x) >> 32;
+        let start: usize = if pass == 0 || slice == SYNC_POINTS - 1 {
+            0
+        } else {
+            (slice + 1) * self.segment_len
+        };
+        (start + area - 1 - y as usize) % self.lane_len
+    }
+}
+
+/// Error type for when the parameters of Argon2 are not valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamError
+{
+    /// `m_cost` is less than `8 * p_cost`.
+    MCost,
+    /// `t_cost` is 0.
+    TCost,
+    /// `p_cost` is 0 or not less than `2^24`.
+    PCost,
+    /// The salt is shorter than 8 bytes or longer than `2^32 - 1` bytes.
+    Salt,
+    /// The derived key is shorter than 4 bytes or longer than `2^32 - 1`
+    /// bytes.
+    DkLen,
+    /// The password, the secret or the associated data is longer than
+    /// `2^32 - 1` bytes.
+    InputLen,
+    /// The memory is over the limit or could not be allocated.
+    Memory(MemoryError),
+}
+
+impl From<MemoryError> for ParamError
+{
+    fn from(e: MemoryError) -> Self { Self::Memory(e) }
+}
+
+impl Display for ParamError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | Self::MCost => write!(f, "Memory size must be at least 8 * p_cost KiB"),
+            | Self::TCost => write!(f, "Number of passes must be at least 1"),
+            | Self::PCost => write!(f, "Number of lanes must be at least 1 and less than 2^24"),
+            | Self::Salt => write!(f, "Salt must be at least 8 bytes long"),
+            | Self::DkLen => write!(f, "Derived key must be at least 4 bytes long"),
+            | Self::InputLen => write!(f, "Input is too long"),
+            | Self::Memory(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for ParamError {}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn rfc9106()
+    {
+        let params = Params {
+            m_cost: 32,
+            t_cost: 3,
+            p_cost: 4,
+            secret: &[3; 8],
+            ad:     &[4; 12],
+        };
+        for (variant, expected) in [
+            (
+                Variant::Argon2d,
+                "512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb",
+            ),
+            (
+                Variant::Argon2i,
+                "c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8",
+            ),
+            (
+                Variant::Argon2id,
+                "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659",
+            ),
+        ] {
+            let mut dk = [0; 32];
+            derive(variant, &[1; 32], &[2; 16], &params, &mut dk).unwrap();
+            assert_eq!(hex::encode(dk), expected, "{variant}");
+            // Fewer, as many and more threads than lanes.
+            for threads in [1, 2, 3, 4, 8] {
+                let mut dk = [0; 32];
+                derive_threads(variant, &[1; 32], &[2; 16], &params, &mut dk, threads).unwrap();
+                assert_eq!(hex::encode(dk), expected, "{variant}, threads = {threads}");
+            }
+        }
+    }
+
+    #[test]
+    fn phc()
+    {
+        // argon2 reference implementation
+        let phc = "$argon2i$v=19$m=65536,t=2,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG";
+        assert_eq!(verify(b"password", phc), Ok(()));
+        assert_eq!(verify(b"passwore", phc), Err(VerifyError::Password));
+        assert_eq!(
+            verify_with_max_memory(b"password", phc, 1 << 25),
+            Err(VerifyError::Params(ParamError::Memory(
+                MemoryError::Limit {
+                    at_most: 1 << 25,
+                    got:     1 << 26,
+                }
+            )))
+        );
+
+        let params = Params {
+            m_cost: 256,
+            t_cost: 2,
+            p_cost: 2,
+            secret: b"pepper",
+            ad:     b"user",
+        };
+        let phc = hash(Variant::Argon2id, b"hunter2", b"saltysaltysalty!", &params).unwrap();
+        assert!(
+            phc.starts_with("$argon2id$v=19$m=256,t=2,p=2,data=dXNlcg$c2FsdHlzYWx0eXNhbHR5IQ$")
+        );
+        assert_eq!(
+            verify_with_secret(b"hunter2", &phc, b"pepper", 1 << 20),
+            Ok(())
+        );
+        assert_eq!(verify(b"hunter2", &phc), Err(VerifyError::Password));
+
+        for phc in [
+            "$argon2id$m=256,t=2,p=2$c29tZXNhbHQ$RdescudvJCsgt3ub",
+            "$argon2id$v=16$m=256,t=2,p=2$c29tZXNhbHQ$RdescudvJCsgt3ub",
+            "$argon2id$v=19$m=256,t=2$c29tZXNhbHQ$RdescudvJCsgt3ub",
+            "$argon2id$v=19$m=256,t=2,p=2,x=1$c29tZXNhbHQ$RdescudvJCsgt3ub",
+            "$argon2id$v=19$m=256,t=2,p=2,data=!$c29tZXNhbHQ$RdescudvJCsgt3ub",
+            "$argon2x$v=19$m=256,t=2,p=2$c29tZXNhbHQ$RdescudvJCsgt3ub",
+            "$scrypt$ln=10,r=8,p=1$c29tZXNhbHQ$RdescudvJCsgt3ub",
+        ] {
+            assert_eq!(verify(b"password", phc), Err(VerifyError::Phc), "{phc}");
+        }
+    }
+
+    #[test]
+    fn params()
+    {
+        let mut dk = [0; 32];
+        for (m_cost, t_cost, p_cost, e) in [
+            (8, 1, 0, ParamError::PCost),
+            (1 << 30, 1, 1 << 24, ParamError::PCost),
+            (15, 1, 2, ParamError::MCost),
+            (16, 0, 2, ParamError::TCost),
+        ] {
+            let params = Params {
+                m_cost,
+                t_cost,
+                p_cost,
+                ..Default::default()
+            };
+            assert_eq!(
+                derive(Variant::Argon2id, b"", b"somesalt", &params, &mut dk),
+                Err(e)
+            );
+        }
+        let params = Params {
+            m_cost: 8,
+            t_cost: 1,
+            p_cost: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            derive(Variant::Argon2id, b"", b"salt", &params, &mut dk),
+            Err(ParamError::Salt)
+        );
+        assert_eq!(
+            derive(Variant::Argon2id, b"", b"somesalt", &params, &mut dk[0..3]),
+            Err(ParamError::DkLen)
+        );
+    }
+
+    #[test]
+    fn memory_len()
+    {
+        for (m_cost, p_cost, expected) in [
+            (32, 4, 32 * 1024),
+            (33, 4, 32 * 1024),
+            (65536, 4, 65536 * 1024),
+            (u32::MAX, 1, (u32::MAX as u64 - 3) * 1024),
+            (u32::MAX, 1 << 30, 0),
+            (u32::MAX, u32::MAX, 0),
+            (8, 0, 0),
+        ] {
+            let params = Params {
+                m_cost,
+                p_cost,
+                ..Default::default()
+            };
+            assert_eq!(params.memory_len(), expected, "m = {m_cost}, p = {p_cost}");
+        }
+    }
+}
@@ -0,0 +1,825 @@
+//! # [BLAKE2]
+//!
+//! BLAKE2 is a family of hash functions standardized in [RFC 7693]. BLAKE2b is
+//! optimized for 64-bit platforms and produces digests of up to 64 bytes,
+//! while BLAKE2s is optimized for smaller platforms and produces digests of
+//! up to 32 bytes.
+//!
+//! Both of them are configured through a parameter block, which holds the
+//! digest length, an optional key that turns them into a MAC, a salt, a
+//! personalization string and the parameters of tree hashing mode. A
+//! different digest length results in a completely different digest, so the
+//! digest length is a parameter of the types rather than a truncation.
+//! [`Blake2bVar`] and [`Blake2sVar`] take it at runtime instead.
+//!
+//! [BLAKE2]: https://www.blake2.net
+//! [RFC 7693]: https://www.rfc-editor.org/rfc/rfc7693
+
+use core::fmt::Display;
+use core::mem;
+use core::slice;
+
+use oxicrypt_core::blake2;
+
+use crate::digest::DigestMeta;
+use crate::digest::FinishInternal;
+use crate::digest::Reset;
+use crate::digest::Update;
+use crate::digest::Verify;
+use crate::traits::New;
+
+/// Parameters used to initialize the BLAKE2 contexts.
+///
+/// The salt and the personalization string are padded with zeros if they are
+/// shorter than the maximum length.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::blake2::*;
+/// # use oxicrypt::digest::*;
+/// let mut ctx = Blake2b256::with_params(&Params {
+///     key: b"key",
+///     salt: b"salt",
+///     personal: b"personal",
+///     ..Default::default()
+/// })
+/// .unwrap();
+/// ctx.update(b"abc");
+/// assert_eq!(
+///     hex::encode(ctx.finish()),
+///     "0d83ffda436ece44d4987ba5fb068a275124a87c6e8eead5c207844b87552fc2"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Params<'a>
+{
+    /// Key, at most 64 bytes for BLAKE2b and 32 bytes for BLAKE2s.
+    pub key:      &'a [u8],
+    /// Salt, at most 16 bytes for BLAKE2b and 8 bytes for BLAKE2s.
+    pub salt:     &'a [u8],
+    /// Personalization string, at most 16 bytes for BLAKE2b and 8 bytes for
+    /// BLAKE2s.
+    pub personal: &'a [u8],
+    /// Tree hashing parameters.
+    pub tree:     Tree,
+}
+
+/// Tree hashing parameters of the parameter block.
+///
+/// The default value is the sequential mode, which is a tree with a single
+/// node. Every node of a tree is hashed with its own context and the digests
+/// of the children are the input of their parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tree
+{
+    /// Maximum number of children of a node, or 0 for unlimited.
+    pub fanout:      u8,
+    /// Maximum depth of the tree, or 255 for unlimited.
+    pub max_depth:   u8,
+    /// Maximum byte length of the leaves, or 0 for unlimited.
+    pub leaf_len:    u32,
+    /// Offset of the node in its level, starting from 0. At most 48 bits for
+    /// BLAKE2s.
+    pub node_offset: u64,
+    /// Depth of the node, starting from 0 for the leaves.
+    pub node_depth:  u8,
+    /// Digest length of the inner nodes, or 0 in sequential mode.
+    pub inner_len:   u8,
+    /// Whether the node is the last one in its level.
+    pub last_node:   bool,
+}
+
+impl Tree
+{
+    /// Parameters of the sequential mode.
+    pub const SEQUENTIAL: Self = Self {
+        fanout:      1,
+        max_depth:   1,
+        leaf_len:    0,
+        node_offset: 0,
+        node_depth:  0,
+        inner_len:   0,
+        last_node:   false,
+    };
+}
+
+impl Default for Tree
+{
+    fn default() -> Self { Self::SEQUENTIAL }
+}
+
+macro_rules! blake2 {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $(#[$var_meta:meta])*
+        $var:ident,
+        $word:ty,
+        $counter:ty,
+        $compress:path,
+        $iv:path,
+        $block_len:literal,
+        $max_len:literal,
+        $node_offset_len:literal
+    ) => {
+        $(#[$var_meta])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $var
+        {
+            state:      [$word; 8],
+            block:      [u8; $block_len],
+            index:      usize,
+            counter:    $counter,
+            initial:    [$word; 8],
+            key:        [u8; $block_len],
+            keyed:      bool,
+            last_node:  bool,
+            digest_len: usize,
+        }
+
+        impl $var
+        {
+            /// Create a new context with a digest length of `digest_len` bytes.
+            ///
+            /// Returns an [`Err`](`Result::Err`) when the digest length is out
+            /// of range.
+            pub fn new(digest_len: usize) -> Result<Self, ParamError>
+            {
+                Self::with_params(&Params::default(), digest_len)
+            }
+
+            /// Create a new context using the parameters, with a digest length
+            /// of `digest_len` bytes.
+            ///
+            /// Returns an [`Err`](`Result::Err`) when the digest length is out
+            /// of range or a parameter doesn't fit in the parameter block.
+            pub fn with_params(params: &Params, digest_len: usize) -> Result<Self, ParamError>
+            {
+                if !(1..=$max_len).contains(&digest_len) {
+                    return Err(ParamError::DigestLen {
+                        at_most: $max_len,
+                        got:     digest_len,
+                    });
+                }
+                check_len(params.key, $max_len).map_err(|got| ParamError::Key {
+                    at_most: $max_len,
+                    got,
+                })?;
+                check_len(params.salt, $max_len / 4).map_err(|got| ParamError::Salt {
+                    at_most: $max_len / 4,
+                    got,
+                })?;
+                check_len(params.personal, $max_len / 4).map_err(|got| {
+                    ParamError::Personal {
+                        at_most: $max_len / 4,
+                        got,
+                    }
+                })?;
+                if params.tree.inner_len as usize > $max_len {
+                    return Err(ParamError::InnerLen {
+                        at_most: $max_len,
+                        got:     params.tree.inner_len as usize,
+                    });
+                }
+                if params.tree.node_offset >> ($node_offset_len * 8 - 1) >> 1 != 0 {
+                    return Err(ParamError::NodeOffset {
+                        at_most: u64::MAX >> (64 - $node_offset_len * 8),
+                        got:     params.tree.node_offset,
+                    });
+                }
+
+                // The parameter block is as long as the state and is XORed with the
+                // initialization vector.
+                let mut block: [u8; $max_len] = [0; $max_len];
+                block[0] = digest_len as u8;
+                block[1] = params.key.len() as u8;
+                block[2] = params.tree.fanout;
+                block[3] = params.tree.max_depth;
+                block[4..8].copy_from_slice(&params.tree.leaf_len.to_le_bytes());
+                block[8..8 + $node_offset_len]
+                    .copy_from_slice(&params.tree.node_offset.to_le_bytes()[0..$node_offset_len]);
+                block[8 + $node_offset_len] = params.tree.node_depth;
+                block[9 + $node_offset_len] = params.tree.inner_len;
+                block[$max_len / 2..$max_len / 2 + params.salt.len()].copy_from_slice(params.salt);
+                block[$max_len * 3 / 4..$max_len * 3 / 4 + params.personal.len()]
+                    .copy_from_slice(params.personal);
+
+                let mut initial: [$word; 8] = $iv;
+                initial
+                    .iter_mut()
+                    .zip(block.chunks_exact(mem::size_of::<$word>()))
+                    .for_each(|(h, p)| *h ^= <$word>::from_le_bytes(p.try_into().unwrap()));
+
+                // A key is hashed as if it was a full block that comes before the data.
+                let mut key: [u8; $block_len] = [0; $block_len];
+                key[0..params.key.len()].copy_from_slice(params.key);
+
+                let mut ctx = Self {
+                    state: initial,
+                    block: [0; $block_len],
+                    index: 0,
+                    counter: 0,
+                    initial,
+                    key,
+                    keyed: !params.key.is_empty(),
+                    last_node: params.tree.last_node,
+                    digest_len,
+                };
+                ctx.reset();
+                Ok(ctx)
+            }
+
+            /// Digest length of the context in bytes.
+            #[inline(always)]
+            pub const fn digest_len(&self) -> usize { self.digest_len }
+
+            /// Compress a block that isn't the final block.
+            #[inline(always)]
+            fn compress(&mut self, block: *const u8)
+            {
+                self.counter += $block_len;
+                unsafe { $compress(self.state.as_mut_ptr(), block, self.counter, false, false) };
+            }
+        }
+
+        impl Reset for $var
+        {
+            /// Reset the context to the state right after it was created, keeping
+            /// the key and the rest of the parameters.
+            fn reset(&mut self)
+            {
+                self.state = self.initial;
+                self.counter = 0;
+                if self.keyed {
+                    self.block = self.key;
+                    self.index = $block_len;
+                } else {
+                    self.index = 0;
+                }
+            }
+        }
+
+        impl Update for $var
+        {
+            fn update(&mut self, mut data: &[u8])
+            {
+                // The final block has to be compressed with a flag set, so a full block
+                // is only compressed once there is more data after it.
+                if data.len() > $block_len - self.index {
+                    let len = $block_len - self.index;
+                    self.block[self.index..].copy_from_slice(&data[0..len]);
+                    data = &data[len..];
+                    self.compress(self.block.as_ptr());
+                    self.index = 0;
+
+                    // Compress full blocks straight from `data`.
+                    while data.len() > $block_len {
+                        self.compress(data.as_ptr());
+                        data = &data[$block_len..];
+                    }
+                }
+
+                self.block[self.index..self.index + data.len()].copy_from_slice(data);
+                self.index += data.len();
+            }
+        }
+
+        impl FinishInternal for $var
+        {
+            unsafe fn finish_internal(&mut self) -> &[u8]
+            {
+                self.counter += self.index as $counter;
+                self.block[self.index..].fill(0);
+                unsafe {
+                    $compress(
+                        self.state.as_mut_ptr(),
+                        self.block.as_ptr(),
+                        self.counter,
+                        true,
+                        self.last_node,
+                    )
+                };
+
+                self.state.iter_mut().for_each(|h| *h = h.to_le());
+                unsafe { slice::from_raw_parts(self.state.as_ptr().cast(), self.digest_len) }
+            }
+        }
+
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name<const DIGEST_LEN: usize>($var);
+
+        impl<const DIGEST_LEN: usize> $name<DIGEST_LEN>
+        {
+            const ASSERT_DIGEST_LEN: () = assert!(
+                DIGEST_LEN >= 1 && DIGEST_LEN <= $max_len,
+                "digest length is out of range"
+            );
+
+            /// Create a new context using the key.
+            ///
+            /// Returns an [`Err`](`Result::Err`) when the key is too long.
+            pub fn with_key(key: &[u8]) -> Result<Self, ParamError>
+            {
+                Self::with_params(&Params {
+                    key,
+                    ..Default::default()
+                })
+            }
+
+            /// Create a new context using the parameters.
+            ///
+            /// Returns an [`Err`](`Result::Err`) when a parameter doesn't fit
+            /// in the parameter block.
+            pub fn with_params(params: &Params) -> Result<Self, ParamError>
+            {
+                #[allow(clippy::let_unit_value)]
+                let () = Self::ASSERT_DIGEST_LEN;
+
+                $var::with_params(params, DIGEST_LEN).map(Self)
+            }
+        }
+
+        impl<const DIGEST_LEN: usize> DigestMeta for $name<DIGEST_LEN>
+        {
+            const BLOCK_LEN: usize = $block_len;
+            const DIGEST_LEN: usize = DIGEST_LEN;
+        }
+
+        impl<const DIGEST_LEN: usize> New for $name<DIGEST_LEN>
+        {
+            fn new() -> Self { Self::with_params(&Params::default()).unwrap() }
+        }
+
+        impl<const DIGEST_LEN: usize> Reset for $name<DIGEST_LEN>
+        {
+            /// Reset the context to the state right after it was created, keeping
+            /// the key and the rest of the parameters.
+            fn reset(&mut self) { self.0.reset(); }
+        }
+
+        impl<const DIGEST_LEN: usize> Update for $name<DIGEST_LEN>
+        {
+            fn update(&mut self, data: &[u8]) { self.0.update(data); }
+        }
+
+        impl<const DIGEST_LEN: usize> FinishInternal for $name<DIGEST_LEN>
+        {
+            unsafe fn finish_internal(&mut self) -> &[u8] { unsafe { self.0.finish_internal() } }
+        }
+
+        /// Only useful when a key is used, which makes BLAKE2 a MAC.
+        impl<const DIGEST_LEN: usize> Verify for $name<DIGEST_LEN> {}
+    };
+}
+
+blake2!(
+    /// BLAKE2b context with a digest length of `DIGEST_LEN` bytes, which must
+    /// be between 1 and 64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::blake2::*;
+    /// # use oxicrypt::digest::*;
+    /// let digest = Blake2b512::oneshot(b"abc");
+    /// assert_eq!(
+    ///     hex::encode(digest),
+    ///     "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+    ///      7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    /// );
+    /// ```
+    Blake2b,
+    /// BLAKE2b context with a digest length chosen at runtime, between 1 and
+    /// 64 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::blake2::*;
+    /// # use oxicrypt::digest::*;
+    /// let mut ctx = Blake2bVar::new(20).unwrap();
+    /// ctx.update(b"abc");
+    /// let mut digest = [0; 20];
+    /// ctx.finish_to_slice(&mut digest);
+    /// assert_eq!(
+    ///     hex::encode(digest),
+    ///     "384264f676f39536840523f284921cdc68b6846b"
+    /// );
+    /// ```
+    Blake2bVar,
+    u64,
+    u128,
+    blake2::blake2b,
+    blake2::BLAKE2B_IV,
+    128,
+    64,
+    8
+);
+
+blake2!(
+    /// BLAKE2s context with a digest length of `DIGEST_LEN` bytes, which must
+    /// be between 1 and 32.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::blake2::*;
+    /// # use oxicrypt::digest::*;
+    /// let digest = Blake2s256::oneshot(b"abc");
+    /// assert_eq!(
+    ///     hex::encode(digest),
+    ///     "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+    /// );
+    /// ```
+    Blake2s,
+    /// BLAKE2s context with a digest length chosen at runtime, between 1 and
+    /// 32 bytes.
+    Blake2sVar,
+    u32,
+    u64,
+    blake2::blake2s,
+    blake2::BLAKE2S_IV,
+    64,
+    32,
+    6
+);
+
+/// BLAKE2b-256
+pub type Blake2b256 = Blake2b<32>;
+/// BLAKE2b-384
+pub type Blake2b384 = Blake2b<48>;
+/// BLAKE2b-512
+pub type Blake2b512 = Blake2b<64>;
+/// BLAKE2s-128
+pub type Blake2s128 = Blake2s<16>;
+/// BLAKE2s-256
+pub type Blake2s256 = Blake2s<32>;
+
+/// Returns the length of `param` as an error if it is longer than `at_most`.
+fn check_len(param: &[u8], at_most: usize) -> Result<(), usize>
+{
+    if param.len() > at_most {
+        return Err(param.len());
+    }
+    Ok(())
+}
+
+/// Error returned when a parameter doesn't fit in the parameter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamError
+{
+    DigestLen
+    {
+        at_most: usize, got: usize
+    },
+    Key
+    {
+        at_most: usize, got: usize
+    },
+    Salt
+    {
+        at_most: usize, got: usize
+    },
+    Personal
+    {
+        at_most: usize, got: usize
+    },
+    InnerLen
+    {
+        at_most: usize, got: usize
+    },
+    NodeOffset
+    {
+        at_most: u64, got: u64
+    },
+}
+
+impl Display for ParamError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | ParamError::DigestLen { at_most, got } => write!(
+                f,
+                "Digest length is expected to be between 1 and {}, but got {} instead",
+                at_most, got
+            ),
+            | ParamError::Key { at_most, got } => write!(
+                f,
+                "Length of `key` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+            | ParamError::Salt { at_most, got } => write!(
+                f,
+                "Length of `salt` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+            | ParamError::Personal { at_most, got } => write!(
+                f,
+                "Length of `personal` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+            | ParamError::InnerLen { at_most, got } => write!(
+                f,
+                "`inner_len` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+            | ParamError::NodeOffset { at_most, got } => write!(
+                f,
+                "`node_offset` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for ParamError {}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::digest::Finish;
+    use crate::digest::FinishToSlice;
+    use crate::digest::Oneshot;
+
+    type Vectors = &'static [(usize, &'static str, &'static str)];
+
+    /// Checks the unkeyed and keyed digests of the KATs from the BLAKE2
+    /// reference repository, with the fixed and the runtime digest lengths.
+    macro_rules! check_kat {
+        ($name:ty, $var:ty, $digest_len:literal, $vectors:expr) => {{
+            let vectors: Vectors = $vectors;
+            let key: Vec<u8> = (0..$digest_len).collect();
+            for &(len, hash, keyed) in vectors {
+                let data: Vec<u8> = (0..len as u8).collect();
+                assert_eq!(hex::encode(<$name>::oneshot(&data)), hash, "len = {len}");
+
+                let mut ctx = <$name>::with_key(&key).unwrap();
+                ctx.update(&data);
+                assert_eq!(hex::encode(ctx.finish()), keyed, "len = {len}");
+
+                let mut ctx = <$var>::with_params(
+                    &Params {
+                        key: &key,
+                        ..Default::default()
+                    },
+                    $digest_len,
+                )
+                .unwrap();
+                ctx.update(&data);
+                let mut digest = [0; $digest_len];
+                ctx.finish_to_slice(&mut digest);
+                assert_eq!(hex::encode(digest), keyed, "len = {len}");
+            }
+        }};
+    }
+
+    #[test]
+    fn kat()
+    {
+        check_kat!(
+            Blake2b512,
+            Blake2bVar,
+            64,
+            &include!(env!("OXI_TEST_blake2b-kat.txt"))
+        );
+        check_kat!(
+            Blake2s256,
+            Blake2sVar,
+            32,
+            &include!(env!("OXI_TEST_blake2s-kat.txt"))
+        );
+    }
+
+    /// Updates that end on a block boundary must leave the block in the
+    /// context, since the last block is compressed with the final flag.
+    #[test]
+    fn block_boundaries()
+    {
+        let vectors: Vectors = &include!(env!("OXI_TEST_blake2b-kat.txt"));
+        let key: Vec<u8> = (0..64).collect();
+        for &(len, _, keyed) in vectors {
+            let data: Vec<u8> = (0..len as u8).collect();
+            for piece in [1, 64, 127, 128] {
+                let mut ctx = Blake2b512::with_key(&key).unwrap();
+                data.chunks(piece).for_each(|chunk| ctx.update(chunk));
+                ctx.update(b"");
+                assert_eq!(
+                    hex::encode(ctx.finish()),
+                    keyed,
+                    "len = {len}, piece = {piece}"
+                );
+            }
+        }
+
+        let vectors: Vectors = &include!(env!("OXI_TEST_blake2s-kat.txt"));
+        for &(len, hash, _) in vectors {
+            let data: Vec<u8> = (0..len as u8).collect();
+            for piece in [1, 32, 63, 64] {
+                let mut ctx = Blake2s256::new();
+                data.chunks(piece).for_each(|chunk| ctx.update(chunk));
+                assert_eq!(
+                    hex::encode(ctx.finish()),
+                    hash,
+                    "len = {len}, piece = {piece}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reset()
+    {
+        let vectors: Vectors = &include!(env!("OXI_TEST_blake2b-kat.txt"));
+        let key: Vec<u8> = (0..64).collect();
+        let mut ctx = Blake2b512::with_key(&key).unwrap();
+        for &(len, _, keyed) in [vectors[0], vectors[128], vectors[255], vectors[3]].iter() {
+            let data: Vec<u8> = (0..len as u8).collect();
+            ctx.update(&data);
+            assert_eq!(hex::encode(ctx.finalize_reset()), keyed, "len = {len}");
+        }
+
+        ctx.update(b"abc");
+        ctx.reset();
+        assert_eq!(hex::encode(ctx.finish()), vectors[0].2);
+    }
+
+    #[test]
+    fn tree()
+    {
+        let data: Vec<u8> = (0..200).collect();
+        let mut tree = Tree {
+            fanout:      2,
+            max_depth:   3,
+            leaf_len:    4096,
+            node_offset: 5,
+            node_depth:  1,
+            inner_len:   64,
+            last_node:   true,
+        };
+        for (last_node, expected) in [
+            (
+                true,
+                "ff3a9f26d77a0cfea1f4794e4dabce4cd80ac950115966849f90bd5fe23b9a7d\
+                 a4701f9a647392104d917ce815386284be2d61351d2db83d28f3c6907c4517c8",
+            ),
+            (
+                false,
+                "3e892fd5410cd2adc850be7e825454de6e764e9172059da58bffbf191b411a60\
+                 c3a38ab9b999a3b53d99371268e9d31b74ac2acafc59240a67f0554d3ac32a00",
+            ),
+        ] {
+            tree.last_node = last_node;
+            let mut ctx = Blake2b512::with_params(&Params {
+                tree,
+                ..Default::default()
+            })
+            .unwrap();
+            ctx.update(&data);
+            assert_eq!(hex::encode(ctx.finish()), expected);
+        }
+
+        // The largest values of every field.
+        let mut tree = Tree {
+            fanout:      0,
+            max_depth:   255,
+            leaf_len:    u32::MAX,
+            node_offset: (1 << 48) - 1,
+            node_depth:  7,
+            inner_len:   32,
+            last_node:   true,
+        };
+        for (last_node, expected) in [
+            (
+                true,
+                "35770fbda33309359eb0ffcb1ef7fa3820988bc8ad33996946d1cef979cb8e71",
+            ),
+            (
+                false,
+                "49b209081686c38be77737c66b23f0183ae06650b38d70f39e0b4e12f0444c6b",
+            ),
+        ] {
+            tree.last_node = last_node;
+            let mut ctx = Blake2s256::with_params(&Params {
+                key: b"key",
+                tree,
+                ..Default::default()
+            })
+            .unwrap();
+            ctx.update(&data);
+            assert_eq!(hex::encode(ctx.finish()), expected);
+        }
+    }
+
+    #[test]
+    fn params()
+    {
+        let long = [0; 65];
+        let err = |params: Params| Blake2b512::with_params(&params).err();
+        assert_eq!(
+            err(Params {
+                key: &long,
+                ..Default::default()
+            }),
+            Some(ParamError::Key {
+                at_most: 64,
+                got:     65,
+            })
+        );
+        assert_eq!(
+            err(Params {
+                salt: &long[0..17],
+                ..Default::default()
+            }),
+            Some(ParamError::Salt {
+                at_most: 16,
+                got:     17,
+            })
+        );
+        assert_eq!(
+            err(Params {
+                personal: &long[0..17],
+                ..Default::default()
+            }),
+            Some(ParamError::Personal {
+                at_most: 16,
+                got:     17,
+            })
+        );
+        assert_eq!(
+            err(Params {
+                tree: Tree {
+                    inner_len: 65,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            Some(ParamError::InnerLen {
+                at_most: 64,
+                got:     65,
+            })
+        );
+
+        let err = |params: Params| Blake2s256::with_params(&params).err();
+        assert_eq!(
+            err(Params {
+                key: &long[0..33],
+                salt: &long[0..8],
+                personal: &long[0..8],
+                ..Default::default()
+            }),
+            Some(ParamError::Key {
+                at_most: 32,
+                got:     33,
+            })
+        );
+        assert_eq!(
+            err(Params {
+                salt: &long[0..9],
+                ..Default::default()
+            }),
+            Some(ParamError::Salt {
+                at_most: 8,
+                got:     9,
+            })
+        );
+        assert_eq!(
+            err(Params {
+                tree: Tree {
+                    node_offset: 1 << 48,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            Some(ParamError::NodeOffset {
+                at_most: (1 << 48) - 1,
+                got:     1 << 48,
+            })
+        );
+
+        for digest_len in [0, 65] {
+            assert_eq!(
+                Blake2bVar::new(digest_len).err(),
+                Some(ParamError::DigestLen {
+                    at_most: 64,
+                    got:     digest_len,
+                })
+            );
+        }
+        assert_eq!(
+            Blake2sVar::new(33).err(),
+            Some(ParamError::DigestLen {
+                at_most: 32,
+                got:     33,
+            })
+        );
+        assert_eq!(Blake2sVar::new(32).unwrap().digest_len(), 32);
+    }
+}
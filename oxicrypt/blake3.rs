@@ -0,0 +1,751 @@
+//! # [BLAKE3]
+//!
+//! BLAKE3 is a hash function that splits the input into chunks of 1024 bytes
+//! and arranges them as the leaves of a binary tree. The chunks are hashed
+//! independently, so large inputs can be hashed in parallel using SIMD
+//! instructions and multiple threads. Besides hashing, it has a keyed hash
+//! mode that works as a MAC and a key derivation mode. The output can be
+//! extended to any length.
+//!
+//! [BLAKE3]: https://github.com/BLAKE3-team/BLAKE3-specs
+
+use core::cmp;
+
+use oxicrypt_core::blake3;
+use oxicrypt_core::blake3::BLOCK_LEN;
+use oxicrypt_core::blake3::CHUNK_LEN;
+
+use crate::digest::DigestMeta;
+use crate::digest::ExtendableOutput;
+use crate::digest::FinishInternal;
+use crate::digest::FinishToSlice;
+use crate::digest::Reset;
+use crate::digest::Update;
+use crate::digest::Verify;
+use crate::digest::XofReader;
+use crate::runtime::Feature;
+use crate::traits::New;
+
+/// Maximum depth of the tree, which is enough for 2^64 bytes of input. One
+/// more chaining value is kept on the stack before it is merged.
+const MAX_DEPTH: usize = 54;
+
+/// Node that hasn't been compressed yet.
+///
+/// The last node is the root, which is compressed with the `ROOT` flag to
+/// produce the output. Every other node is compressed into a chaining value.
+#[derive(Debug, Clone, Copy)]
+struct Output
+{
+    cv:        [u32; 8],
+    block:     [u8; BLOCK_LEN],
+    block_len: u32,
+    counter:   u64,
+    flags:     u32,
+}
+
+impl Output
+{
+    fn chaining_value(&self) -> [u32; 8]
+    {
+        let mut cv: [u32; 8] = self.cv;
+        unsafe {
+            blake3::blake3(
+                cv.as_mut_ptr(),
+                self.block.as_ptr(),
+                self.block_len,
+                self.counter,
+                self.flags,
+            )
+        };
+        cv
+    }
+
+    /// Write the output block at index `counter` of the root node to `out`.
+    fn root_block(&self, counter: u64, out: &mut [u8; BLOCK_LEN])
+    {
+        unsafe {
+            blake3::blake3_xof(
+                self.cv.as_ptr(),
+                self.block.as_ptr(),
+                self.block_len,
+                counter,
+                self.flags | blake3::ROOT,
+                out.as_mut_ptr(),
+            )
+        };
+    }
+}
+
+/// Parent node of the chaining values `left` and `right`.
+fn parent_output(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Output
+{
+    let mut block: [u8; BLOCK_LEN] = [0; BLOCK_LEN];
+    block
+        .chunks_exact_mut(4)
+        .zip(left.iter().chain(right.iter()))
+        .for_each(|(b, w)| b.copy_from_slice(&w.to_le_bytes()));
+    Output {
+        cv: *key,
+        block,
+        block_len: BLOCK_LEN as u32,
+        counter: 0,
+        flags: flags | blake3::PARENT,
+    }
+}
+
+/// Chunk that is being hashed.
+#[derive(Debug, Clone, Copy)]
+struct ChunkState
+{
+    cv:        [u32; 8],
+    counter:   u64,
+    block:     [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks:    usize,
+    flags:     u32,
+}
+
+impl ChunkState
+{
+    const fn new(key: &[u32; 8], counter: u64, flags: u32) -> Self
+    {
+        Self {
+            cv: *key,
+            counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks: 0,
+            flags,
+        }
+    }
+
+    const fn len(&self) -> usize { self.blocks * BLOCK_LEN + self.block_len }
+
+    const fn start_flag(&self) -> u32
+    {
+        if self.blocks == 0 {
+            blake3::CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8])
+    {
+        while !data.is_empty() {
+            // The last block of the chunk is compressed with a different flag, so a full
+            // block is only compressed once there is more data after it.
+            if self.block_len == BLOCK_LEN {
+                let flags: u32 = self.flags | self.start_flag();
+                unsafe {
+                    blake3::blake3(
+                        self.cv.as_mut_ptr(),
+                        self.block.as_ptr(),
+                        BLOCK_LEN as u32,
+                        self.counter,
+                        flags,
+                    )
+                };
+                self.blocks += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+
+            let len = cmp::min(BLOCK_LEN - self.block_len, data.len());
+            self.block[self.block_len..self.block_len + len].copy_from_slice(&data[0..len]);
+            self.block_len += len;
+            data = &data[len..];
+        }
+    }
+
+    fn output(&self) -> Output
+    {
+        Output {
+            cv:        self.cv,
+            block:     self.block,
+            block_len: self.block_len as u32,
+            counter:   self.counter,
+            flags:     self.flags | self.start_flag() | blake3::CHUNK_END,
+        }
+    }
+}
+
+/// Computes the chaining values of the whole chunks in `data` in order, the
+/// first of which is the chunk at index `counter`.
+///
+/// The chunks are hashed in parallel lanes when the CPU supports it.
+fn chunk_cvs(
+    mut data: &[u8],
+    mut counter: u64,
+    key: &[u32; 8],
+    flags: u32,
+    mut f: impl FnMut([u32; 8]),
+)
+{
+    if Feature::Avx2.is_available() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        unsafe {
+            chunk_cvs_lanes::<8>(
+                &mut data,
+                &mut counter,
+                key,
+                flags,
+                &mut f,
+                blake3::blake3_x8,
+            )
+        };
+    } else if Feature::ArmNeon.is_available() {
+        #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+        unsafe {
+            chunk_cvs_lanes::<4>(
+                &mut data,
+                &mut counter,
+                key,
+                flags,
+                &mut f,
+                blake3::blake3_x4,
+            )
+        };
+    }
+
+    for chunk in data.chunks_exact(CHUNK_LEN) {
+        let mut state = ChunkState::new(key, counter, flags);
+        state.update(chunk);
+        f(state.output().chaining_value());
+        counter += 1;
+    }
+}
+
+/// Signature of [`blake3_x8`](`blake3::blake3_x8`) and
+/// [`blake3_x4`](`blake3::blake3_x4`).
+#[cfg(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+))]
+type CompressLanes<const LANES: usize> =
+    unsafe fn([*const u8; LANES], usize, *const u32, [u64; LANES], u32, [*mut u32; LANES]);
+
+/// Hashes `LANES` chunks at a time from the start of `data` using a
+/// compression function that works on `LANES` inputs, and advances `data` and
+/// `counter` past them.
+#[cfg(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+))]
+unsafe fn chunk_cvs_lanes<const LANES: usize>(
+    data: &mut &[u8],
+    counter: &mut u64,
+    key: &[u32; 8],
+    flags: u32,
+    f: &mut impl FnMut([u32; 8]),
+    compress: CompressLanes<LANES>,
+)
+{
+    while data.len() >= LANES * CHUNK_LEN {
+        let mut cvs: [[u32; 8]; LANES] = [[0; 8]; LANES];
+        compress(
+            core::array::from_fn(|i| data[i * CHUNK_LEN..].as_ptr()),
+            CHUNK_LEN / BLOCK_LEN,
+            key.as_ptr(),
+            core::array::from_fn(|i| *counter + i as u64),
+            flags,
+            core::array::from_fn(|i| cvs[i].as_mut_ptr()),
+        );
+        cvs.into_iter().for_each(&mut *f);
+        *data = &data[LANES * CHUNK_LEN..];
+        *counter += LANES as u64;
+    }
+}
+
+/// BLAKE3 context.
+///
+/// The digest is 32 bytes long, but any amount of output can be read using
+/// [`finish_xof`](`ExtendableOutput::finish_xof`).
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::blake3::*;
+/// # use oxicrypt::digest::*;
+/// let digest = Blake3::oneshot(b"abc");
+/// assert_eq!(
+///     hex::encode(digest),
+///     "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+/// );
+/// ```
+///
+/// The first 32 bytes of the extended output are the digest.
+/// ```
+/// # use oxicrypt::blake3::*;
+/// # use oxicrypt::digest::*;
+/// let mut ctx = Blake3::new();
+/// ctx.update(b"abc");
+/// let mut reader = ctx.finish_xof();
+/// let mut output = [0; 64];
+/// reader.read(&mut output[0..7]);
+/// reader.read(&mut output[7..]);
+/// assert_eq!(
+///     hex::encode(output),
+///     "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85\
+///      1fb250ae7393f5d02813b65d521a0d492d9ba09cf7ce7f4cffd900f23374bf0b"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Blake3
+{
+    key:       [u32; 8],
+    flags:     u32,
+    chunk:     ChunkState,
+    stack:     [[u32; 8]; MAX_DEPTH + 1],
+    stack_len: usize,
+}
+
+impl Blake3
+{
+    #[inline(always)]
+    pub const fn new() -> Self { Self::with_key_words(blake3::IV, 0) }
+
+    const fn with_key_words(key: [u32; 8], flags: u32) -> Self
+    {
+        Self {
+            key,
+            flags,
+            chunk: ChunkState::new(&key, 0, flags),
+            stack: [[0; 8]; MAX_DEPTH + 1],
+            stack_len: 0,
+        }
+    }
+
+    /// Create a new context in the keyed hash mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::blake3::*;
+    /// # use oxicrypt::digest::*;
+    /// let mut ctx = Blake3::with_key(b"whats the Elephant doing here?!!");
+    /// ctx.update(b"abc");
+    /// assert_eq!(
+    ///     hex::encode(ctx.finish()),
+    ///     "548441595e17bdf8b7f1715b2c3b5f0efec70672394e7930c0b06f1ef91bacbc"
+    /// );
+    /// ```
+    pub fn with_key(key: &[u8; 32]) -> Self
+    {
+        let mut words: [u32; 8] = [0; 8];
+        words
+            .iter_mut()
+            .zip(key.chunks_exact(4))
+            .for_each(|(w, b)| *w = u32::from_le_bytes(b.try_into().unwrap()));
+        Self::with_key_words(words, blake3::KEYED_HASH)
+    }
+
+    /// Create a new context in the key derivation mode.
+    ///
+    /// The context string should be hardcoded, globally unique and application
+    /// specific. The key material is then fed to the context using
+    /// [`update`](`Update::update`).
+    pub fn with_context(context: &str) -> Self
+    {
+        let mut ctx = Self::with_key_words(blake3::IV, blake3::DERIVE_KEY_CONTEXT);
+        ctx.update(context.as_bytes());
+        let mut key: [u8; 32] = [0; 32];
+        ctx.finish_to_slice(&mut key);
+        let mut ctx = Self::with_key(&key);
+        ctx.flags = blake3::DERIVE_KEY_MATERIAL;
+        ctx.chunk.flags = blake3::DERIVE_KEY_MATERIAL;
+        ctx
+    }
+
+    /// Oneshot key derivation function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::blake3::*;
+    /// let key = Blake3::derive_key("example.com 2023-01-01 session keys", b"key material");
+    /// assert_eq!(
+    ///     hex::encode(key),
+    ///     "849d38a265b5b9e2619d7adfe7208c2624d08567a1ec8dff42f2b8020fb73f07"
+    /// );
+    /// ```
+    pub fn derive_key(context: &str, material: &[u8]) -> [u8; 32]
+    {
+        let mut ctx = Self::with_context(context);
+        ctx.update(material);
+        let mut key: [u8; 32] = [0; 32];
+        ctx.finish_to_slice(&mut key);
+        key
+    }
+
+    /// Push the chaining value of the subtree that starts at the chunk with
+    /// the index `counter`.
+    fn push_cv(&mut self, cv: [u32; 8], counter: u64)
+    {
+        self.merge_stack(counter);
+        self.stack[self.stack_len] = cv;
+        self.stack_len += 1;
+    }
+
+    /// Merge the chaining values on the stack, so that there is one for every
+    /// complete subtree of the first `chunks` chunks.
+    ///
+    /// Merging is delayed until there is more data after the chaining values,
+    /// since the last of them might belong to the root node.
+    fn merge_stack(&mut self, chunks: u64)
+    {
+        while self.stack_len > chunks.count_ones() as usize {
+            let right: [u32; 8] = self.stack[self.stack_len - 1];
+            let left: [u32; 8] = self.stack[self.stack_len - 2];
+            self.stack[self.stack_len - 2] =
+                parent_output(&left, &right, &self.key, self.flags).chaining_value();
+            self.stack_len -= 1;
+        }
+    }
+
+    /// Push the chaining value of the current chunk and start the next one.
+    fn finish_chunk(&mut self)
+    {
+        let cv: [u32; 8] = self.chunk.output().chaining_value();
+        let counter: u64 = self.chunk.counter;
+        self.push_cv(cv, counter);
+        self.chunk = ChunkState::new(&self.key, counter + 1, self.flags);
+    }
+
+    fn update_(&mut self, mut data: &[u8])
+    {
+        while !data.is_empty() {
+            // The last chunk is compressed differently, so a full chunk is only
+            // finished once there is more data after it.
+            if self.chunk.len() == CHUNK_LEN {
+                self.finish_chunk();
+            }
+
+            // Hash whole chunks straight from `data`, keeping at least a byte for the
+            // current chunk.
+            if self.chunk.len() == 0 && data.len() > CHUNK_LEN {
+                let len = (data.len() - 1) / CHUNK_LEN * CHUNK_LEN;
+                let (key, flags) = (self.key, self.flags);
+                let mut counter: u64 = self.chunk.counter;
+                chunk_cvs(&data[0..len], counter, &key, flags, |cv| {
+                    self.push_cv(cv, counter);
+                    counter += 1;
+                });
+                self.chunk = ChunkState::new(&key, counter, flags);
+                data = &data[len..];
+            }
+
+            // The current chunk might be the last one now, so the chaining values before
+            // it have to be merged.
+            self.merge_stack(self.chunk.counter);
+            let len = cmp::min(CHUNK_LEN - self.chunk.len(), data.len());
+            self.chunk.update(&data[0..len]);
+            data = &data[len..];
+        }
+    }
+
+    /// Root node of the tree.
+    fn output(&self) -> Output
+    {
+        // The current chunk always has some data in it once a chaining value is
+        // pushed to the stack, so it is the rightmost leaf of the tree and the stack
+        // only holds complete subtrees.
+        self.stack[0..self.stack_len]
+            .iter()
+            .rev()
+            .fold(self.chunk.output(), |output, cv| {
+                parent_output(cv, &output.chaining_value(), &self.key, self.flags)
+            })
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl Blake3
+{
+    /// Update the context using multiple threads.
+    ///
+    /// The input is split into subtrees that are hashed on separate threads,
+    /// up to the available parallelism. Spawning the threads has an overhead,
+    /// so this is only faster than [`update`](`Update::update`) for inputs
+    /// of at least a few hundred kilobytes. The result is the same either way.
+    pub fn update_parallel(&mut self, data: &[u8])
+    {
+        let threads: usize = std::thread::available_parallelism().map_or(1, |n| n.get());
+        self.update_threads(data, threads);
+    }
+
+    /// Same as [`update_parallel`](`Self::update_parallel`), with up to
+    /// `threads` threads.
+    fn update_threads(&mut self, mut data: &[u8], threads: usize)
+    {
+        // Fill the current chunk first, so that the subtrees start at a chunk
+        // boundary.
+        if self.chunk.len() != 0 {
+            let len = cmp::min(CHUNK_LEN - self.chunk.len(), data.len());
+            self.update_(&data[0..len]);
+            data = &data[len..];
+        }
+
+        while data.len() > 2 * CHUNK_LEN {
+            if self.chunk.len() == CHUNK_LEN {
+                self.finish_chunk();
+            }
+
+            // The largest subtree that keeps at least a byte for the last chunk. A
+            // subtree has to start at a multiple of its size.
+            let counter: u64 = self.chunk.counter;
+            let mut chunks: u64 = 1 << ((data.len() - 1) / CHUNK_LEN).ilog2();
+            if counter != 0 {
+                chunks = cmp::min(chunks, 1 << counter.trailing_zeros());
+            }
+
+            let len = chunks as usize * CHUNK_LEN;
+            let cv: [u32; 8] = hash_subtree(&data[0..len], counter, &self.key, self.flags, threads);
+            self.push_cv(cv, counter);
+            self.chunk = ChunkState::new(&self.key, counter + chunks, self.flags);
+            data = &data[len..];
+        }
+
+        self.update_(data);
+    }
+}
+
+/// Computes the chaining value of a subtree that isn't the root, which must
+/// be made up of a power of two chunks.
+///
+/// The two halves of the subtree are hashed on separate threads until there
+/// are no more threads left or the halves get too small.
+#[cfg(any(feature = "std", doc))]
+fn hash_subtree(data: &[u8], counter: u64, key: &[u32; 8], flags: u32, threads: usize) -> [u32; 8]
+{
+    /// Subtrees smaller than this are not worth a thread.
+    const MIN_THREAD_CHUNKS: usize = 64;
+
+    let chunks: usize = data.len() / CHUNK_LEN;
+    if threads <= 1 || chunks <= MIN_THREAD_CHUNKS {
+        let mut stack: [[u32; 8]; MAX_DEPTH + 1] = [[0; 8]; MAX_DEPTH + 1];
+        let mut stack_len: usize = 0;
+        let mut hashed: u64 = 0;
+        chunk_cvs(data, counter, key, flags, |cv| {
+            stack[stack_len] = cv;
+            stack_len += 1;
+            hashed += 1;
+
+            // Merge the subtrees that are complete. None of them is the root, so they
+            // can be merged right away.
+            let mut complete: u64 = hashed;
+            while complete & 1 == 0 {
+                stack[stack_len - 2] =
+                    parent_output(&stack[stack_len - 2], &stack[stack_len - 1], key, flags)
+                        .chaining_value();
+                stack_len -= 1;
+                complete >>= 1;
+            }
+        });
+        return stack[0];
+    }
+
+    let (left, right) = data.split_at(data.len() / 2);
+    let (left, right) = std::thread::scope(|s| {
+        let left = s.spawn(|| hash_subtree(left, counter, key, flags, threads / 2));
+        let right = hash_subtree(
+            right,
+            counter + chunks as u64 / 2,
+            key,
+            flags,
+            threads - threads / 2,
+        );
+        (left.join().unwrap(), right)
+    });
+    parent_output(&left, &right, key, flags).chaining_value()
+}
+
+impl const DigestMeta for Blake3
+{
+    const BLOCK_LEN: usize = BLOCK_LEN;
+    const DIGEST_LEN: usize = 32;
+}
+
+impl const New for Blake3
+{
+    fn new() -> Self { Self::new() }
+}
+
+impl Reset for Blake3
+{
+    /// Reset the context to its original state, keeping the key.
+    fn reset(&mut self)
+    {
+        self.chunk = ChunkState::new(&self.key, 0, self.flags);
+        self.stack_len = 0;
+    }
+}
+
+impl Update for Blake3
+{
+    fn update(&mut self, data: &[u8]) { self.update_(data); }
+}
+
+impl FinishInternal for Blake3
+{
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        // The block of the current chunk is no longer needed, so the output is
+        // written there.
+        let output: Output = self.output();
+        output.root_block(0, &mut self.chunk.block);
+        &self.chunk.block[0..32]
+    }
+}
+
+/// Only useful in the keyed hash mode, where BLAKE3 is a MAC.
+impl Verify for Blake3 {}
+
+impl ExtendableOutput for Blake3
+{
+    type Reader = Blake3Reader;
+
+    fn finish_xof(self) -> Self::Reader
+    {
+        Blake3Reader {
+            output:  self.output(),
+            counter: 0,
+            block:   [0; BLOCK_LEN],
+            index:   BLOCK_LEN,
+        }
+    }
+}
+
+/// Reader that produces the output of BLAKE3 one block at a time.
+#[derive(Debug, Clone, Copy)]
+pub struct Blake3Reader
+{
+    output:  Output,
+    counter: u64,
+    block:   [u8; BLOCK_LEN],
+    index:   usize,
+}
+
+impl XofReader for Blake3Reader
+{
+    fn read(&mut self, mut buf: &mut [u8])
+    {
+        while !buf.is_empty() {
+            if self.index == BLOCK_LEN {
+                self.output.root_block(self.counter, &mut self.block);
+                self.counter += 1;
+                self.index = 0;
+            }
+
+            let len = cmp::min(BLOCK_LEN - self.index, buf.len());
+            buf[0..len].copy_from_slice(&self.block[self.index..self.index + len]);
+            self.index += len;
+            buf = &mut buf[len..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::digest::Finish;
+    use crate::digest::Oneshot;
+
+    const KEY: &[u8; 32] = b"whats the Elvish word for friend";
+    const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+    fn input(len: usize) -> Vec<u8> { (0..len).map(|i| (i % 251) as u8).collect() }
+
+    fn check(ctx: Blake3, expected: &str)
+    {
+        assert_eq!(hex::encode(ctx.finish()), expected[0..64]);
+        let mut output = [0; 131];
+        ctx.finish_xof().read(&mut output);
+        assert_eq!(hex::encode(output), expected);
+    }
+
+    /// Test vectors with the inputs of `test_vectors.json` from the BLAKE3
+    /// repository, with 131 bytes of extended output.
+    #[test]
+    fn test_vectors()
+    {
+        let vectors: &[(usize, &str, &str, &str)] = &include!(env!("OXI_TEST_blake3.txt"));
+        for &(len, hash, keyed_hash, derive_key) in vectors {
+            let data = input(len);
+
+            let mut ctx = Blake3::new();
+            ctx.update(&data);
+            check(ctx, hash);
+
+            let mut ctx = Blake3::with_key(KEY);
+            ctx.update(&data);
+            check(ctx, keyed_hash);
+
+            let mut ctx = Blake3::with_context(CONTEXT);
+            ctx.update(&data);
+            check(ctx, derive_key);
+            assert_eq!(
+                hex::encode(Blake3::derive_key(CONTEXT, &data)),
+                derive_key[0..64]
+            );
+        }
+    }
+
+    /// Feeding the input in pieces that don't line up with the chunks.
+    #[test]
+    fn update_pieces()
+    {
+        let data = input(31744);
+        for piece in [1, 63, 64, 65, 1023, 1024, 1025, 3000] {
+            let mut ctx = Blake3::new();
+            data.chunks(piece).for_each(|chunk| ctx.update(chunk));
+            assert_eq!(ctx.finish(), Blake3::oneshot(&data));
+        }
+    }
+
+    /// Hashing with threads after prefixes fed with [`Update::update`], which
+    /// leave the context in the middle of a block, a chunk or a subtree.
+    #[test]
+    #[cfg(feature = "std")]
+    fn update_threads()
+    {
+        let data = input(300_000);
+        for threads in [1, 2, 3, 4] {
+            for prefix in [0, 1, 64, 1024, 1025, 3 * 1024, 5000, 65 * 1024] {
+                let mut ctx = Blake3::with_key(KEY);
+                ctx.update(&data[0..prefix]);
+                ctx.update_threads(&data[prefix..], threads);
+
+                let mut expected = Blake3::with_key(KEY);
+                expected.update(&data);
+                let (mut output, mut expected_output) = ([0; 200], [0; 200]);
+                ctx.finish_xof().read(&mut output);
+                expected.finish_xof().read(&mut expected_output);
+                assert_eq!(
+                    output, expected_output,
+                    "{threads} threads, prefix {prefix}"
+                );
+            }
+        }
+
+        // Several calls, each of them merging subtrees pushed by the previous
+        // ones.
+        let mut ctx = Blake3::new();
+        data.chunks(70_000)
+            .for_each(|chunk| ctx.update_threads(chunk, 4));
+        assert_eq!(ctx.finish(), Blake3::oneshot(&data));
+
+        let mut ctx = Blake3::new();
+        ctx.update_parallel(&data);
+        assert_eq!(ctx.finish(), Blake3::oneshot(&data));
+    }
+}
@@ -0,0 +1,33 @@
+//! Constant-time operations.
+//!
+//! The functions in this module take the same time no matter where their inputs
+//! differ, so they can be used on secret data such as authentication tags. Only
+//! the lengths of the inputs are not hidden.
+
+/// Check whether `a` and `b` are equal, without exiting early on the first
+/// difference.
+///
+/// Slices of different lengths are never equal, and this is checked before
+/// comparing the contents.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::ct;
+/// assert!(ct::eq(b"tag", b"tag"));
+/// assert!(!ct::eq(b"tag", b"tab"));
+/// assert!(!ct::eq(b"tag", b"ta"));
+/// ```
+pub fn eq(a: &[u8], b: &[u8]) -> bool
+{
+    if a.len() != b.len() {
+        return false;
+    }
+    // Passing the accumulator through `black_box` on every iteration keeps the
+    // compiler from turning the loop into one that exits once it is nonzero.
+    let diff = a
+        .iter()
+        .zip(b)
+        .fold(0u8, |acc, (x, y)| core::hint::black_box(acc | (x ^ y)));
+    diff == 0
+}
@@ -5,10 +5,12 @@ use alloc::boxed::Box;
 use core::cmp;
 use core::mem::MaybeUninit;
 
+use crate::ct;
 use crate::traits::New;
 
 #[cfg(not(any(feature = "alloc", doc)))]
 pub trait Digest = DigestMeta
+    + New
     + Reset
     + Update
     + Finish
@@ -18,6 +20,7 @@ pub trait Digest = DigestMeta
     + OneshotToSlice;
 #[cfg(any(feature = "alloc", doc))]
 pub trait Digest = DigestMeta
+    + New
     + Reset
     + Update
     + Finish
@@ -47,52 +50,137 @@ pub trait Reset
     fn reset(&mut self);
 }
 
+/// Trait for MACs, whose contexts are created from a key.
+///
+/// Together with [`Reset`], it allows computing many MACs with the same
+/// context without knowing which algorithm it uses.
+pub trait SetKey
+where
+    Self: Sized,
+{
+    /// Create a new context using the key.
+    fn with_key(key: &[u8]) -> Self;
+
+    /// Set the key.
+    ///
+    /// This process will reset the inner state of the context as well as the
+    /// key.
+    fn set_key(&mut self, key: &[u8]);
+}
+
 /// Trait for feeding more data to the context.
 pub trait Update
 {
     /// Update the context.
+    ///
+    /// # Panics
+    ///
+    /// Contexts that also implement [`UpdateBits`] panic if they were already
+    /// updated with a partial byte.
     fn update(&mut self, data: &[u8]);
 }
 
+/// Trait for feeding data to the context one bit at a time.
+///
+/// Bits are taken from the most significant end of each byte, which is the
+/// order used by the SHS and the bit-oriented CAVP test vectors.
+pub trait UpdateBits
+{
+    /// Update the context with the first `nbits` bits of `data`.
+    ///
+    /// Only the last input may end in the middle of a byte. The unused low bits
+    /// of that byte are ignored. After that, the context can only be finished
+    /// or reset, and any further call to this function or to
+    /// [`update`](`Update::update`) panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is shorter than `nbits` bits, or if the context was
+    /// already updated with a partial byte.
+    fn update_bits(&mut self, data: &[u8], nbits: usize);
+}
+
 /// Trait for getting the digest value.
+///
+/// None of these functions can leave the context in a finished state that
+/// could be updated by accident. [`finish`](`Finish::finish`) consumes the
+/// context, while [`finalize_reset`](`Finish::finalize_reset`) and
+/// [`finalize_clone`](`Finish::finalize_clone`) leave it ready to be used
+/// again.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let mut ctx = Sha256::new();
+/// ctx.update(b"a");
+/// let intermediate = ctx.finalize_clone();
+/// ctx.update(b"bc");
+///
+/// assert_eq!(intermediate, Sha256::oneshot(b"a"));
+/// assert_eq!(ctx.finalize_reset(), Sha256::oneshot(b"abc"));
+/// assert_eq!(ctx.finish(), Sha256::oneshot(b""));
+/// ```
 pub trait Finish
 where
-    Self: DigestMeta,
+    Self: DigestMeta + Sized,
 {
-    /// Calculate the digest and return it.
-    ///
-    /// Calling this function will change the internal state, so any attempt to
-    /// call [`update`](`Update::update`) or any other finish function will
-    /// result in undefined behaviour. If you would like to keep updating
-    /// the existing data, you may want to copy the object before using this
-    /// function.
-    fn finish(&mut self) -> [u8; Self::DIGEST_LEN];
+    /// Calculate the digest and return it, consuming the context.
+    fn finish(self) -> [u8; Self::DIGEST_LEN];
+
+    /// Calculate the digest and return it, then reset the context to its
+    /// original state.
+    fn finalize_reset(&mut self) -> [u8; Self::DIGEST_LEN]
+    where
+        Self: Reset;
+
+    /// Calculate the digest of the data fed so far and return it, without
+    /// changing the context.
+    fn finalize_clone(&self) -> [u8; Self::DIGEST_LEN]
+    where
+        Self: Clone;
 }
 
 impl<T> Finish for T
 where
-    T: DigestMeta + FinishToSlice,
+    T: DigestMeta + FinishInternal,
 {
-    fn finish(&mut self) -> [u8; Self::DIGEST_LEN]
+    fn finish(mut self) -> [u8; Self::DIGEST_LEN]
+    {
+        let mut digest: MaybeUninit<[u8; Self::DIGEST_LEN]> = MaybeUninit::uninit();
+        // The context is consumed, so it can't be used after being finished.
+        unsafe { digest.assume_init_mut() }.copy_from_slice(unsafe { self.finish_internal() });
+        unsafe { digest.assume_init() }
+    }
+
+    fn finalize_reset(&mut self) -> [u8; Self::DIGEST_LEN]
+    where
+        Self: Reset,
     {
         let mut digest: MaybeUninit<[u8; Self::DIGEST_LEN]> = MaybeUninit::uninit();
-        self.finish_to_slice(unsafe { digest.assume_init_mut() });
+        unsafe { digest.assume_init_mut() }.copy_from_slice(unsafe { self.finish_internal() });
+        self.reset();
         unsafe { digest.assume_init() }
     }
+
+    fn finalize_clone(&self) -> [u8; Self::DIGEST_LEN]
+    where
+        Self: Clone,
+    {
+        self.clone().finish()
+    }
 }
 
 /// Trait for getting the digest value in a [`Box`].
 #[cfg(any(feature = "alloc", doc))]
 pub trait FinishBoxed
+where
+    Self: Sized,
 {
-    /// Calculate the digest and return it in a [`Box`].
-    ///
-    /// Calling this function will change the internal state, so any attempt to
-    /// call [`update`](`Update::update`) or any other finish function will
-    /// result in undefined behaviour. If you would like to keep updating
-    /// the existing data, you may want to copy the object before using this
-    /// function.
-    fn finish_boxed(&mut self) -> Box<[u8]>;
+    /// Calculate the digest and return it in a [`Box`], consuming the
+    /// context.
+    fn finish_boxed(self) -> Box<[u8]>;
 }
 
 #[cfg(any(feature = "alloc", doc))]
@@ -100,9 +188,9 @@ impl<T> FinishBoxed for T
 where
     T: FinishInternal,
 {
-    fn finish_boxed(&mut self) -> Box<[u8]>
+    fn finish_boxed(mut self) -> Box<[u8]>
     {
-        let digest = self.finish_internal();
+        let digest = unsafe { self.finish_internal() };
         let mut digest_buffer: Box<[u8]> =
             unsafe { Box::new_uninit_slice(digest.len()).assume_init() };
         digest_buffer.copy_from_slice(digest);
@@ -112,37 +200,208 @@ where
 
 /// Trait for writing the digest value to a buffer.
 pub trait FinishToSlice
+where
+    Self: Sized,
 {
-    /// Calculate the digest and write it to the given buffer.
+    /// Calculate the digest and write it to the given buffer, consuming the
+    /// context.
     ///
     /// Provided buffer may have a length smaller than the digest length, in
     /// which case only the first *N* bytes are written where *N* is the
     /// length of the buffer.
-    fn finish_to_slice(&mut self, buf: &mut [u8]);
+    fn finish_to_slice(self, buf: &mut [u8]);
 }
 
 impl<T> FinishToSlice for T
 where
     T: FinishInternal,
 {
-    fn finish_to_slice(&mut self, buf: &mut [u8])
+    fn finish_to_slice(mut self, buf: &mut [u8])
     {
-        let digest = self.finish_internal();
+        let digest = unsafe { self.finish_internal() };
         let n = cmp::min(buf.len(), digest.len());
         buf[0..n].copy_from_slice(&digest[0..n]);
     }
 }
 
 /// Trait for getting the digest value which is stored in the context itself.
+///
+/// This is the building block of the other finish traits. Prefer those, since
+/// they can't be misused.
 pub trait FinishInternal
 {
     /// Calculate the digest and return a reference to it.
     ///
     /// Returned value references the context itself and will always have a
     /// length that is equal to the digest length used by the algorithm.
-    fn finish_internal(&mut self) -> &[u8];
+    ///
+    /// # Safety
+    ///
+    /// This function leaves the context in a finished state. The caller must
+    /// not update or finish the context again, unless it is reset first.
+    unsafe fn finish_internal(&mut self) -> &[u8];
+}
+
+/// Trait for checking the tag computed by a MAC.
+///
+/// The tag is compared in constant time with [`ct::eq`]. Comparing the output
+/// of [`finish`](`Finish::finish`) with `==` instead leaks how many leading
+/// bytes of the tag are correct, which lets an attacker forge a tag one byte at
+/// a time.
+///
+/// Both functions reset the context after computing the tag, whether it
+/// matches or not, so the context is ready to check another message with the
+/// same key.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sp800_185::*;
+/// let mut ctx = Kmac128::with_key(b"key");
+/// ctx.update(b"message");
+/// let tag = ctx.finish();
+///
+/// let mut ctx = Kmac128::with_key(b"key");
+/// ctx.update(b"message");
+/// assert!(ctx.verify(&tag).is_ok());
+///
+/// ctx.update(b"massage");
+/// assert_eq!(ctx.verify(&tag), Err(MacError));
+/// ```
+pub trait Verify
+where
+    Self: FinishInternal + Reset,
+{
+    /// Calculate the tag and check that it is equal to `tag`, then reset the
+    /// context.
+    fn verify(&mut self, tag: &[u8]) -> Result<(), MacError>
+    {
+        // The context is reset right after being finished.
+        let equal = ct::eq(unsafe { self.finish_internal() }, tag);
+        self.reset();
+        if equal { Ok(()) } else { Err(MacError) }
+    }
+
+    /// Calculate the tag and check that it starts with `tag`, then reset the
+    /// context.
+    ///
+    /// As recommended by RFC 2104, `tag` must be at least half as long as the
+    /// full tag and at least 10 bytes long, or as long as the full tag if it is
+    /// shorter than that. Shorter tags are rejected, since they are much easier
+    /// to guess.
+    fn verify_truncated(&mut self, tag: &[u8]) -> Result<(), MacError>
+    {
+        let mac = unsafe { self.finish_internal() };
+        let min_len = cmp::min(cmp::max(10, mac.len() / 2), mac.len());
+        let equal =
+            tag.len() >= min_len && tag.len() <= mac.len() && ct::eq(&mac[0..tag.len()], tag);
+        self.reset();
+        if equal { Ok(()) } else { Err(MacError) }
+    }
+}
+
+/// Error type for when the tag of a MAC does not match, or a truncated tag does
+/// not have an acceptable length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MacError;
+
+impl core::fmt::Display for MacError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        write!(f, "MAC tag did not match")
+    }
 }
 
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for MacError {}
+
+/// Trait for exporting the context so that hashing can be resumed later,
+/// possibly by another process.
+///
+/// The exported midstate starts with a format version and a description of the
+/// algorithm, both of which are checked by [`import`](`Midstate::import`), so a
+/// midstate can only be imported by the algorithm that exported it. Contexts
+/// that were updated with a partial byte can be exported as well.
+///
+/// It is implemented by the [Merkle–Damgård](`crate::merkle_damgard`) digests,
+/// including [MD4](`crate::legacy::Md4`), and by [`Hmac`](`crate::hmac::Hmac`)
+/// over them. [`Sha1Dc`](`crate::sha1dc::Sha1Dc`), BLAKE2, BLAKE3, SHA-3,
+/// SHAKE and the SP 800-185 functions, including KMAC, don't implement it.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let mut ctx = Sha256::new();
+/// ctx.update(b"first request, ");
+///
+/// let mut midstate = [0; Sha256::MIDSTATE_LEN];
+/// ctx.export(&mut midstate);
+///
+/// let mut ctx = Sha256::import(&midstate).unwrap();
+/// ctx.update(b"second request");
+///
+/// assert_eq!(
+///     ctx.finish(),
+///     Sha256::oneshot(b"first request, second request")
+/// );
+/// assert!(Sha512::import(&midstate).is_err());
+/// ```
+pub trait Midstate
+where
+    Self: Sized,
+{
+    /// Length of the exported midstate in bytes.
+    const MIDSTATE_LEN: usize;
+
+    /// Write the midstate to `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` is not equal to
+    /// [`MIDSTATE_LEN`](`Midstate::MIDSTATE_LEN`).
+    fn export(&self, buf: &mut [u8]);
+
+    /// Create a context from a midstate written by
+    /// [`export`](`Midstate::export`).
+    fn import(midstate: &[u8]) -> Result<Self, MidstateError>;
+}
+
+/// Error type for when a midstate cannot be imported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MidstateError
+{
+    /// The midstate does not have the expected length.
+    Len,
+    /// The midstate uses a format version that is not supported.
+    Version,
+    /// The midstate was exported by a different algorithm.
+    Algorithm,
+    /// The midstate describes a state the context can never be in.
+    Invalid,
+}
+
+impl core::fmt::Display for MidstateError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | Self::Len => write!(f, "Midstate does not have the expected length"),
+            | Self::Version => write!(f, "Midstate format version is not supported"),
+            | Self::Algorithm => write!(f, "Midstate was exported by a different algorithm"),
+            | Self::Invalid => write!(f, "Midstate is not valid"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for MidstateError {}
+
 pub trait Oneshot
 where
     Self: DigestMeta,
@@ -198,12 +457,47 @@ where
     }
 }
 
+/// Trait for digest algorithms with an extendable output.
+pub trait ExtendableOutput
+{
+    /// Reader used for getting the output.
+    type Reader: XofReader;
+
+    /// Finish feeding data to the context and return a reader that can produce
+    /// an output of any length.
+    fn finish_xof(self) -> Self::Reader;
+}
+
+/// Trait for reading the output of an extendable-output function.
+pub trait XofReader
+{
+    /// Fill the buffer with the next `buf.len()` bytes of the output.
+    fn read(&mut self, buf: &mut [u8]);
+}
+
+pub trait OneshotXof
+{
+    fn oneshot_xof(data: &[u8], buf: &mut [u8]);
+}
+
+impl<T> OneshotXof for T
+where
+    T: New + Update + ExtendableOutput,
+{
+    fn oneshot_xof(data: &[u8], buf: &mut [u8])
+    {
+        let mut ctx = T::new();
+        ctx.update(data);
+        ctx.finish_xof().read(buf);
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Output<T, const DIGEST_LEN: usize>
 where
     T: Digest,
 {
-    inner: T,
+    pub(crate) inner: T,
 }
 
 impl<T, const DIGEST_LEN: usize> Output<T, DIGEST_LEN>
@@ -242,9 +536,35 @@ where
     fn update(&mut self, data: &[u8]) { self.inner.update(data); }
 }
 
+impl<T, const DIGEST_LEN: usize> UpdateBits for Output<T, DIGEST_LEN>
+where
+    T: Digest + UpdateBits,
+{
+    fn update_bits(&mut self, data: &[u8], nbits: usize) { self.inner.update_bits(data, nbits); }
+}
+
+impl<T, const DIGEST_LEN: usize> Midstate for Output<T, DIGEST_LEN>
+where
+    T: Digest + Midstate,
+{
+    const MIDSTATE_LEN: usize = T::MIDSTATE_LEN;
+
+    fn export(&self, buf: &mut [u8]) { self.inner.export(buf); }
+
+    fn import(midstate: &[u8]) -> Result<Self, MidstateError>
+    {
+        Ok(Self {
+            inner: T::import(midstate)?,
+        })
+    }
+}
+
 impl<T, const DIGEST_LEN: usize> FinishInternal for Output<T, DIGEST_LEN>
 where
     T: Digest,
 {
-    fn finish_internal(&mut self) -> &[u8] { &self.inner.finish_internal()[0..DIGEST_LEN] }
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        unsafe { &self.inner.finish_internal()[0..DIGEST_LEN] }
+    }
 }
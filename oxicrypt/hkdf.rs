@@ -8,23 +8,32 @@
 //!
 //! # HKDF-Extract
 //!
-//! A pseudorandom key can be generated using HMAC. Because this step is
-//! trivial, it is not included as a seperate function.
+//! [`Hkdf::extract`] generates a pseudorandom key from the salt and the input
+//! keying material. It returns a [`Prk`], which can only be used to create an
+//! [`Hkdf`] context for HKDF-Expand.
 //!
 //! ```
-//! use oxicrypt::hmac::HmacSha256;
+//! use oxicrypt::hkdf::*;
 //!
 //! let salt = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
 //! let ikm = [11; 22];
-//! let prk = HmacSha256::oneshot(&salt, &ikm);
+//! let prk = HkdfSha256::extract(Some(&salt), &ikm);
+//!
+//! let mut okm = [0; 42];
+//! HkdfSha256::from_prk(&prk)
+//!     .expand(&[b"info"], &mut okm)
+//!     .unwrap();
 //! ```
+//!
+//! Both steps can be done at once with [`Hkdf::extract_and_expand`].
 
 use core::fmt::Debug;
 use core::fmt::Display;
-use core::mem::MaybeUninit;
+use core::marker::PhantomData;
 
 use crate::digest::Digest;
 use crate::hmac::Hmac;
+use crate::hmac::HmacKey;
 use crate::merkle_damgard;
 
 /// HKDF-Expand.
@@ -35,7 +44,7 @@ where
     [u8; D::BLOCK_LEN]:,
     [u8; D::DIGEST_LEN]:,
 {
-    hmac: Hmac<D>,
+    key: HmacKey<D>,
 }
 
 /// HKDF-MD5
@@ -55,12 +64,90 @@ pub type HkdfSha512_224 = Hkdf<merkle_damgard::Sha512_224>;
 /// HKDF-SHA-512/256
 pub type HkdfSha512_256 = Hkdf<merkle_damgard::Sha512_256>;
 
+/// Pseudorandom key returned by HKDF-Extract.
+///
+/// It is derived from the input keying material, so it has to be kept as
+/// secret as the input keying material itself. Its [`Debug`] output leaves
+/// the key out.
+#[derive(Clone, Copy)]
+pub struct Prk<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    prk:     [u8; D::DIGEST_LEN],
+    _digest: PhantomData<D>,
+}
+
+impl<D> Prk<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    /// Use a pseudorandom key that was derived elsewhere.
+    pub const fn from_bytes(prk: [u8; D::DIGEST_LEN]) -> Self
+    {
+        Self {
+            prk,
+            _digest: PhantomData,
+        }
+    }
+
+    /// Bytes of the pseudorandom key.
+    pub const fn as_bytes(&self) -> &[u8; D::DIGEST_LEN] { &self.prk }
+}
+
+impl<D> Debug for Prk<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        f.debug_struct("Prk").finish_non_exhaustive()
+    }
+}
+
 impl<D> Hkdf<D>
 where
     D: Digest + Copy,
     [u8; D::BLOCK_LEN]:,
     [u8; D::DIGEST_LEN]:,
 {
+    /// HKDF-Extract.
+    ///
+    /// The salt is used as the HMAC key and the input keying material as the
+    /// message. If `salt` is `None`, a string of zeros as long as the digest is
+    /// used instead, as specified by RFC 5869.
+    pub fn extract(salt: Option<&[u8]>, ikm: &[u8]) -> Prk<D>
+    {
+        let zeros = [0; D::DIGEST_LEN];
+        let salt = salt.unwrap_or(&zeros);
+        Prk::from_bytes(Hmac::<D>::oneshot(ikm, salt))
+    }
+
+    /// HKDF-Extract followed by HKDF-Expand.
+    pub fn extract_and_expand(
+        salt: Option<&[u8]>,
+        ikm: &[u8],
+        info: &[&[u8]],
+        okm: &mut [u8],
+    ) -> Result<(), LenError>
+    {
+        Self::from_prk(&Self::extract(salt, ikm)).expand(info, okm)
+    }
+
+    /// Create a context for HKDF-Expand from the output of HKDF-Extract.
+    pub fn from_prk(prk: &Prk<D>) -> Self
+    {
+        Self {
+            key: HmacKey::new(&prk.prk),
+        }
+    }
+
     pub fn with_prk(prk: &[u8]) -> Result<Self, LenError>
     {
         if prk.len() < D::DIGEST_LEN {
@@ -69,18 +156,14 @@ where
                 got:      prk.len(),
             });
         }
-        let mut ctx: MaybeUninit<Self> = MaybeUninit::uninit();
-        unsafe {
-            ctx.assume_init_mut().set_prk_unchecked(prk);
-        }
-        Ok(unsafe { ctx.assume_init() })
+        Ok(unsafe { Self::with_prk_unchecked(prk) })
     }
 
     pub unsafe fn with_prk_unchecked(prk: &[u8]) -> Self
     {
-        let mut ctx: MaybeUninit<Self> = MaybeUninit::uninit();
-        ctx.assume_init_mut().set_prk_unchecked(prk);
-        ctx.assume_init()
+        Self {
+            key: HmacKey::new(prk),
+        }
     }
 
     pub fn set_prk(&mut self, prk: &[u8]) -> Result<(), LenError>
@@ -97,7 +180,7 @@ where
         Ok(())
     }
 
-    pub unsafe fn set_prk_unchecked(&mut self, prk: &[u8]) { self.hmac.set_key(prk); }
+    pub unsafe fn set_prk_unchecked(&mut self, prk: &[u8]) { self.key = HmacKey::new(prk); }
 
     pub fn expand(&self, info: &[&[u8]], okm: &mut [u8]) -> Result<(), LenError>
     {
@@ -118,7 +201,7 @@ where
         let mut hmac: Hmac<D>;
         for chunk in okm.chunks_mut(D::DIGEST_LEN) {
             i += 1;
-            hmac = self.hmac;
+            hmac = Hmac::from_key(&self.key);
             hmac.update(t);
             info.iter().for_each(|i| hmac.update(i));
             hmac.update(&[i]);
@@ -176,3 +259,121 @@ impl Display for LenError
 #[cfg(any(feature = "std", doc))]
 #[doc(cfg(feature = "std"))]
 impl std::error::Error for LenError {}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::format;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    /// Test vectors from RFC 5869, appendix A.
+    macro_rules! check {
+        ($hkdf:ty, $salt:expr, $ikm:expr, $info:expr, $prk:literal, $okm:literal) => {{
+            let salt: Option<Vec<u8>> = $salt;
+            let ikm: Vec<u8> = $ikm;
+            let info: Vec<u8> = $info;
+            let expected = hex::decode($okm).unwrap();
+
+            let prk = <$hkdf>::extract(salt.as_deref(), &ikm);
+            assert_eq!(hex::encode(prk.as_bytes()), $prk);
+
+            let mut okm = vec![0; expected.len()];
+            <$hkdf>::from_prk(&prk).expand(&[&info], &mut okm).unwrap();
+            assert_eq!(okm, expected);
+
+            let mut okm = vec![0; expected.len()];
+            <$hkdf>::extract_and_expand(salt.as_deref(), &ikm, &[&info], &mut okm).unwrap();
+            assert_eq!(okm, expected);
+
+            let mut okm = vec![0; expected.len()];
+            <$hkdf>::with_prk(prk.as_bytes())
+                .unwrap()
+                .expand(
+                    &[&info[..info.len() / 2], &info[info.len() / 2..]],
+                    &mut okm,
+                )
+                .unwrap();
+            assert_eq!(okm, expected);
+        }};
+    }
+
+    #[test]
+    fn rfc5869_sha256()
+    {
+        check!(
+            HkdfSha256,
+            Some((0x00..=0x0c).collect()),
+            vec![0x0b; 22],
+            (0xf0..=0xf9).collect(),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        check!(
+            HkdfSha256,
+            Some((0x60..=0xaf).collect()),
+            (0x00..=0x4f).collect(),
+            (0xb0..=0xff).collect(),
+            "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+            "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+             59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+             cc30c58179ec3e87c14c01d5c1f3434f1d87"
+        );
+        check!(
+            HkdfSha256,
+            Some(vec![]),
+            vec![0x0b; 22],
+            vec![],
+            "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+    }
+
+    #[test]
+    fn rfc5869_sha1()
+    {
+        check!(
+            HkdfSha1,
+            Some((0x00..=0x0c).collect()),
+            vec![0x0b; 11],
+            (0xf0..=0xf9).collect(),
+            "9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243",
+            "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896"
+        );
+        check!(
+            HkdfSha1,
+            Some((0x60..=0xaf).collect()),
+            (0x00..=0x4f).collect(),
+            (0xb0..=0xff).collect(),
+            "8adae09a2a307059478d309b26c4115a224cfaf6",
+            "0bd770a74d1160f7c9f12cd5912a06ebff6adcae899d92191fe4305673ba2ffe\
+             8fa3f1a4e5ad79f3f334b3b202b2173c486ea37ce3d397ed034c7f9dfeb15c5e\
+             927336d0441f4c4300e2cff0d0900b52d3b4"
+        );
+        check!(
+            HkdfSha1,
+            Some(vec![]),
+            vec![0x0b; 22],
+            vec![],
+            "da8c8a73c7fa77288ec6f5e7c297786aa0d32d01",
+            "0ac1af7002b3d761d1e55298da9d0506b9ae52057220a306e07b6b87e8df21d0ea00033de03984d34918"
+        );
+        check!(
+            HkdfSha1,
+            None,
+            vec![0x0c; 22],
+            vec![],
+            "2adccada18779e7c2077ad2eb19d3f3e731385dd",
+            "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48"
+        );
+    }
+
+    #[test]
+    fn prk_debug()
+    {
+        let prk = HkdfSha256::extract(None, &[0x0b; 22]);
+        assert_eq!(format!("{prk:?}"), "Prk { .. }");
+    }
+}
@@ -9,12 +9,16 @@
 #[cfg(any(feature = "alloc", doc))]
 use alloc::boxed::Box;
 use core::fmt::Debug;
-use core::mem::MaybeUninit;
 
 use crate::digest::Digest;
 use crate::digest::DigestMeta;
 use crate::digest::FinishInternal;
+use crate::digest::Midstate;
+use crate::digest::MidstateError;
+use crate::digest::Reset;
+use crate::digest::SetKey;
 use crate::digest::Update;
+use crate::digest::Verify;
 use crate::merkle_damgard;
 
 /// HMAC-X context.
@@ -25,8 +29,48 @@ where
     [u8; D::BLOCK_LEN]:,
     [u8; D::DIGEST_LEN]:,
 {
-    ikey: [u8; D::BLOCK_LEN],
-    ctx:  D,
+    ctx: D,
+    /// Inner and outer hash contexts after absorbing the key, used to reset
+    /// the context and to compute the outer hash.
+    key: HmacKey<D>,
+}
+
+/// HMAC-X key.
+///
+/// The key is stored as the inner and outer hash contexts after they absorbed
+/// the i_key_pad and o_key_pad blocks. Creating a context from it with
+/// [`Hmac::from_key`] is only a copy, so computing many HMACs with the same key
+/// saves two compressions per HMAC, plus the hashing of keys longer than the
+/// block.
+///
+/// Both contexts are derived from the key, so they have to be kept as secret as
+/// the key itself.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::hmac::*;
+/// # use oxicrypt::merkle_damgard::Sha256;
+/// let key = HmacKey::<Sha256>::new(b"key");
+///
+/// let mut ctx = Hmac::from_key(&key);
+/// ctx.update(b"The quick brown fox jumps over the lazy dog");
+/// assert_eq!(
+///     hex::encode(ctx.finish()),
+///     "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+/// );
+/// assert_eq!(key.oneshot(b"abc"), HmacSha256::oneshot(b"abc", b"key"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HmacKey<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    ictx: D,
+    octx: D,
 }
 
 /// HMAC-MD5
@@ -46,6 +90,90 @@ pub type HmacSha512_224 = Hmac<merkle_damgard::Sha512_224>;
 /// HMAC-SHA-512/256
 pub type HmacSha512_256 = Hmac<merkle_damgard::Sha512_256>;
 
+/// Implements the const functions used for computing HMACs at compile time,
+/// using the const functions of the hash context.
+macro_rules! impl_const_hmac {
+    ($hmac:ty: $digest:ty, $block_len:literal, $digest_len:literal) => {
+        impl $hmac
+        {
+            /// Same as [`with_key`](`Self::with_key`), but can be used in a
+            /// const context.
+            pub const fn const_with_key(key: &[u8]) -> Self
+            {
+                let mut pad: [u8; $block_len] = [0; $block_len];
+
+                // key shortening
+                if key.len() > $block_len {
+                    let digest = <$digest>::const_oneshot(key);
+                    let mut i = 0;
+                    while i < digest.len() {
+                        pad[i] = digest[i];
+                        i += 1;
+                    }
+                } else {
+                    let mut i = 0;
+                    while i < key.len() {
+                        pad[i] = key[i];
+                        i += 1;
+                    }
+                }
+
+                // xor with 0x36 to get i_key_pad
+                let mut i = 0;
+                while i < pad.len() {
+                    pad[i] ^= 0x36;
+                    i += 1;
+                }
+                let ctx = <$digest>::new().const_update(&pad);
+
+                // xor with 0x36 ^ 0x5c to get o_key_pad
+                let mut i = 0;
+                while i < pad.len() {
+                    pad[i] ^= 0x36 ^ 0x5c;
+                    i += 1;
+                }
+                let octx = <$digest>::new().const_update(&pad);
+
+                Self {
+                    ctx,
+                    key: HmacKey { ictx: ctx, octx },
+                }
+            }
+
+            /// Same as [`update`](`Update::update`), but can be used in a const
+            /// context.
+            pub const fn const_update(mut self, data: &[u8]) -> Self
+            {
+                self.ctx = self.ctx.const_update(data);
+                self
+            }
+
+            /// Same as [`finish`](`crate::digest::Finish::finish`), but can be
+            /// used in a const context.
+            pub const fn const_finish(self) -> [u8; $digest_len]
+            {
+                let i_digest = self.ctx.const_finish();
+                self.key.octx.const_update(&i_digest).const_finish()
+            }
+
+            /// Calculate the HMAC of `data` in a const context.
+            pub const fn const_oneshot(data: &[u8], key: &[u8]) -> [u8; $digest_len]
+            {
+                Self::const_with_key(key).const_update(data).const_finish()
+            }
+        }
+    };
+}
+
+impl_const_hmac!(HmacMd5: merkle_damgard::Md5, 64, 16);
+impl_const_hmac!(HmacSha1: merkle_damgard::Sha1, 64, 20);
+impl_const_hmac!(HmacSha224: merkle_damgard::Sha224, 64, 28);
+impl_const_hmac!(HmacSha256: merkle_damgard::Sha256, 64, 32);
+impl_const_hmac!(HmacSha384: merkle_damgard::Sha384, 128, 48);
+impl_const_hmac!(HmacSha512: merkle_damgard::Sha512, 128, 64);
+impl_const_hmac!(HmacSha512_224: merkle_damgard::Sha512_224, 128, 28);
+impl_const_hmac!(HmacSha512_256: merkle_damgard::Sha512_256, 128, 32);
+
 impl<D> DigestMeta for Hmac<D>
 where
     D: Digest + Copy,
@@ -63,56 +191,37 @@ where
     [u8; D::DIGEST_LEN]:,
 {
     /// Create a new context using the key.
-    pub fn with_key(key: &[u8]) -> Self
+    pub fn with_key(key: &[u8]) -> Self { Self::from_key(&HmacKey::new(key)) }
+
+    /// Create a new context using a precomputed key.
+    pub fn from_key(key: &HmacKey<D>) -> Self
     {
-        let mut ctx: MaybeUninit<Self> = MaybeUninit::uninit();
-        unsafe { ctx.assume_init_mut() }.set_key(key);
-        unsafe { ctx.assume_init() }
+        Self {
+            ctx: key.ictx,
+            key: *key,
+        }
     }
 
     /// Set the key.
     ///
     /// This process will reset the inner state of the hash context as well as
     /// the key.
-    pub fn set_key(&mut self, key: &[u8])
-    {
-        self.ikey = [0; D::BLOCK_LEN];
-        self.ctx.reset();
-
-        // key shortening
-        if key.len() > D::BLOCK_LEN {
-            self.ctx.update(key);
-            self.ctx.finish_to_slice(&mut self.ikey[0..D::DIGEST_LEN]);
-            self.ctx.reset();
-        } else {
-            self.ikey[0..key.len()].copy_from_slice(key);
-        }
-
-        // xor with 0x36 to get i_key_pad
-        self.ikey.iter_mut().for_each(|b0| *b0 ^= 0x36);
-
-        self.ctx.update(&self.ikey);
-    }
+    pub fn set_key(&mut self, key: &[u8]) { *self = Self::with_key(key); }
 
+    /// Finish the inner hash and feed its digest to a copy of the outer hash
+    /// context, which replaces the inner one. The key is left untouched so
+    /// that the context can still be reset.
     fn finish_(&mut self)
     {
         let i_digest = self.ctx.finish();
-        self.ctx.reset();
-
-        // xor with 0x36 ^ 0x5c. xoring with 0x36 again has the effect of getting the
-        // original ikey and xoring with 0x5c is what we need in the next step.
-        self.ikey.iter_mut().for_each(|b0| *b0 ^= 0x36 ^ 0x5c);
-        self.ctx.update(&self.ikey);
+        self.ctx = self.key.octx;
         self.ctx.update(&i_digest);
     }
 
     /// Oneshot HMAC function.
     pub fn oneshot(data: &[u8], key: &[u8]) -> [u8; D::DIGEST_LEN]
     {
-        let mut ctx = Self::with_key(key);
-        ctx.update(data);
-        ctx.finish_();
-        ctx.ctx.finish()
+        HmacKey::new(key).oneshot(data)
     }
 
     /// Oneshot HMAC function that puts the result in `buf`.
@@ -146,10 +255,148 @@ where
     [u8; D::DIGEST_LEN]:,
 {
     /// Finish the HMAC calculation and return a reference to the inner state.
-    fn finish_internal(&mut self) -> &[u8]
+    unsafe fn finish_internal(&mut self) -> &[u8]
     {
         self.finish_();
-        self.ctx.finish_internal()
+        unsafe { self.ctx.finish_internal() }
+    }
+}
+
+impl<D> Reset for Hmac<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    /// Reset the context to the state right after the key was set.
+    fn reset(&mut self) { self.ctx = self.key.ictx; }
+}
+
+impl<D> SetKey for Hmac<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    fn with_key(key: &[u8]) -> Self { Self::with_key(key) }
+
+    fn set_key(&mut self, key: &[u8]) { self.set_key(key); }
+}
+
+impl<D> Verify for Hmac<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+}
+
+impl<D> HmacKey<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    /// Precompute the inner and outer hash contexts for the key.
+    pub fn new(key: &[u8]) -> Self
+    {
+        let ctx = D::new();
+
+        let mut pad = [0; D::BLOCK_LEN];
+
+        // key shortening
+        if key.len() > D::BLOCK_LEN {
+            let mut key_ctx = ctx;
+            key_ctx.update(key);
+            pad[0..D::DIGEST_LEN].copy_from_slice(&key_ctx.finish());
+        } else {
+            pad[0..key.len()].copy_from_slice(key);
+        }
+
+        // xor with 0x36 to get i_key_pad
+        pad.iter_mut().for_each(|b0| *b0 ^= 0x36);
+        let mut ictx = ctx;
+        ictx.update(&pad);
+
+        // xor with 0x36 ^ 0x5c. xoring with 0x36 again has the effect of getting the
+        // original key and xoring with 0x5c gives o_key_pad.
+        pad.iter_mut().for_each(|b0| *b0 ^= 0x36 ^ 0x5c);
+        let mut octx = ctx;
+        octx.update(&pad);
+
+        Self { ictx, octx }
+    }
+
+    /// Calculate the HMAC of `data` with this key.
+    pub fn oneshot(&self, data: &[u8]) -> [u8; D::DIGEST_LEN]
+    {
+        let mut ctx = Hmac::from_key(self);
+        ctx.update(data);
+        ctx.finish_();
+        ctx.ctx.finish()
+    }
+
+    /// Calculate the HMAC of `data` with this key and put the result in `buf`.
+    ///
+    /// Same principles apply as
+    /// [`FinishToSlice`](`crate::digest::FinishToSlice`).
+    pub fn oneshot_to_slice(&self, data: &[u8], buf: &mut [u8])
+    {
+        let mut ctx = Hmac::from_key(self);
+        ctx.update(data);
+        ctx.finish_to_slice(buf);
+    }
+}
+
+impl<D> From<&HmacKey<D>> for Hmac<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    fn from(key: &HmacKey<D>) -> Self { Self::from_key(key) }
+}
+
+/// The midstate is the midstate of the inner hash context followed by the ones
+/// of the inner and outer hash contexts right after absorbing the key, which
+/// are needed for resetting and finishing. It has to be kept as secret as the
+/// key itself.
+impl<D> Midstate for Hmac<D>
+where
+    D: Digest + Copy + Midstate,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    const MIDSTATE_LEN: usize = 3 * D::MIDSTATE_LEN;
+
+    fn export(&self, buf: &mut [u8])
+    {
+        assert_eq!(
+            buf.len(),
+            Self::MIDSTATE_LEN,
+            "length of the buffer is not equal to the midstate length"
+        );
+        let (ctx, key) = buf.split_at_mut(D::MIDSTATE_LEN);
+        let (inner, outer) = key.split_at_mut(D::MIDSTATE_LEN);
+        self.ctx.export(ctx);
+        self.key.ictx.export(inner);
+        self.key.octx.export(outer);
+    }
+
+    fn import(midstate: &[u8]) -> Result<Self, MidstateError>
+    {
+        if midstate.len() != Self::MIDSTATE_LEN {
+            return Err(MidstateError::Len);
+        }
+        let (ctx, key) = midstate.split_at(D::MIDSTATE_LEN);
+        let (inner, outer) = key.split_at(D::MIDSTATE_LEN);
+        Ok(Self {
+            ctx: D::import(ctx)?,
+            key: HmacKey {
+                ictx: D::import(inner)?,
+                octx: D::import(outer)?,
+            },
+        })
     }
 }
 
@@ -171,13 +418,11 @@ where
 {
     fn finish(&self) -> u64
     {
-        // The state is copied here, since we need a mutable reference but cant with the
-        // borrow.
-        let mut ctx: Self = *self;
-        let mut digest: MaybeUninit<[u8; 8]> = MaybeUninit::uninit();
+        // The state is copied here, since finishing consumes the context.
+        let mut digest: [u8; 8] = [0; 8];
         // FIXME: What if the digest length is less than 8 bytes long.
-        unsafe { digest.assume_init_mut() }.copy_from_slice(&ctx.finish_internal()[0..8]);
-        u64::from_be_bytes(unsafe { digest.assume_init() })
+        (*self).finish_to_slice(&mut digest);
+        u64::from_be_bytes(digest)
     }
 
     fn write(&mut self, bytes: &[u8]) { self.update(bytes); }
@@ -205,3 +450,126 @@ where
 
     fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::digest::Finish;
+    use crate::digest::MacError;
+
+    #[test]
+    fn midstate()
+    {
+        let mut ctx = HmacSha256::with_key(b"key");
+        ctx.update(b"The quick brown fox ");
+
+        let mut midstate = [0; HmacSha256::MIDSTATE_LEN];
+        ctx.export(&mut midstate);
+        let mut ctx = HmacSha256::import(&midstate).unwrap();
+        ctx.update(b"jumps over the lazy dog");
+
+        assert_eq!(
+            hex::encode(ctx.finish()),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+        assert_eq!(
+            HmacSha512::import(&midstate).err(),
+            Some(MidstateError::Len)
+        );
+    }
+
+    #[test]
+    fn hmac_key()
+    {
+        // RFC 4231, test case 6
+        let key = HmacKey::<merkle_damgard::Sha256>::new(&[0xaa; 131]);
+        let data = b"Test Using Larger Than Block-Size Key - Hash Key First";
+        assert_eq!(
+            hex::encode(key.oneshot(data)),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+
+        let key = HmacKey::<merkle_damgard::Sha512>::new(b"key");
+        for len in [0, 1, 127, 128, 129, 300] {
+            let data = [0x5a; 300];
+            let mut ctx = Hmac::from(&key);
+            ctx.update(&data[0..len]);
+            let mut buf = [0; 64];
+            key.oneshot_to_slice(&data[0..len], &mut buf);
+
+            let expected = HmacSha512::oneshot(&data[0..len], b"key");
+            assert_eq!(ctx.finish(), expected);
+            assert_eq!(buf, expected);
+        }
+    }
+
+    #[test]
+    fn verify()
+    {
+        // RFC 4231, test case 5
+        let key = HmacKey::<merkle_damgard::Sha256>::new(&[0x0c; 20]);
+        let tag = hex::decode("a3b6167473100ee06e0c796c2955552b").unwrap();
+        let data = b"Test With Truncation";
+        let mut ctx = Hmac::from_key(&key);
+
+        ctx.update(data);
+        assert_eq!(ctx.verify_truncated(&tag), Ok(()));
+        ctx.update(data);
+        assert_eq!(ctx.verify_truncated(&tag[0..15]), Err(MacError));
+        ctx.update(data);
+        assert_eq!(ctx.verify(&tag), Err(MacError));
+
+        let mut tag = ctx.finalize_clone();
+        ctx.update(data);
+        assert_eq!(ctx.verify(&tag), Err(MacError));
+        // The context is reset even when the tag doesn't match.
+        assert_eq!(ctx.verify(&tag), Ok(()));
+
+        tag = key.oneshot(data);
+        ctx.update(data);
+        assert_eq!(ctx.verify(&tag), Ok(()));
+        tag[31] ^= 1;
+        ctx.update(data);
+        assert_eq!(ctx.verify(&tag), Err(MacError));
+        ctx.update(data);
+        assert_eq!(ctx.verify_truncated(&tag[0..31]), Ok(()));
+        ctx.update(data);
+        assert_eq!(
+            ctx.verify_truncated(&[tag.as_slice(), &[0]].concat()),
+            Err(MacError)
+        );
+    }
+
+    #[test]
+    fn const_fn()
+    {
+        const KEY: &[u8] = b"key";
+        const LONG_KEY: &[u8] = &[0x61; 200];
+        const DATA: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+        macro_rules! check {
+            ($($hmac:ty),*) => {$({
+                const DIGEST: [u8; <$hmac>::DIGEST_LEN] = <$hmac>::const_oneshot(DATA, KEY);
+                assert_eq!(DIGEST, <$hmac>::oneshot(DATA, KEY));
+                const LONG: [u8; <$hmac>::DIGEST_LEN] = <$hmac>::const_oneshot(DATA, LONG_KEY);
+                assert_eq!(LONG, <$hmac>::oneshot(DATA, LONG_KEY));
+            })*};
+        }
+
+        check!(
+            HmacMd5,
+            HmacSha1,
+            HmacSha224,
+            HmacSha256,
+            HmacSha384,
+            HmacSha512,
+            HmacSha512_224,
+            HmacSha512_256
+        );
+        assert_eq!(
+            hex::encode(HmacSha256::const_oneshot(DATA, KEY)),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! # Legacy algorithms
+//!
+//! The algorithms in this module are broken and must not be used for anything
+//! that relies on their security. They are only provided to interoperate with
+//! protocols that still depend on them, such as NTLM and rsync.
+
+use oxicrypt_core::md_compress;
+
+use crate::digest::Finish;
+use crate::digest::Update;
+use crate::merkle_damgard::Compress;
+use crate::merkle_damgard::InitializationVector;
+use crate::merkle_damgard::MerkleDamgard;
+use crate::num::ByteOrder;
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressMd4();
+
+impl Compress<u32> for CompressMd4
+{
+    const ID: u8 = 7;
+
+    unsafe fn compress(h: *mut u32, b: *const u8, n: usize)
+    {
+        unsafe { md_compress::md4(h, b, n) };
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IvMd4();
+
+impl const InitializationVector<u32, 4> for IvMd4
+{
+    fn initial() -> [u32; 4] { MD4_INITIAL_H }
+}
+
+/// MD4 context.
+///
+/// MD4 is completely broken, since collisions can be found by hand. Only use
+/// it where a legacy protocol requires it.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::legacy::*;
+/// let digest = Md4::oneshot(b"abc");
+/// assert_eq!(hex::encode(digest), "a448017aaf21d8525fc10ae87aa6729d");
+/// ```
+pub type Md4 = MerkleDamgard<u32, u64, IvMd4, CompressMd4, { ByteOrder::Little }, 4, 64>;
+
+/// NT hash of `password`, which is the MD4 digest of the password encoded as
+/// UTF-16LE.
+///
+/// NTLM authentication uses it to store and verify passwords.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::legacy::*;
+/// let hash = nt_hash("password");
+/// assert_eq!(hex::encode(hash), "8846f7eaee8fb117ad06bdd830b7586c");
+/// ```
+pub fn nt_hash(password: &str) -> [u8; 16]
+{
+    let mut ctx = Md4::new();
+    password
+        .encode_utf16()
+        .for_each(|unit| ctx.update(&unit.to_le_bytes()));
+    ctx.finish()
+}
+
+/// Initial state of the MD4 algorithm.
+#[rustfmt::skip]
+pub const MD4_INITIAL_H: [u32; 4] = [
+    0x67452301,
+    0xefcdab89,
+    0x98badcfe,
+    0x10325476,
+];
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::digest::Oneshot;
+
+    /// Test suite from RFC 1320, appendix A.5.
+    #[test]
+    fn rfc1320()
+    {
+        let tests: [(&[u8], &str); 7] = [
+            (b"", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            (b"a", "bde52cb31de33e46245e05fbdbd6fb24"),
+            (b"abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            (b"message digest", "d9130a8164549fe818874806e1c7014b"),
+            (
+                b"abcdefghijklmnopqrstuvwxyz",
+                "d79e1c308aa5bbcdeea8ed63df412da9",
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "043f8582f241db351ce627e153e7f0e4",
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+        for (data, digest) in tests {
+            assert_eq!(hex::encode(Md4::oneshot(data)), digest);
+        }
+    }
+
+    #[test]
+    fn nt_hash_password()
+    {
+        assert_eq!(
+            hex::encode(nt_hash("password")),
+            "8846f7eaee8fb117ad06bdd830b7586c"
+        );
+        assert_eq!(hex::encode(nt_hash("")), "31d6cfe0d16ae931b73c59d7e0c089c0");
+    }
+}
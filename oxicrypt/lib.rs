@@ -21,12 +21,30 @@ extern crate alloc;
 extern crate std;
 
 pub mod aes;
+#[cfg(any(feature = "alloc", doc))]
+#[doc(cfg(feature = "alloc"))]
+pub mod argon2;
+pub mod blake2;
+pub mod blake3;
+pub mod ct;
 pub mod digest;
 pub mod hkdf;
 pub mod hmac;
+pub mod legacy;
 pub mod merkle_damgard;
+pub mod mode;
 pub mod num;
+pub mod pbkdf2;
+#[cfg(any(feature = "alloc", doc))]
+mod phc;
 pub mod runtime;
+#[cfg(any(feature = "alloc", doc))]
+#[doc(cfg(feature = "alloc"))]
+pub mod scrypt;
+pub mod sha1dc;
+pub mod sm4;
+pub mod sp800_185;
+pub mod sponge;
 pub mod traits;
 
 //#[cfg(test)] pub(crate) mod test_vectors;
@@ -6,24 +6,47 @@
 //! and the final block is padded. Some of the most popular hashing algorithms
 //! suchs as the SHA-2 family of functions use this construction.
 //!
+//! Every hash in this module can also be computed at compile time with
+//! `const_update`, `const_finish` and `const_oneshot`, which take the context
+//! by value.
+//!
+//! ```
+//! # use oxicrypt::digest::*;
+//! # use oxicrypt::merkle_damgard::*;
+//! const DIGEST: [u8; 32] = Sha256::const_oneshot(b"abc");
+//!
+//! assert_eq!(DIGEST, Sha256::oneshot(b"abc"));
+//! ```
+//!
 //! [Merkle–Damgård]: https://en.wikipedia.org/wiki/Merkle%E2%80%93Damg%C3%A5rd_construction
 
+use core::cmp;
 use core::intrinsics;
 use core::marker::PhantomData;
 use core::mem;
-use core::mem::MaybeUninit;
+use core::ptr;
 use core::slice;
 
 use num_traits::NumCast;
 use num_traits::PrimInt;
 use oxicrypt_core::md_compress;
+#[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+use oxicrypt_core::sha_arm;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use oxicrypt_core::shani;
 
 use crate::digest::DigestMeta;
 use crate::digest::FinishInternal;
+use crate::digest::Midstate;
+use crate::digest::MidstateError;
+use crate::digest::Oneshot;
 use crate::digest::Output;
 use crate::digest::Reset;
 use crate::digest::Update;
+use crate::digest::UpdateBits;
+use crate::legacy::Md4;
 use crate::num::ByteOrder;
+use crate::runtime::Feature;
 use crate::traits::New;
 
 /// Compression function used by Merkle–Damgård.
@@ -31,7 +54,12 @@ pub trait Compress<Int>
 where
     Int: PrimInt,
 {
-    unsafe fn compress(h: *mut Int, b: *const u8);
+    /// Identifier of the compression function, which is written to the
+    /// midstate header. Every compression function must use a different one.
+    const ID: u8;
+
+    /// Compresses `n` consecutive blocks pointed to by `b` into the state `h`.
+    unsafe fn compress(h: *mut Int, b: *const u8, n: usize);
 }
 
 /// Initialization vector used by the algorithm.
@@ -46,6 +74,30 @@ where
     fn initial() -> [Int; LEN];
 }
 
+/// Version of the midstate format written by [`Midstate::export`].
+const MIDSTATE_VERSION: u8 = 2;
+
+/// Length of the midstate fields that come before the state words.
+///
+/// | Offset | Length | Field                                       |
+/// |--------|--------|---------------------------------------------|
+/// | 0      | 1      | format version                              |
+/// | 1      | 1      | byte order, 0 for little and 1 for big      |
+/// | 2      | 1      | size of a state word                        |
+/// | 3      | 1      | number of state words                       |
+/// | 4      | 2      | block length                                |
+/// | 6      | 1      | identifier of the compression function      |
+/// | 7      | 8      | number of compressed blocks                 |
+/// | 15     | 2      | number of bytes in the block                |
+/// | 17     | 1      | number of bits used in the last byte        |
+///
+/// Integers are big-endian. The words of the initialization vector, the state
+/// words and the block follow, with the unused part of the block set to zeros.
+/// The compression function and the initialization vector together identify
+/// the algorithm, so for example MD4 and MD5, or SHA-224 and SHA-256, can't
+/// import each other's midstates.
+const MIDSTATE_HEADER_LEN: usize = 18;
+
 /// Generic Merkle–Damgård construction.
 #[derive(Debug, Clone, Copy)]
 pub struct MerkleDamgard<
@@ -67,6 +119,8 @@ pub struct MerkleDamgard<
     pub block: [u8; BLOCK_LEN],
     pub index: usize,
     pub count: usize,
+    /// Number of bits used in the last byte of the block, or 0 if it is full.
+    bits:      usize,
     _length:   PhantomData<Length>,
     _iv:       PhantomData<IV>,
     _compress: PhantomData<Compress>,
@@ -93,9 +147,12 @@ where
     {
         Self {
             state:     IV::initial(),
-            block:     unsafe { MaybeUninit::uninit().assume_init() },
+            // The block is zeroed, since reading uninitialized memory is an error when
+            // hashing at compile time.
+            block:     [0; BLOCK_LEN],
             index:     0,
             count:     0,
+            bits:      0,
             _length:   PhantomData,
             _iv:       PhantomData,
             _compress: PhantomData,
@@ -105,52 +162,197 @@ where
     #[inline(always)]
     pub fn compress(&mut self)
     {
-        unsafe { Compress::compress(self.state.as_mut_ptr(), self.block.as_ptr()) };
+        unsafe { Compress::compress(self.state.as_mut_ptr(), self.block.as_ptr(), 1) };
     }
 
     /// Update the inner block and compress the state when the block is full
     /// according to the specifications of the Merkle–Damgård construction.
+    ///
+    /// Full blocks are compressed directly from `data` without being copied
+    /// into the inner block first.
     fn update_(&mut self, mut data: &[u8])
     {
-        // Loop until all the data is processed.
-        while !data.is_empty() {
-            let emptyspace = BLOCK_LEN - self.index;
-            // If there is enough space in the block, then we can just copy `data` into
-            // `block`.
-            if emptyspace >= data.len() {
-                let newindex = self.index + data.len();
-                self.block[self.index..newindex].copy_from_slice(data);
-                self.index = newindex;
-                // All of the data is read at this point. We need to set the length of `data` to
-                // 0 so we can exit out of the loop.
-                data = &data[0..0];
-            } else {
-                self.block[self.index..BLOCK_LEN].copy_from_slice(&data[0..emptyspace]);
-                // We filled `self.block` completely.
-                self.index = BLOCK_LEN;
-                data = &data[emptyspace..];
-            }
+        assert!(self.bits == 0, "cannot update after a partial byte");
+
+        // Fill the partially filled block first, if there is one.
+        if self.index != 0 {
+            let len = cmp::min(BLOCK_LEN - self.index, data.len());
+            self.block[self.index..self.index + len].copy_from_slice(&data[0..len]);
+            self.index += len;
+            data = &data[len..];
 
-            if self.index == BLOCK_LEN {
-                self.compress();
-                self.index = 0;
-                self.count += 1;
+            // The block is still not full, so all of the data is read at this point.
+            if self.index != BLOCK_LEN {
+                return;
             }
+
+            self.compress();
+            self.index = 0;
+            self.count += 1;
+        }
+
+        // Compress as many blocks as possible straight from `data`.
+        let blocks = data.len() / BLOCK_LEN;
+        if blocks != 0 {
+            unsafe { Compress::compress(self.state.as_mut_ptr(), data.as_ptr(), blocks) };
+            self.count += blocks;
+            data = &data[blocks * BLOCK_LEN..];
+        }
+
+        // Keep the remaining bytes for the next call.
+        self.block[0..data.len()].copy_from_slice(data);
+        self.index = data.len();
+    }
+
+    fn update_bits_(&mut self, data: &[u8], nbits: usize)
+    {
+        assert!(
+            nbits <= data.len() * 8,
+            "`data` is shorter than `nbits` bits"
+        );
+
+        self.update_(&data[0..nbits / 8]);
+
+        // The partial byte is kept in the block even if it fills the block, because
+        // the padding bit has to be appended to it before it can be compressed.
+        if nbits % 8 != 0 {
+            self.block[self.index] = data[nbits / 8];
+            self.index += 1;
+            self.bits = nbits % 8;
+        }
+    }
+
+    /// Fields of the midstate header that describe the algorithm, apart from
+    /// the initialization vector.
+    fn midstate_id() -> [u8; 7]
+    {
+        let mut id: [u8; 7] = [0; 7];
+        id[0] = MIDSTATE_VERSION;
+        id[1] = match ENDIAN {
+            | ByteOrder::Little => 0,
+            | ByteOrder::Big => 1,
+        };
+        id[2] = mem::size_of::<State>() as u8;
+        id[3] = STATE_LEN as u8;
+        id[4..6].copy_from_slice(&(BLOCK_LEN as u16).to_be_bytes());
+        id[6] = Compress::ID;
+        id
+    }
+
+    /// Write `words` to `buf` in big endian.
+    fn write_words(words: &[State], buf: &mut [u8])
+    {
+        for (word, chunk) in words
+            .iter()
+            .zip(buf.chunks_exact_mut(mem::size_of::<State>()))
+        {
+            let word: u64 = <u64 as NumCast>::from(*word).unwrap();
+            chunk.copy_from_slice(&word.to_be_bytes()[8 - mem::size_of::<State>()..]);
+        }
+    }
+
+    /// Read the words written by [`write_words`](`Self::write_words`).
+    fn read_words(buf: &[u8]) -> impl Iterator<Item = State> + '_
+    {
+        buf.chunks_exact(mem::size_of::<State>()).map(|chunk| {
+            let mut bytes: [u8; 8] = [0; 8];
+            bytes[8 - mem::size_of::<State>()..].copy_from_slice(chunk);
+            <State as NumCast>::from(u64::from_be_bytes(bytes)).unwrap()
+        })
+    }
+
+    fn export_(&self, buf: &mut [u8])
+    {
+        assert_eq!(
+            buf.len(),
+            Self::MIDSTATE_LEN,
+            "length of the buffer is not equal to the midstate length"
+        );
+        let (header, buf) = buf.split_at_mut(MIDSTATE_HEADER_LEN);
+        let (iv, buf) = buf.split_at_mut(mem::size_of::<State>() * STATE_LEN);
+        let (state, block) = buf.split_at_mut(mem::size_of::<State>() * STATE_LEN);
+
+        header[0..7].copy_from_slice(&Self::midstate_id());
+        header[7..15].copy_from_slice(&(self.count as u64).to_be_bytes());
+        header[15..17].copy_from_slice(&(self.index as u16).to_be_bytes());
+        header[17] = self.bits as u8;
+
+        Self::write_words(&IV::initial(), iv);
+        Self::write_words(&self.state, state);
+
+        block[0..self.index].copy_from_slice(&self.block[0..self.index]);
+        block[self.index..].fill(0);
+    }
+
+    fn import_(midstate: &[u8]) -> Result<Self, MidstateError>
+    {
+        if midstate.len() != Self::MIDSTATE_LEN {
+            return Err(MidstateError::Len);
+        }
+        let (header, midstate) = midstate.split_at(MIDSTATE_HEADER_LEN);
+        let (iv, midstate) = midstate.split_at(mem::size_of::<State>() * STATE_LEN);
+        let (state, block) = midstate.split_at(mem::size_of::<State>() * STATE_LEN);
+
+        if header[0] != MIDSTATE_VERSION {
+            return Err(MidstateError::Version);
+        }
+        if header[0..7] != Self::midstate_id() || !Self::read_words(iv).eq(IV::initial()) {
+            return Err(MidstateError::Algorithm);
+        }
+
+        let count = u64::from_be_bytes(header[7..15].try_into().unwrap());
+        let index = u16::from_be_bytes(header[15..17].try_into().unwrap()) as usize;
+        let bits = header[17] as usize;
+
+        // The block is only full when its last byte is partial, and the total number
+        // of bits has to fit in the length counter.
+        let count = usize::try_from(count).map_err(|_| MidstateError::Invalid)?;
+        let valid_index = match bits {
+            | 0 => index < BLOCK_LEN,
+            | 1..=7 => index != 0 && index <= BLOCK_LEN,
+            | _ => false,
+        };
+        let valid_count = count
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(BLOCK_LEN * 8))
+            .is_some();
+        if !valid_index || !valid_count || block[index..].iter().any(|&b| b != 0) {
+            return Err(MidstateError::Invalid);
         }
+
+        let mut ctx = Self::new();
+        ctx.state
+            .iter_mut()
+            .zip(Self::read_words(state))
+            .for_each(|(word, value)| *word = value);
+        ctx.block[0..index].copy_from_slice(&block[0..index]);
+        ctx.index = index;
+        ctx.count = count;
+        ctx.bits = bits;
+        Ok(ctx)
     }
 
     fn finish_(&mut self)
     {
         // total number of bits processed
-        let len = (self.count * BLOCK_LEN + self.index) * 8;
+        let mut len = (self.count * BLOCK_LEN + self.index) * 8;
 
-        // We need to pad `self.block` with a "1" bit followed by "0" bits according to
-        // the specifications of the algorithm. 0x80 byte represents 0b10000000. We
-        // can append this byte without checking if there is enough space, because a
-        // call to update would have reset the block if there weren't enough space
-        // for at least one byte.
-        self.block[self.index] = 0x80;
-        self.index += 1;
+        if self.bits != 0 {
+            // The "1" bit goes right after the last bit of the partial byte, and the
+            // unused bits after it are cleared.
+            len -= 8 - self.bits;
+            let last = &mut self.block[self.index - 1];
+            *last = (*last & !(0xff >> self.bits)) | (0x80 >> self.bits);
+            self.bits = 0;
+        } else {
+            // We need to pad `self.block` with a "1" bit followed by "0" bits according
+            // to the specifications of the algorithm. 0x80 byte represents 0b10000000.
+            // We can append this byte without checking if there is enough space,
+            // because a call to update would have reset the block if there weren't
+            // enough space for at least one byte.
+            self.block[self.index] = 0x80;
+            self.index += 1;
+        }
 
         // If there is not enough space to write the length counter, fill the remaining
         // space in the block with zeros and compress it.
@@ -250,6 +452,7 @@ where
         self.state = IV::initial();
         self.index = 0;
         self.count = 0;
+        self.bits = 0;
     }
 }
 
@@ -269,9 +472,58 @@ where
     IV: ~const InitializationVector<State, STATE_LEN>,
     Compress: self::Compress<State>,
 {
+    /// Update the context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the context was already updated with a partial byte by
+    /// [`update_bits`](`UpdateBits::update_bits`).
     fn update(&mut self, data: &[u8]) { self.update_(data); }
 }
 
+impl<
+    State,
+    Length,
+    IV,
+    Compress,
+    const ENDIAN: ByteOrder,
+    const STATE_LEN: usize,
+    const BLOCK_LEN: usize,
+> UpdateBits for MerkleDamgard<State, Length, IV, Compress, ENDIAN, STATE_LEN, BLOCK_LEN>
+where
+    State: PrimInt,
+    [State; STATE_LEN]:,
+    Length: PrimInt,
+    IV: ~const InitializationVector<State, STATE_LEN>,
+    Compress: self::Compress<State>,
+{
+    fn update_bits(&mut self, data: &[u8], nbits: usize) { self.update_bits_(data, nbits); }
+}
+
+impl<
+    State,
+    Length,
+    IV,
+    Compress,
+    const ENDIAN: ByteOrder,
+    const STATE_LEN: usize,
+    const BLOCK_LEN: usize,
+> Midstate for MerkleDamgard<State, Length, IV, Compress, ENDIAN, STATE_LEN, BLOCK_LEN>
+where
+    State: PrimInt,
+    [State; STATE_LEN]:,
+    Length: PrimInt,
+    IV: ~const InitializationVector<State, STATE_LEN>,
+    Compress: self::Compress<State>,
+{
+    const MIDSTATE_LEN: usize =
+        MIDSTATE_HEADER_LEN + 2 * mem::size_of::<State>() * STATE_LEN + BLOCK_LEN;
+
+    fn export(&self, buf: &mut [u8]) { self.export_(buf); }
+
+    fn import(midstate: &[u8]) -> Result<Self, MidstateError> { Self::import_(midstate) }
+}
+
 impl<
     State,
     Length,
@@ -288,7 +540,7 @@ where
     IV: ~const InitializationVector<State, STATE_LEN>,
     Compress: self::Compress<State>,
 {
-    fn finish_internal(&mut self) -> &[u8]
+    unsafe fn finish_internal(&mut self) -> &[u8]
     {
         self.finish_();
         unsafe { slice::from_raw_parts(self.state.as_ptr().cast(), Self::DIGEST_LEN) }
@@ -307,25 +559,102 @@ pub struct CompressSha512();
 #[doc(hidden)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CompressMd5();
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressRipemd160();
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressSm3();
 
 impl Compress<u32> for CompressSha1
 {
-    unsafe fn compress(h: *mut u32, b: *const u8) { unsafe { md_compress::sha1(h, b) }; }
+    const ID: u8 = 1;
+
+    unsafe fn compress(h: *mut u32, b: *const u8, n: usize)
+    {
+        if Feature::Shani.is_available() {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            unsafe {
+                shani::sha1(h, b, n)
+            };
+        } else if Feature::ArmSha2.is_available() {
+            #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+            unsafe {
+                sha_arm::sha1(h, b, n)
+            };
+        } else {
+            unsafe { md_compress::sha1(h, b, n) };
+        }
+    }
 }
 
 impl Compress<u32> for CompressSha256
 {
-    unsafe fn compress(h: *mut u32, b: *const u8) { unsafe { md_compress::sha256(h, b) }; }
+    const ID: u8 = 2;
+
+    unsafe fn compress(h: *mut u32, b: *const u8, n: usize)
+    {
+        if Feature::Shani.is_available() {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            unsafe {
+                shani::sha256(h, b, n)
+            };
+        } else if Feature::ArmSha2.is_available() {
+            #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+            unsafe {
+                sha_arm::sha256(h, b, n)
+            };
+        } else {
+            unsafe { md_compress::sha256(h, b, n) };
+        }
+    }
 }
 
 impl Compress<u64> for CompressSha512
 {
-    unsafe fn compress(h: *mut u64, b: *const u8) { unsafe { md_compress::sha512(h, b) }; }
+    const ID: u8 = 3;
+
+    unsafe fn compress(h: *mut u64, b: *const u8, n: usize)
+    {
+        if Feature::ArmSha512.is_available() {
+            #[cfg(target_arch = "aarch64")]
+            unsafe {
+                sha_arm::sha512(h, b, n)
+            };
+        } else {
+            unsafe { md_compress::sha512(h, b, n) };
+        }
+    }
 }
 
 impl Compress<u32> for CompressMd5
 {
-    unsafe fn compress(h: *mut u32, b: *const u8) { unsafe { md_compress::md5(h, b) }; }
+    const ID: u8 = 4;
+
+    unsafe fn compress(h: *mut u32, b: *const u8, n: usize)
+    {
+        unsafe { md_compress::md5(h, b, n) };
+    }
+}
+
+impl Compress<u32> for CompressRipemd160
+{
+    const ID: u8 = 5;
+
+    unsafe fn compress(h: *mut u32, b: *const u8, n: usize)
+    {
+        unsafe { md_compress::ripemd160(h, b, n) };
+    }
+}
+
+impl Compress<u32> for CompressSm3
+{
+    const ID: u8 = 6;
+
+    unsafe fn compress(h: *mut u32, b: *const u8, n: usize)
+    {
+        unsafe { md_compress::sm3(h, b, n) };
+    }
 }
 
 macro_rules! impl_iv {
@@ -349,6 +678,22 @@ impl_iv!(const IvSha512: [u64; 8] = SHA_INITIAL_H512);
 impl_iv!(const IvSha512_224: [u64; 8] = SHA_INITIAL_H512_224);
 impl_iv!(const IvSha512_256: [u64; 8] = SHA_INITIAL_H512_256);
 impl_iv!(const IvMd5: [u32; 4] = MD5_INITIAL_H);
+impl_iv!(const IvRipemd160: [u32; 5] = RIPEMD160_INITIAL_H);
+impl_iv!(const IvSm3: [u32; 8] = SM3_INITIAL_H);
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IvSha512T<const T: usize>();
+
+impl<const T: usize> IvSha512T<T>
+{
+    const IV: [u64; 8] = sha512t_initial_h(T);
+}
+
+impl<const T: usize> const InitializationVector<u64, 8> for IvSha512T<T>
+{
+    fn initial() -> [u64; 8] { Self::IV }
+}
 
 // MerkleDamgard<State, Length, IV, Compress, Endian, STATE_LEN, BLOCK_LEN>
 pub type Sha1 = MerkleDamgard<u32, u64, IvSha1, CompressSha1, { ByteOrder::Big }, 5, 64>;
@@ -363,6 +708,839 @@ pub type Sha512_224 =
 pub type Sha512_256 =
     Output<MerkleDamgard<u64, u128, IvSha512_256, CompressSha512, { ByteOrder::Big }, 8, 128>, 32>;
 pub type Md5 = MerkleDamgard<u32, u64, IvMd5, CompressMd5, { ByteOrder::Little }, 4, 64>;
+pub type Ripemd160 =
+    MerkleDamgard<u32, u64, IvRipemd160, CompressRipemd160, { ByteOrder::Little }, 5, 64>;
+pub type Sm3 = MerkleDamgard<u32, u64, IvSm3, CompressSm3, { ByteOrder::Big }, 8, 64>;
+
+/// SHA-512/t, which is SHA-512 with a different initial state and a digest
+/// truncated to `T` bits.
+///
+/// The initial state is computed at compile time by [`sha512t_initial_h`], so
+/// an invalid `T` fails to compile as soon as a context is created.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// assert_eq!(Sha512T::<224>::oneshot(b"abc"), Sha512_224::oneshot(b"abc"));
+///
+/// let digest = Sha512T::<192>::oneshot(b"abc");
+/// assert_eq!(
+///     hex::encode(digest),
+///     "6c4cb5b80909c1f4858dd872ababebce67bc9a3ea8e9866c"
+/// );
+/// ```
+///
+/// `T` must be a multiple of 8 below 512 other than 0 and 384:
+///
+/// ```compile_fail
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let digest = Sha512T::<0>::oneshot(b"abc");
+/// ```
+///
+/// ```compile_fail
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let digest = Sha512T::<384>::oneshot(b"abc");
+/// ```
+///
+/// ```compile_fail
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let digest = Sha512T::<512>::oneshot(b"abc");
+/// ```
+///
+/// ```compile_fail
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let digest = Sha512T::<100>::oneshot(b"abc");
+/// ```
+pub type Sha512T<const T: usize> = Output<
+    MerkleDamgard<u64, u128, IvSha512T<T>, CompressSha512, { ByteOrder::Big }, 8, 128>,
+    { T / 8 },
+>;
+
+/// Implements the const functions used for hashing at compile time.
+///
+/// The compression functions in `md_compress` are const, so these work the same
+/// way as their [`Update`] and [`Finish`](`crate::digest::Finish`)
+/// counterparts, except the context is passed by value.
+macro_rules! impl_const_md {
+    (
+        $md:ty:
+        $compress:path, $length:ty, $to_bytes:ident, $block_len:literal, $digest_len:literal
+    ) => {
+        impl $md
+        {
+            /// Same as [`update`](`Update::update`), but can be used in a const
+            /// context.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the context was already updated with a partial byte.
+            pub const fn const_update(mut self, data: &[u8]) -> Self
+            {
+                assert!(self.bits == 0, "cannot update after a partial byte");
+
+                let mut i = 0;
+                while i < data.len() {
+                    if self.index == 0 && data.len() - i >= $block_len {
+                        // Compress as many blocks as possible straight from `data`.
+                        let blocks = (data.len() - i) / $block_len;
+                        unsafe { $compress(self.state.as_mut_ptr(), data.as_ptr().add(i), blocks) };
+                        self.count += blocks;
+                        i += blocks * $block_len;
+                    } else {
+                        self.block[self.index] = data[i];
+                        self.index += 1;
+                        i += 1;
+                        if self.index == $block_len {
+                            unsafe { $compress(self.state.as_mut_ptr(), self.block.as_ptr(), 1) };
+                            self.index = 0;
+                            self.count += 1;
+                        }
+                    }
+                }
+                self
+            }
+
+            /// Same as [`finish`](`crate::digest::Finish::finish`), but can be
+            /// used in a const context.
+            pub const fn const_finish(mut self) -> [u8; $digest_len]
+            {
+                let mut len = (self.count * $block_len + self.index) * 8;
+                if self.bits != 0 {
+                    len -= 8 - self.bits;
+                    let last = self.block[self.index - 1];
+                    self.block[self.index - 1] =
+                        (last & !(0xff >> self.bits)) | (0x80 >> self.bits);
+                } else {
+                    self.block[self.index] = 0x80;
+                    self.index += 1;
+                }
+
+                let len_offset = $block_len - mem::size_of::<$length>();
+                if self.index > len_offset {
+                    while self.index < $block_len {
+                        self.block[self.index] = 0;
+                        self.index += 1;
+                    }
+                    unsafe { $compress(self.state.as_mut_ptr(), self.block.as_ptr(), 1) };
+                    self.index = 0;
+                }
+                while self.index < len_offset {
+                    self.block[self.index] = 0;
+                    self.index += 1;
+                }
+
+                let len = (len as $length).$to_bytes();
+                let mut i = 0;
+                while i < len.len() {
+                    self.block[len_offset + i] = len[i];
+                    i += 1;
+                }
+                unsafe { $compress(self.state.as_mut_ptr(), self.block.as_ptr(), 1) };
+
+                let mut digest: [u8; $digest_len] = [0; $digest_len];
+                let word_len = $digest_len / self.state.len();
+                let mut i = 0;
+                while i < self.state.len() {
+                    let word = self.state[i].$to_bytes();
+                    let mut j = 0;
+                    while j < word_len {
+                        digest[i * word_len + j] = word[j];
+                        j += 1;
+                    }
+                    i += 1;
+                }
+                digest
+            }
+
+            /// Calculate the digest of `data` in a const context.
+            pub const fn const_oneshot(data: &[u8]) -> [u8; $digest_len]
+            {
+                Self::new().const_update(data).const_finish()
+            }
+        }
+    };
+}
+
+/// Implements the const functions of a truncated digest by calling the ones
+/// of the inner context.
+macro_rules! impl_const_output {
+    ($output:ty: $digest_len:literal) => {
+        impl $output
+        {
+            /// Same as [`update`](`Update::update`), but can be used in a const
+            /// context.
+            pub const fn const_update(self, data: &[u8]) -> Self
+            {
+                Self {
+                    inner: self.inner.const_update(data),
+                }
+            }
+
+            /// Same as [`finish`](`crate::digest::Finish::finish`), but can be
+            /// used in a const context.
+            pub const fn const_finish(self) -> [u8; $digest_len]
+            {
+                let inner = self.inner.const_finish();
+                let mut digest: [u8; $digest_len] = [0; $digest_len];
+                let mut i = 0;
+                while i < digest.len() {
+                    digest[i] = inner[i];
+                    i += 1;
+                }
+                digest
+            }
+
+            /// Calculate the digest of `data` in a const context.
+            pub const fn const_oneshot(data: &[u8]) -> [u8; $digest_len]
+            {
+                Self::new().const_update(data).const_finish()
+            }
+        }
+    };
+}
+
+// context: compress, length, byte order, block length, full digest length
+impl_const_md!(Sha1: md_compress::sha1, u64, to_be_bytes, 64, 20);
+impl_const_md!(Sha256: md_compress::sha256, u64, to_be_bytes, 64, 32);
+impl_const_md!(Sha512: md_compress::sha512, u128, to_be_bytes, 128, 64);
+impl_const_md!(Md4: md_compress::md4, u64, to_le_bytes, 64, 16);
+impl_const_md!(Md5: md_compress::md5, u64, to_le_bytes, 64, 16);
+impl_const_md!(Ripemd160: md_compress::ripemd160, u64, to_le_bytes, 64, 20);
+impl_const_md!(Sm3: md_compress::sm3, u64, to_be_bytes, 64, 32);
+impl_const_md!(
+    MerkleDamgard<u32, u64, IvSha224, CompressSha256, { ByteOrder::Big }, 8, 64>:
+    md_compress::sha256, u64, to_be_bytes, 64, 32
+);
+impl_const_md!(
+    MerkleDamgard<u64, u128, IvSha384, CompressSha512, { ByteOrder::Big }, 8, 128>:
+    md_compress::sha512, u128, to_be_bytes, 128, 64
+);
+impl_const_md!(
+    MerkleDamgard<u64, u128, IvSha512_224, CompressSha512, { ByteOrder::Big }, 8, 128>:
+    md_compress::sha512, u128, to_be_bytes, 128, 64
+);
+impl_const_md!(
+    MerkleDamgard<u64, u128, IvSha512_256, CompressSha512, { ByteOrder::Big }, 8, 128>:
+    md_compress::sha512, u128, to_be_bytes, 128, 64
+);
+
+impl_const_output!(Sha224: 28);
+impl_const_output!(Sha384: 48);
+impl_const_output!(Sha512_224: 28);
+impl_const_output!(Sha512_256: 32);
+
+// The const functions of SHA-512/t run on a SHA-512 context with the state
+// copied over, since an inherent impl on the generic inner type trips up the
+// const generics of downstream crates.
+impl<const T: usize> Sha512T<T>
+where
+    [(); T / 8]:,
+{
+    /// Same as [`update`](`Update::update`), but can be used in a const
+    /// context.
+    pub const fn const_update(mut self, data: &[u8]) -> Self
+    {
+        let ctx = Sha512 {
+            state:     self.inner.state,
+            block:     self.inner.block,
+            index:     self.inner.index,
+            count:     self.inner.count,
+            bits:      self.inner.bits,
+            _length:   PhantomData,
+            _iv:       PhantomData,
+            _compress: PhantomData,
+        }
+        .const_update(data);
+        self.inner.state = ctx.state;
+        self.inner.block = ctx.block;
+        self.inner.index = ctx.index;
+        self.inner.count = ctx.count;
+        self
+    }
+
+    /// Same as [`finish`](`crate::digest::Finish::finish`), but can be used in
+    /// a const context.
+    pub const fn const_finish(self) -> [u8; T / 8]
+    {
+        let inner = Sha512 {
+            state:     self.inner.state,
+            block:     self.inner.block,
+            index:     self.inner.index,
+            count:     self.inner.count,
+            bits:      self.inner.bits,
+            _length:   PhantomData,
+            _iv:       PhantomData,
+            _compress: PhantomData,
+        }
+        .const_finish();
+        let mut digest: [u8; T / 8] = [0; T / 8];
+        let mut i = 0;
+        while i < digest.len() {
+            digest[i] = inner[i];
+            i += 1;
+        }
+        digest
+    }
+
+    /// Calculate the digest of `data` in a const context.
+    pub const fn const_oneshot(data: &[u8]) -> [u8; T / 8]
+    {
+        Self::new().const_update(data).const_finish()
+    }
+}
+
+/// HASH160, which is RIPEMD-160 of the SHA-256 digest of `data`.
+///
+/// Bitcoin uses it to hash public keys and scripts for addresses.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::merkle_damgard::*;
+/// let digest = hash160(b"abc");
+/// assert_eq!(
+///     hex::encode(digest),
+///     "bb1be98c142444d7a56aa3981c3942a978e4dc33"
+/// );
+/// ```
+pub fn hash160(data: &[u8]) -> [u8; 20] { Ripemd160::oneshot(&Sha256::oneshot(data)) }
+
+/// Double SHA-256, which is SHA-256 of the SHA-256 digest of `data`.
+///
+/// Bitcoin uses it to hash blocks and transactions.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::merkle_damgard::*;
+/// let digest = sha256d(b"abc");
+/// assert_eq!(
+///     hex::encode(digest),
+///     "4f8b42c22dd3729b519ba6f68d2da7cc5b2d606d05daed5ad5128cc03e6c6358"
+/// );
+/// ```
+pub fn sha256d(data: &[u8]) -> [u8; 32] { Sha256::oneshot(&Sha256::oneshot(data)) }
+
+/// Algorithms that can be chosen at runtime with [`DynDigest`].
+///
+/// Every Merkle–Damgård digest is included except the generic [`Sha512T`],
+/// whose output length is a const generic parameter. Only SHA-512/224 and
+/// SHA-512/256 have a name and an OID, and they are included as
+/// [`Sha512_224`](`Self::Sha512_224`) and [`Sha512_256`](`Self::Sha512_256`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm
+{
+    Md4,
+    Md5,
+    Ripemd160,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha512_224,
+    Sha512_256,
+    Sm3,
+}
+
+impl Algorithm
+{
+    /// Every algorithm, in the order of declaration.
+    pub const ALL: [Self; 11] = [
+        Self::Md4,
+        Self::Md5,
+        Self::Ripemd160,
+        Self::Sha1,
+        Self::Sha224,
+        Self::Sha256,
+        Self::Sha384,
+        Self::Sha512,
+        Self::Sha512_224,
+        Self::Sha512_256,
+        Self::Sm3,
+    ];
+
+    /// Find the algorithm with the given name.
+    ///
+    /// Names are compared case-insensitively and `-`, `_` and `/` are ignored,
+    /// so `"sha512_256"`, `"SHA-512/256"` and `"sha512256"` are all accepted.
+    /// The dotted form of the OID is accepted as well.
+    pub fn from_name(name: &str) -> Option<Self>
+    {
+        fn normalize(name: &str) -> impl Iterator<Item = u8> + '_
+        {
+            name.bytes()
+                .filter(|b| !matches!(b, b'-' | b'_' | b'/'))
+                .map(|b| b.to_ascii_lowercase())
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|alg| normalize(name).eq(normalize(alg.name())) || name == alg.oid_str())
+    }
+
+    /// Find the algorithm with the given OID, which is the content of a DER
+    /// encoded `OBJECT IDENTIFIER` without the tag and length bytes.
+    pub fn from_oid(oid: &[u8]) -> Option<Self>
+    {
+        Self::ALL.into_iter().find(|alg| alg.oid() == oid)
+    }
+
+    /// Name of the algorithm as used by the standard that defines it.
+    pub const fn name(self) -> &'static str
+    {
+        match self {
+            | Self::Md4 => "MD4",
+            | Self::Md5 => "MD5",
+            | Self::Ripemd160 => "RIPEMD-160",
+            | Self::Sha1 => "SHA-1",
+            | Self::Sha224 => "SHA-224",
+            | Self::Sha256 => "SHA-256",
+            | Self::Sha384 => "SHA-384",
+            | Self::Sha512 => "SHA-512",
+            | Self::Sha512_224 => "SHA-512/224",
+            | Self::Sha512_256 => "SHA-512/256",
+            | Self::Sm3 => "SM3",
+        }
+    }
+
+    /// OID of the algorithm, which is the content of a DER encoded
+    /// `OBJECT IDENTIFIER` without the tag and length bytes.
+    pub const fn oid(self) -> &'static [u8]
+    {
+        match self {
+            | Self::Md4 => &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x04],
+            | Self::Md5 => &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x05],
+            | Self::Ripemd160 => &[0x2b, 0x24, 0x03, 0x02, 0x01],
+            | Self::Sha1 => &[0x2b, 0x0e, 0x03, 0x02, 0x1a],
+            | Self::Sha224 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04],
+            | Self::Sha256 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01],
+            | Self::Sha384 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02],
+            | Self::Sha512 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03],
+            | Self::Sha512_224 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x05],
+            | Self::Sha512_256 => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x06],
+            | Self::Sm3 => &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x83, 0x11],
+        }
+    }
+
+    /// OID of the algorithm in dotted decimal form.
+    pub const fn oid_str(self) -> &'static str
+    {
+        match self {
+            | Self::Md4 => "1.2.840.113549.2.4",
+            | Self::Md5 => "1.2.840.113549.2.5",
+            | Self::Ripemd160 => "1.3.36.3.2.1",
+            | Self::Sha1 => "1.3.14.3.2.26",
+            | Self::Sha224 => "2.16.840.1.101.3.4.2.4",
+            | Self::Sha256 => "2.16.840.1.101.3.4.2.1",
+            | Self::Sha384 => "2.16.840.1.101.3.4.2.2",
+            | Self::Sha512 => "2.16.840.1.101.3.4.2.3",
+            | Self::Sha512_224 => "2.16.840.1.101.3.4.2.5",
+            | Self::Sha512_256 => "2.16.840.1.101.3.4.2.6",
+            | Self::Sm3 => "1.2.156.10197.1.401",
+        }
+    }
+
+    /// Digest length used by the algorithm.
+    pub const fn digest_len(self) -> usize
+    {
+        match self {
+            | Self::Md4 => Md4::DIGEST_LEN,
+            | Self::Md5 => Md5::DIGEST_LEN,
+            | Self::Ripemd160 => Ripemd160::DIGEST_LEN,
+            | Self::Sha1 => Sha1::DIGEST_LEN,
+            | Self::Sha224 => Sha224::DIGEST_LEN,
+            | Self::Sha256 => Sha256::DIGEST_LEN,
+            | Self::Sha384 => Sha384::DIGEST_LEN,
+            | Self::Sha512 => Sha512::DIGEST_LEN,
+            | Self::Sha512_224 => Sha512_224::DIGEST_LEN,
+            | Self::Sha512_256 => Sha512_256::DIGEST_LEN,
+            | Self::Sm3 => Sm3::DIGEST_LEN,
+        }
+    }
+
+    /// Block length used by the algorithm.
+    pub const fn block_len(self) -> usize
+    {
+        match self {
+            | Self::Md4 => Md4::BLOCK_LEN,
+            | Self::Md5 => Md5::BLOCK_LEN,
+            | Self::Ripemd160 => Ripemd160::BLOCK_LEN,
+            | Self::Sha1 => Sha1::BLOCK_LEN,
+            | Self::Sha224 => Sha224::BLOCK_LEN,
+            | Self::Sha256 => Sha256::BLOCK_LEN,
+            | Self::Sha384 => Sha384::BLOCK_LEN,
+            | Self::Sha512 => Sha512::BLOCK_LEN,
+            | Self::Sha512_224 => Sha512_224::BLOCK_LEN,
+            | Self::Sha512_256 => Sha512_256::BLOCK_LEN,
+            | Self::Sm3 => Sm3::BLOCK_LEN,
+        }
+    }
+}
+
+impl core::fmt::Display for Algorithm
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Digest context whose algorithm is chosen at runtime.
+///
+/// The [`Digest`](`crate::digest::Digest`) trait has const generic parameters,
+/// so it can't be used as a trait object. This enum wraps the context of every
+/// [`Algorithm`] instead, and implements the digest traits that don't depend
+/// on the digest length, such as [`Update`] and
+/// [`FinishToSlice`](`crate::digest::FinishToSlice`).
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::merkle_damgard::*;
+/// let mut ctx = DynDigest::from_name("SHA-512/256").unwrap();
+/// ctx.update(b"abc");
+///
+/// let mut digest = vec![0; ctx.digest_len()];
+/// ctx.finish_to_slice(&mut digest);
+/// assert_eq!(digest, Sha512_256::oneshot(b"abc"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub enum DynDigest
+{
+    Md4(Md4),
+    Md5(Md5),
+    Ripemd160(Ripemd160),
+    Sha1(Sha1),
+    Sha224(Sha224),
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    Sha512_224(Sha512_224),
+    Sha512_256(Sha512_256),
+    Sm3(Sm3),
+}
+
+/// Calls `$f` with the context wrapped by the [`DynDigest`].
+macro_rules! dispatch {
+    ($self:expr, $ctx:ident => $f:expr) => {
+        match $self {
+            | DynDigest::Md4($ctx) => $f,
+            | DynDigest::Md5($ctx) => $f,
+            | DynDigest::Ripemd160($ctx) => $f,
+            | DynDigest::Sha1($ctx) => $f,
+            | DynDigest::Sha224($ctx) => $f,
+            | DynDigest::Sha256($ctx) => $f,
+            | DynDigest::Sha384($ctx) => $f,
+            | DynDigest::Sha512($ctx) => $f,
+            | DynDigest::Sha512_224($ctx) => $f,
+            | DynDigest::Sha512_256($ctx) => $f,
+            | DynDigest::Sm3($ctx) => $f,
+        }
+    };
+}
+
+impl DynDigest
+{
+    /// Create a context for the given algorithm.
+    pub fn new(algorithm: Algorithm) -> Self
+    {
+        match algorithm {
+            | Algorithm::Md4 => Self::Md4(Md4::new()),
+            | Algorithm::Md5 => Self::Md5(Md5::new()),
+            | Algorithm::Ripemd160 => Self::Ripemd160(Ripemd160::new()),
+            | Algorithm::Sha1 => Self::Sha1(Sha1::new()),
+            | Algorithm::Sha224 => Self::Sha224(Sha224::new()),
+            | Algorithm::Sha256 => Self::Sha256(Sha256::new()),
+            | Algorithm::Sha384 => Self::Sha384(Sha384::new()),
+            | Algorithm::Sha512 => Self::Sha512(Sha512::new()),
+            | Algorithm::Sha512_224 => Self::Sha512_224(Sha512_224::new()),
+            | Algorithm::Sha512_256 => Self::Sha512_256(Sha512_256::new()),
+            | Algorithm::Sm3 => Self::Sm3(Sm3::new()),
+        }
+    }
+
+    /// Create a context for the algorithm with the given name. See
+    /// [`Algorithm::from_name`] for the accepted names.
+    pub fn from_name(name: &str) -> Option<Self> { Algorithm::from_name(name).map(Self::new) }
+
+    /// Create a context for the algorithm with the given OID. See
+    /// [`Algorithm::from_oid`] for the expected encoding.
+    pub fn from_oid(oid: &[u8]) -> Option<Self> { Algorithm::from_oid(oid).map(Self::new) }
+
+    /// Algorithm used by the context.
+    pub fn algorithm(&self) -> Algorithm
+    {
+        match self {
+            | Self::Md4(_) => Algorithm::Md4,
+            | Self::Md5(_) => Algorithm::Md5,
+            | Self::Ripemd160(_) => Algorithm::Ripemd160,
+            | Self::Sha1(_) => Algorithm::Sha1,
+            | Self::Sha224(_) => Algorithm::Sha224,
+            | Self::Sha256(_) => Algorithm::Sha256,
+            | Self::Sha384(_) => Algorithm::Sha384,
+            | Self::Sha512(_) => Algorithm::Sha512,
+            | Self::Sha512_224(_) => Algorithm::Sha512_224,
+            | Self::Sha512_256(_) => Algorithm::Sha512_256,
+            | Self::Sm3(_) => Algorithm::Sm3,
+        }
+    }
+
+    /// Digest length used by the algorithm.
+    pub fn digest_len(&self) -> usize { self.algorithm().digest_len() }
+
+    /// Block length used by the algorithm.
+    pub fn block_len(&self) -> usize { self.algorithm().block_len() }
+}
+
+impl Reset for DynDigest
+{
+    fn reset(&mut self) { dispatch!(self, ctx => ctx.reset()) }
+}
+
+impl Update for DynDigest
+{
+    fn update(&mut self, data: &[u8]) { dispatch!(self, ctx => ctx.update(data)) }
+}
+
+impl UpdateBits for DynDigest
+{
+    fn update_bits(&mut self, data: &[u8], nbits: usize)
+    {
+        dispatch!(self, ctx => ctx.update_bits(data, nbits))
+    }
+}
+
+impl FinishInternal for DynDigest
+{
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        dispatch!(self, ctx => unsafe { ctx.finish_internal() })
+    }
+}
+
+impl Sha256
+{
+    /// Calculate the digests of many independent messages.
+    ///
+    /// `digests[i]` is set to the digest of `data[i]`. When the CPU lacks the
+    /// SHA extensions but supports AVX2 (x86) or NEON (ARM), 8 or 4 messages
+    /// are compressed in parallel, which is a lot faster than calling
+    /// [`oneshot`](`crate::digest::Oneshot::oneshot`) for each message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` and `digests` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::digest::*;
+    /// # use oxicrypt::merkle_damgard::*;
+    /// let data: [&[u8]; 3] = [b"abc", b"", &[0x42; 1000]];
+    /// let mut digests = [[0; 32]; 3];
+    /// Sha256::oneshot_many(&data, &mut digests);
+    /// assert_eq!(digests[2], Sha256::oneshot(&[0x42; 1000]));
+    /// ```
+    pub fn oneshot_many(data: &[&[u8]], digests: &mut [[u8; 32]])
+    {
+        assert_eq!(data.len(), digests.len());
+
+        // A single stream hashed with the SHA extensions is faster than the
+        // multi-buffer compression functions.
+        if Feature::Shani.is_available() || Feature::ArmSha2.is_available() {
+            sha256_many_serial(data, digests);
+        } else if Feature::Avx2.is_available() {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            unsafe {
+                sha256_many::<8>(data, digests, md_compress::sha256_x8)
+            };
+        } else if Feature::ArmNeon.is_available() {
+            #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+            unsafe {
+                sha256_many::<4>(data, digests, md_compress::sha256_x4)
+            };
+        } else {
+            sha256_many_serial(data, digests);
+        }
+    }
+}
+
+fn sha256_many_serial(data: &[&[u8]], digests: &mut [[u8; 32]])
+{
+    data.iter()
+        .zip(digests.iter_mut())
+        .for_each(|(data, digest)| *digest = Sha256::oneshot(data));
+}
+
+/// Hashes `data` using a compression function that works on `LANES` messages
+/// at a time.
+///
+/// Each lane compresses the full blocks of its message straight from `data`,
+/// followed by the padded final blocks. As soon as a lane is done, the next
+/// message is loaded into it. Once there are no messages left to fill the
+/// lanes, the remaining ones are finished one at a time.
+#[cfg(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+))]
+unsafe fn sha256_many<const LANES: usize>(
+    data: &[&[u8]],
+    digests: &mut [[u8; 32]],
+    compress: unsafe fn([*mut u32; LANES], [*const u8; LANES], usize),
+)
+{
+    let mut messages = data.iter().enumerate();
+    let mut lanes: [Sha256Lane; LANES] = [Sha256Lane::EMPTY; LANES];
+
+    let mut filled = true;
+    for lane in lanes.iter_mut() {
+        match messages.next() {
+            | Some((index, message)) => lane.load(index, message),
+            | None => filled = false,
+        }
+    }
+
+    while filled {
+        let n = lanes.iter().map(|lane| lane.blocks).min().unwrap_or(0);
+        compress(
+            core::array::from_fn(|i| lanes[i].state.as_mut_ptr()),
+            core::array::from_fn(|i| lanes[i].block),
+            n,
+        );
+
+        for lane in lanes.iter_mut() {
+            if lane.advance(n) {
+                digests[lane.index] = lane.digest();
+                match messages.next() {
+                    | Some((index, message)) => lane.load(index, message),
+                    | None => filled = false,
+                }
+            }
+        }
+    }
+
+    for lane in lanes.iter_mut().filter(|lane| lane.blocks != 0) {
+        loop {
+            let n = lane.blocks;
+            CompressSha256::compress(lane.state.as_mut_ptr(), lane.block, n);
+            if lane.advance(n) {
+                break;
+            }
+        }
+        digests[lane.index] = lane.digest();
+    }
+}
+
+/// A single message that is being hashed by [`sha256_many`].
+#[cfg(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+))]
+#[derive(Clone, Copy)]
+struct Sha256Lane
+{
+    /// Index of the message in the input.
+    index:       usize,
+    state:       [u32; 8],
+    /// Next block to be compressed.
+    block:       *const u8,
+    /// Number of blocks left starting from `block`, 0 if the lane is empty.
+    blocks:      usize,
+    /// Padded final blocks of the message.
+    tail:        [u8; 128],
+    tail_blocks: usize,
+    in_tail:     bool,
+}
+
+#[cfg(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+))]
+impl Sha256Lane
+{
+    const EMPTY: Self = Self {
+        index:       0,
+        state:       [0; 8],
+        block:       ptr::null(),
+        blocks:      0,
+        tail:        [0; 128],
+        tail_blocks: 0,
+        in_tail:     true,
+    };
+
+    /// Start hashing `message` in this lane.
+    fn load(&mut self, index: usize, message: &[u8])
+    {
+        let body = message.len() / 64 * 64;
+        let rest = &message[body..];
+
+        // Pad the final blocks the same way `MerkleDamgard::finish_` does.
+        self.tail_blocks = if rest.len() < 64 - mem::size_of::<u64>() {
+            1
+        } else {
+            2
+        };
+        let end = self.tail_blocks * 64;
+        self.tail[0..rest.len()].copy_from_slice(rest);
+        self.tail[rest.len()] = 0x80;
+        self.tail[rest.len() + 1..end - mem::size_of::<u64>()].fill(0);
+        self.tail[end - mem::size_of::<u64>()..end]
+            .copy_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+
+        self.index = index;
+        self.state = SHA_INITIAL_H256;
+        if body != 0 {
+            self.block = message.as_ptr();
+            self.blocks = body / 64;
+            self.in_tail = false;
+        } else {
+            self.block = self.tail.as_ptr();
+            self.blocks = self.tail_blocks;
+            self.in_tail = true;
+        }
+    }
+
+    /// Mark `n` blocks as compressed. Returns `true` if the whole message has
+    /// been compressed.
+    fn advance(&mut self, n: usize) -> bool
+    {
+        self.blocks -= n;
+        self.block = self.block.wrapping_add(n * 64);
+        if self.blocks == 0 {
+            if self.in_tail {
+                return true;
+            }
+            self.block = self.tail.as_ptr();
+            self.blocks = self.tail_blocks;
+            self.in_tail = true;
+        }
+        false
+    }
+
+    fn digest(&self) -> [u8; 32]
+    {
+        let mut digest = [0; 32];
+        digest
+            .chunks_exact_mut(4)
+            .zip(self.state)
+            .for_each(|(bytes, h)| bytes.copy_from_slice(&h.to_be_bytes()));
+        digest
+    }
+}
 
 // Initial state for the SHA-1 algorithm.
 #[rustfmt::skip]
@@ -452,6 +1630,58 @@ pub const SHA_INITIAL_H512_256: [u64; 8] = [
     0x0eb72ddc81c52ca2,
 ];
 
+/// Computes the initial state for the SHA-512/t algorithm, as described by
+/// the IV generation function in FIPS 180-4.
+///
+/// The state is the SHA-512 digest of the string `"SHA-512/t"`, where `t` is
+/// written in decimal, computed with every word of [`SHA_INITIAL_H512`] XORed
+/// with `0xa5a5a5a5a5a5a5a5`.
+///
+/// # Panics
+///
+/// Panics if `t` is not a multiple of 8 between 8 and 504, or if `t` is 384.
+pub const fn sha512t_initial_h(t: usize) -> [u64; 8]
+{
+    assert!(
+        t != 0 && t < 512 && t != 384 && t % 8 == 0,
+        "SHA-512/t requires a multiple of 8 below 512 other than 384"
+    );
+
+    let mut h: [u64; 8] = SHA_INITIAL_H512;
+    let mut i = 0;
+    while i < 8 {
+        h[i] ^= 0xa5a5a5a5a5a5a5a5;
+        i += 1;
+    }
+
+    // "SHA-512/" followed by at most three digits always fits in one block.
+    let mut block: [u8; 128] = [0; 128];
+    let mut len = 0;
+    while len < 8 {
+        block[len] = b"SHA-512/"[len];
+        len += 1;
+    }
+    let mut div = 100;
+    while div != 0 {
+        if t >= div || div == 1 {
+            block[len] = b'0' + (t / div % 10) as u8;
+            len += 1;
+        }
+        div /= 10;
+    }
+    block[len] = 0x80;
+
+    let bits: [u8; 16] = ((len * 8) as u128).to_be_bytes();
+    let mut i = 0;
+    while i < 16 {
+        block[112 + i] = bits[i];
+        i += 1;
+    }
+
+    unsafe { md_compress::sha512(h.as_mut_ptr(), block.as_ptr(), 1) };
+    h
+}
+
 /// Initial state of the MD5 algorithm.
 #[rustfmt::skip]
 pub const MD5_INITIAL_H: [u32; 4] = [
@@ -460,3 +1690,547 @@ pub const MD5_INITIAL_H: [u32; 4] = [
     0x98badcfe,
     0x10325476,
 ];
+
+/// Initial state of the RIPEMD-160 algorithm.
+#[rustfmt::skip]
+pub const RIPEMD160_INITIAL_H: [u32; 5] = [
+    0x67452301,
+    0xefcdab89,
+    0x98badcfe,
+    0x10325476,
+    0xc3d2e1f0,
+];
+
+/// Initial state of the SM3 algorithm.
+#[rustfmt::skip]
+pub const SM3_INITIAL_H: [u32; 8] = [
+    0x7380166f,
+    0x4914b2b9,
+    0x172442d7,
+    0xda8a0600,
+    0xa96f30bc,
+    0x163138aa,
+    0xe38dee4d,
+    0xb0fb0e4e,
+];
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::digest::Finish;
+    use crate::digest::FinishToSlice;
+
+    type Vectors<'a> = &'a [(&'a str, &'a str, usize)];
+
+    /// Hashes every message twice, once with a single call to `update_bits` and
+    /// once with the first few bytes fed by `update`.
+    fn check<T>(vectors: Vectors)
+    where
+        T: New + Update + UpdateBits + Finish,
+        [u8; T::DIGEST_LEN]:,
+    {
+        for &(digest, msg, len) in vectors {
+            let msg = hex::decode(msg).unwrap();
+
+            let mut ctx = T::new();
+            ctx.update_bits(&msg, len);
+            assert_eq!(hex::encode(ctx.finish()), digest, "len = {len}");
+
+            let split = len / 16;
+            let mut ctx = T::new();
+            ctx.update(&msg[0..split]);
+            ctx.update_bits(&msg[split..], len - split * 8);
+            assert_eq!(hex::encode(ctx.finish()), digest, "len = {len}");
+        }
+    }
+
+    /// Exports the context after every possible split of `data` and checks that
+    /// the imported context gives the same digest.
+    fn check_midstate<T>(data: &[u8], nbits: usize)
+    where
+        T: New + Update + UpdateBits + Finish + Midstate,
+        [u8; T::DIGEST_LEN]:,
+    {
+        let mut ctx = T::new();
+        ctx.update_bits(data, nbits);
+        let digest = ctx.finish();
+
+        for split in 0..=nbits / 8 {
+            let mut ctx = T::new();
+            ctx.update(&data[0..split]);
+            if split == nbits / 8 {
+                ctx.update_bits(&data[split..], nbits % 8);
+            }
+
+            let mut midstate = vec![0; T::MIDSTATE_LEN];
+            ctx.export(&mut midstate);
+            let mut ctx = T::import(&midstate).unwrap();
+            if split != nbits / 8 {
+                ctx.update_bits(&data[split..], nbits - split * 8);
+            }
+            assert_eq!(ctx.finish(), digest, "split = {split}");
+        }
+    }
+
+    #[test]
+    fn midstate()
+    {
+        let data: Vec<u8> = (0..=255).collect();
+        for nbits in [0, 7, 440, 447, 512, 1000, 1023, 2047] {
+            check_midstate::<Md4>(&data, nbits);
+            check_midstate::<Md5>(&data, nbits);
+            check_midstate::<Sha1>(&data, nbits);
+            check_midstate::<Sha224>(&data, nbits);
+            check_midstate::<Sha384>(&data, nbits);
+            check_midstate::<Sha512T<200>>(&data, nbits);
+            check_midstate::<Ripemd160>(&data, nbits);
+            check_midstate::<Sm3>(&data, nbits);
+        }
+    }
+
+    #[test]
+    fn midstate_rejects()
+    {
+        let mut ctx = Sha1::new();
+        ctx.update(&[0x42; 100]);
+        let mut midstate = [0; Sha1::MIDSTATE_LEN];
+        ctx.export(&mut midstate);
+
+        let err = |midstate: &[u8]| Sha1::import(midstate).err();
+        assert_eq!(err(&midstate), None);
+        assert_eq!(err(&midstate[1..]), Some(MidstateError::Len));
+        assert_eq!(Sha256::import(&midstate).err(), Some(MidstateError::Len));
+        assert_eq!(
+            Ripemd160::import(&midstate).err(),
+            Some(MidstateError::Algorithm)
+        );
+
+        let mut m = midstate;
+        m[0] = MIDSTATE_VERSION + 1;
+        assert_eq!(err(&m), Some(MidstateError::Version));
+
+        // The block can't be full, and the bytes after it must be zeros.
+        let mut m = midstate;
+        m[15..17].copy_from_slice(&64u16.to_be_bytes());
+        assert_eq!(err(&m), Some(MidstateError::Invalid));
+        let mut m = midstate;
+        m[Sha1::MIDSTATE_LEN - 1] = 1;
+        assert_eq!(err(&m), Some(MidstateError::Invalid));
+        let mut m = midstate;
+        m[17] = 8;
+        assert_eq!(err(&m), Some(MidstateError::Invalid));
+
+        let mut sha256 = [0; Sha256::MIDSTATE_LEN];
+        Sha256::new().export(&mut sha256);
+        assert_eq!(
+            Sha224::import(&sha256).err(),
+            Some(MidstateError::Algorithm)
+        );
+        assert_eq!(Sm3::import(&sha256).err(), Some(MidstateError::Algorithm));
+
+        // MD4 and MD5 share everything but the compression function.
+        let mut md4 = [0; Md4::MIDSTATE_LEN];
+        let mut md5 = [0; Md5::MIDSTATE_LEN];
+        Md4::new().export(&mut md4);
+        Md5::new().export(&mut md5);
+        assert_eq!(Md5::import(&md4).err(), Some(MidstateError::Algorithm));
+        assert_eq!(Md4::import(&md5).err(), Some(MidstateError::Algorithm));
+        assert!(Md4::import(&md4).is_ok());
+    }
+
+    /// Checks the const functions against the regular ones, both at compile
+    /// time and at runtime.
+    macro_rules! check_const {
+        ($($md:ty),*) => {$({
+            const DATA: [u8; 300] = {
+                let mut data: [u8; 300] = [0; 300];
+                let mut i = 0;
+                while i < data.len() {
+                    data[i] = i as u8;
+                    i += 1;
+                }
+                data
+            };
+            const DIGEST: [u8; <$md>::DIGEST_LEN] = <$md>::const_oneshot(&DATA);
+            assert_eq!(DIGEST, <$md>::oneshot(&DATA));
+
+            for len in 0..DATA.len() {
+                let (a, b) = DATA[0..len].split_at(len / 3);
+                let digest = <$md>::new().const_update(a).const_update(b).const_finish();
+                assert_eq!(digest, <$md>::oneshot(&DATA[0..len]), "len = {len}");
+            }
+        })*};
+    }
+
+    #[test]
+    fn dyn_digest()
+    {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let mut buf = [0; 64];
+        for alg in Algorithm::ALL {
+            let mut ctx = DynDigest::new(alg);
+            assert_eq!(ctx.algorithm(), alg);
+            ctx.update(data);
+            ctx.finish_to_slice(&mut buf);
+
+            let mut expected = [0; 64];
+            let n = alg.digest_len();
+            macro_rules! oneshot {
+                ($md:ty) => {{
+                    assert_eq!((n, alg.block_len()), (<$md>::DIGEST_LEN, <$md>::BLOCK_LEN));
+                    expected[0..n].copy_from_slice(&<$md>::oneshot(data));
+                }};
+            }
+            match alg {
+                | Algorithm::Md4 => oneshot!(Md4),
+                | Algorithm::Md5 => oneshot!(Md5),
+                | Algorithm::Ripemd160 => oneshot!(Ripemd160),
+                | Algorithm::Sha1 => oneshot!(Sha1),
+                | Algorithm::Sha224 => oneshot!(Sha224),
+                | Algorithm::Sha256 => oneshot!(Sha256),
+                | Algorithm::Sha384 => oneshot!(Sha384),
+                | Algorithm::Sha512 => oneshot!(Sha512),
+                | Algorithm::Sha512_224 => oneshot!(Sha512_224),
+                | Algorithm::Sha512_256 => oneshot!(Sha512_256),
+                | Algorithm::Sm3 => oneshot!(Sm3),
+            }
+            assert_eq!(buf[0..n], expected[0..n], "{alg}");
+
+            assert_eq!(Algorithm::from_name(alg.name()), Some(alg));
+            assert_eq!(Algorithm::from_name(alg.oid_str()), Some(alg));
+            assert_eq!(Algorithm::from_oid(alg.oid()), Some(alg));
+
+            // DER encoding of the dotted form
+            let arcs: Vec<u64> = alg
+                .oid_str()
+                .split('.')
+                .map(|a| a.parse().unwrap())
+                .collect();
+            let mut oid = vec![(arcs[0] * 40 + arcs[1]) as u8];
+            for &arc in &arcs[2..] {
+                let mut groups = vec![(arc & 0x7f) as u8];
+                let mut arc = arc >> 7;
+                while arc != 0 {
+                    groups.push((arc & 0x7f) as u8 | 0x80);
+                    arc >>= 7;
+                }
+                oid.extend(groups.iter().rev());
+            }
+            assert_eq!(alg.oid(), oid, "{alg}");
+        }
+
+        assert_eq!(Algorithm::from_name("sha256"), Some(Algorithm::Sha256));
+        assert_eq!(
+            Algorithm::from_name("sha512_256"),
+            Some(Algorithm::Sha512_256)
+        );
+        assert_eq!(
+            Algorithm::from_name("SHA-512/224"),
+            Some(Algorithm::Sha512_224)
+        );
+        assert_eq!(
+            Algorithm::from_name("ripemd160"),
+            Some(Algorithm::Ripemd160)
+        );
+        assert_eq!(Algorithm::from_name("sha-3"), None);
+        assert_eq!(Algorithm::from_name("sha256 "), None);
+        assert_eq!(Algorithm::from_oid(&[0x60, 0x86, 0x48]), None);
+    }
+
+    #[test]
+    fn const_fn()
+    {
+        check_const!(
+            Md4,
+            Md5,
+            Ripemd160,
+            Sha1,
+            Sha224,
+            Sha256,
+            Sha384,
+            Sha512,
+            Sha512_224,
+            Sha512_256,
+            Sha512T<192>,
+            Sm3
+        );
+    }
+
+    /// Compresses the lanes one after another, so the lane handling of
+    /// `sha256_many` is tested on every CPU.
+    #[cfg(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64"
+    ))]
+    unsafe fn compress_lanes<const LANES: usize>(
+        states: [*mut u32; LANES],
+        blocks: [*const u8; LANES],
+        n: usize,
+    )
+    {
+        for (state, block) in states.into_iter().zip(blocks) {
+            CompressSha256::compress(state, block, n);
+        }
+    }
+
+    #[test]
+    fn oneshot_many()
+    {
+        // More messages than lanes, so lanes are refilled with messages of other
+        // lengths, including empty ones and ones that span several blocks.
+        let data: Vec<Vec<u8>> = (0..40).map(|i| vec![i as u8; i * 37 % 301]).collect();
+        let data: Vec<&[u8]> = data.iter().map(Vec::as_slice).collect();
+        let expected: Vec<[u8; 32]> = data.iter().map(|m| Sha256::oneshot(m)).collect();
+
+        let mut digests = vec![[0; 32]; data.len()];
+        Sha256::oneshot_many(&data, &mut digests);
+        assert_eq!(digests, expected);
+
+        #[cfg(any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64"
+        ))]
+        {
+            let mut digests = vec![[0; 32]; data.len()];
+            unsafe { sha256_many::<4>(&data, &mut digests, compress_lanes::<4>) };
+            assert_eq!(digests, expected);
+
+            let mut digests = vec![[0; 32]; data.len()];
+            unsafe { sha256_many::<8>(&data, &mut digests, compress_lanes::<8>) };
+            assert_eq!(digests, expected);
+        }
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if Feature::Avx2.is_available() {
+            let mut digests = vec![[0; 32]; data.len()];
+            unsafe { sha256_many::<8>(&data, &mut digests, md_compress::sha256_x8) };
+            assert_eq!(digests, expected);
+        }
+
+        #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+        if Feature::ArmNeon.is_available() {
+            let mut digests = vec![[0; 32]; data.len()];
+            unsafe { sha256_many::<4>(&data, &mut digests, md_compress::sha256_x4) };
+            assert_eq!(digests, expected);
+        }
+    }
+
+    #[test]
+    fn sha1_bits()
+    {
+        check::<Sha1>(&include!(env!("OXI_CAVP_sha1_test_vectors.txt")));
+        check::<Sha1>(&include!(env!("OXI_TEST_sha1-bits.txt")));
+    }
+
+    #[test]
+    fn sha224_bits()
+    {
+        check::<Sha224>(&include!(env!("OXI_CAVP_sha224_test_vectors.txt")));
+        check::<Sha224>(&include!(env!("OXI_TEST_sha224-bits.txt")));
+    }
+
+    #[test]
+    fn sha256_bits()
+    {
+        check::<Sha256>(&include!(env!("OXI_CAVP_sha256_test_vectors.txt")));
+        check::<Sha256>(&include!(env!("OXI_TEST_sha256-bits.txt")));
+    }
+
+    #[test]
+    fn sha384_bits()
+    {
+        check::<Sha384>(&include!(env!("OXI_CAVP_sha384_test_vectors.txt")));
+        check::<Sha384>(&include!(env!("OXI_TEST_sha384-bits.txt")));
+    }
+
+    #[test]
+    fn sha512_bits()
+    {
+        check::<Sha512>(&include!(env!("OXI_CAVP_sha512_test_vectors.txt")));
+        check::<Sha512>(&include!(env!("OXI_TEST_sha512-bits.txt")));
+    }
+
+    #[test]
+    fn sha512_224_bits()
+    {
+        check::<Sha512_224>(&include!(env!("OXI_CAVP_sha512_224_test_vectors.txt")));
+        check::<Sha512_224>(&include!(env!("OXI_TEST_sha512-224-bits.txt")));
+    }
+
+    #[test]
+    fn sha512_256_bits()
+    {
+        check::<Sha512_256>(&include!(env!("OXI_CAVP_sha512_256_test_vectors.txt")));
+        check::<Sha512_256>(&include!(env!("OXI_TEST_sha512-256-bits.txt")));
+    }
+
+    #[test]
+    fn sha512t_iv()
+    {
+        const IV_224: [u64; 8] = sha512t_initial_h(224);
+        const IV_256: [u64; 8] = sha512t_initial_h(256);
+        assert_eq!(IV_224, IvSha512_224::initial());
+        assert_eq!(IV_256, IvSha512_256::initial());
+        assert_eq!(IvSha512T::<224>::initial(), SHA_INITIAL_H512_224);
+        assert_eq!(IvSha512T::<256>::initial(), SHA_INITIAL_H512_256);
+    }
+
+    #[test]
+    #[should_panic = "SHA-512/t requires a multiple of 8 below 512 other than 384"]
+    fn sha512t_invalid()
+    {
+        let t = core::hint::black_box(100);
+        sha512t_initial_h(t);
+    }
+
+    /// Parses the digests, messages and lengths of a CAVP response file.
+    fn parse_rsp(rsp: &str) -> Vec<(&str, &str, usize)>
+    {
+        let mut vectors = Vec::new();
+        let mut len: usize = 0;
+        let mut msg: &str = "";
+        for line in rsp.lines().map(str::trim) {
+            if let Some(value) = line.strip_prefix("Len = ") {
+                len = value.parse().unwrap();
+            } else if let Some(value) = line.strip_prefix("Msg = ") {
+                msg = value;
+            } else if let Some(value) = line.strip_prefix("MD = ") {
+                vectors.push((value, msg, len));
+            }
+        }
+        vectors
+    }
+
+    /// Bit-oriented response files of the CAVP, from `shabittestvectors.zip`,
+    /// which are expected in `test-vectors/cavp/bits`.
+    #[test]
+    #[cfg_attr(
+        not(oxi_cavp_bits),
+        ignore = "the bit-oriented CAVP files are not in test-vectors/cavp/bits"
+    )]
+    fn cavp_bits()
+    {
+        #[cfg(oxi_cavp_bits)]
+        {
+            macro_rules! check_rsp {
+                ($md:ty, $($file:literal),*) => {$({
+                    let vectors = parse_rsp(include_str!(env!(concat!("OXI_CAVP_BITS_", $file))));
+                    assert!(vectors.iter().any(|&(_, _, len)| len % 8 != 0), $file);
+                    check::<$md>(&vectors);
+                })*};
+            }
+            check_rsp!(Sha1, "SHA1ShortMsg.rsp", "SHA1LongMsg.rsp");
+            check_rsp!(Sha224, "SHA224ShortMsg.rsp", "SHA224LongMsg.rsp");
+            check_rsp!(Sha256, "SHA256ShortMsg.rsp", "SHA256LongMsg.rsp");
+            check_rsp!(Sha384, "SHA384ShortMsg.rsp", "SHA384LongMsg.rsp");
+            check_rsp!(Sha512, "SHA512ShortMsg.rsp", "SHA512LongMsg.rsp");
+        }
+    }
+
+    #[test]
+    fn parse_rsp_format()
+    {
+        let rsp = "#  CAVS 11.0\n#  \"SHA-1 ShortMsg\" information\n\n[L = 20]\n\nLen = 1\nMsg = \
+                   00\nMD = bb6b3e18f0115b57925241676f5b1ae88747b08a\n\nLen = 2\nMsg = 40\nMD = \
+                   ec6b39952e1a3ec3ab3507185cf756181c84bbe2\n";
+        let vectors = parse_rsp(rsp);
+        assert_eq!(
+            vectors,
+            [
+                ("bb6b3e18f0115b57925241676f5b1ae88747b08a", "00", 1),
+                ("ec6b39952e1a3ec3ab3507185cf756181c84bbe2", "40", 2),
+            ]
+        );
+        check::<Sha1>(&vectors);
+    }
+
+    /// Bit-oriented examples published by NIST, which are also used by the
+    /// test suite of RFC 6234, and the first two messages of the bit-oriented
+    /// SHA-1 CAVP file.
+    #[test]
+    fn nist_bits()
+    {
+        check::<Sha1>(&[
+            ("bb6b3e18f0115b57925241676f5b1ae88747b08a", "00", 1),
+            ("ec6b39952e1a3ec3ab3507185cf756181c84bbe2", "40", 2),
+            ("29826b003b906e660eff4027ce98af3531ac75ba", "98", 5),
+            (
+                "6239781e03729919c01955b3ffa8acb60b988340",
+                "49b2aec2594bbe3a3b117542d94ac880",
+                123,
+            ),
+        ]);
+        check::<Sha224>(&[
+            (
+                "e3b048552c3c387bcab37f6eb06bb79b96a4aee5ff27f51531a9551c",
+                "68",
+                5,
+            ),
+            (
+                "1b01db6cb4a9e43ded1516beb3db0b87b6d1ea43187462c608137150",
+                "f07006f25a0bea68cd76a29587c28da0",
+                123,
+            ),
+        ]);
+        check::<Sha256>(&[
+            (
+                "d6d3e02a31a84a8caa9718ed6c2057be09db45e7823eb5079ce7a573a3760f95",
+                "68",
+                5,
+            ),
+            (
+                "77ec1dc89c821ff2a1279089fa091b35b8cd960bcaf7de01c6a7680756beb972",
+                "be2746c6db52765fdb2f88700f9a7360",
+                123,
+            ),
+        ]);
+        check::<Sha384>(&[(
+            "8d17be79e32b6718e07d8a603eb84ba0478f7fcfd1bb93995f7d1149e09143ac\
+             1ffcfc56820e469f3878d957a15a3fe4",
+            "10",
+            5,
+        )]);
+        check::<Sha512>(&[(
+            "d4ee29a9e90985446b913cf1d1376c836f4be2c1cf3cada0720a6bf4857d886a\
+             7ecb3c4e4c0fa8c7f95214e41dc1b0d21b22a84cc03bf8ce4845f34dd5bdbad4",
+            "b0",
+            5,
+        )]);
+    }
+
+    #[test]
+    #[should_panic = "cannot update after a partial byte"]
+    fn update_after_partial_byte()
+    {
+        let mut ctx = Sha256::new();
+        ctx.update_bits(&[0x68], 5);
+        ctx.update(b"abc");
+    }
+
+    /// Examples from GB/T 32905-2016, appendix A.
+    #[test]
+    fn sm3()
+    {
+        check::<Sm3>(&[
+            (
+                "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0",
+                "616263",
+                24,
+            ),
+            (
+                "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732",
+                "6162636461626364616263646162636461626364616263646162636461626364\
+                 6162636461626364616263646162636461626364616263646162636461626364",
+                512,
+            ),
+        ]);
+    }
+}
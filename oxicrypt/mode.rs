@@ -0,0 +1,693 @@
+//! Block cipher modes of operation.
+//!
+//! The modes work with any cipher that implements [`BlockCipher`], which
+//! includes [`aes::Key`] and [`sm4::Key`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use oxicrypt::aes::*;
+//! # use oxicrypt::mode::*;
+//! let key = Key128::with_encrypt_key(&[0x42; 16]).unwrap();
+//! let gcm = Gcm::new(key);
+//! let nonce = [0x24; 12];
+//!
+//! let mut data = *b"attack at dawn";
+//! let tag = gcm.encrypt(&nonce, b"header", &mut data).unwrap();
+//! assert_ne!(&data, b"attack at dawn");
+//!
+//! gcm.decrypt(&nonce, b"header", &mut data, &tag).unwrap();
+//! assert_eq!(&data, b"attack at dawn");
+//! ```
+
+use core::fmt::Display;
+
+use crate::aes;
+use crate::aes::LenError;
+use crate::aes::Variant;
+use crate::ct;
+use crate::sm4;
+
+/// Number of blocks that are handed to the cipher at once by the modes that
+/// can process blocks independently.
+const BATCH: usize = 8;
+
+/// Block cipher with a block size of 16 bytes.
+pub trait BlockCipher
+{
+    /// Encrypts a single block in-place.
+    fn encrypt_block(&self, block: &mut [u8; 16]);
+
+    /// Decrypts a single block in-place.
+    fn decrypt_block(&self, block: &mut [u8; 16]);
+
+    /// Encrypts the given blocks in-place.
+    fn encrypt_chunks(&self, blocks: &mut [[u8; 16]])
+    {
+        blocks
+            .iter_mut()
+            .for_each(|block| self.encrypt_block(block));
+    }
+
+    /// Decrypts the given blocks in-place.
+    fn decrypt_chunks(&self, blocks: &mut [[u8; 16]])
+    {
+        blocks
+            .iter_mut()
+            .for_each(|block| self.decrypt_block(block));
+    }
+}
+
+impl<const V: Variant> BlockCipher for aes::Key<V>
+where
+    [(); Variant::key_sched_len(V)]:,
+{
+    fn encrypt_block(&self, block: &mut [u8; 16]) { aes::Key::encrypt_block(self, block) }
+
+    fn decrypt_block(&self, block: &mut [u8; 16]) { aes::Key::decrypt_block(self, block) }
+
+    fn encrypt_chunks(&self, blocks: &mut [[u8; 16]]) { aes::Key::encrypt_chunks(self, blocks) }
+
+    fn decrypt_chunks(&self, blocks: &mut [[u8; 16]]) { aes::Key::decrypt_chunks(self, blocks) }
+}
+
+impl BlockCipher for sm4::Key
+{
+    fn encrypt_block(&self, block: &mut [u8; 16]) { sm4::Key::encrypt_block(self, block) }
+
+    fn decrypt_block(&self, block: &mut [u8; 16]) { sm4::Key::decrypt_block(self, block) }
+
+    fn encrypt_chunks(&self, blocks: &mut [[u8; 16]]) { sm4::Key::encrypt_chunks(self, blocks) }
+
+    fn decrypt_chunks(&self, blocks: &mut [[u8; 16]]) { sm4::Key::decrypt_chunks(self, blocks) }
+}
+
+/// Splits `data` into blocks, failing when its length is not a multiple of 16.
+fn blocks_mut(data: &mut [u8]) -> Result<&mut [[u8; 16]], LenError>
+{
+    let (blocks, rest) = data.as_chunks_mut::<16>();
+    if !rest.is_empty() {
+        let len = blocks.len() * 16 + rest.len();
+        return Err(LenError::new("data", len / 16 * 16, len));
+    }
+    Ok(blocks)
+}
+
+/// XORs `src` into `dst`.
+#[inline(always)]
+fn xor(dst: &mut [u8], src: &[u8]) { dst.iter_mut().zip(src).for_each(|(d, s)| *d ^= s) }
+
+/// Encrypts `data` in-place using the ECB mode.
+///
+/// ECB encrypts equal blocks to equal ciphertexts, so it leaks patterns of the
+/// plaintext. Prefer [`Gcm`] unless a protocol requires ECB.
+///
+/// Returns an [`Err`](`Result::Err`) when the length of `data` is not a
+/// multiple of 16.
+pub fn ecb_encrypt<C: BlockCipher>(cipher: &C, data: &mut [u8]) -> Result<(), LenError>
+{
+    cipher.encrypt_chunks(blocks_mut(data)?);
+    Ok(())
+}
+
+/// Decrypts `data` in-place using the ECB mode.
+///
+/// `cipher` must be a decryption key.
+///
+/// Returns an [`Err`](`Result::Err`) when the length of `data` is not a
+/// multiple of 16.
+pub fn ecb_decrypt<C: BlockCipher>(cipher: &C, data: &mut [u8]) -> Result<(), LenError>
+{
+    cipher.decrypt_chunks(blocks_mut(data)?);
+    Ok(())
+}
+
+/// Encrypts `data` in-place using the CBC mode.
+///
+/// `iv` must be unpredictable and must never be reused with the same key.
+///
+/// Returns an [`Err`](`Result::Err`) when the length of `data` is not a
+/// multiple of 16.
+pub fn cbc_encrypt<C: BlockCipher>(
+    cipher: &C,
+    iv: &[u8; 16],
+    data: &mut [u8],
+) -> Result<(), LenError>
+{
+    let mut prev: [u8; 16] = *iv;
+    for block in blocks_mut(data)? {
+        xor(block, &prev);
+        cipher.encrypt_block(block);
+        prev = *block;
+    }
+    Ok(())
+}
+
+/// Decrypts `data` in-place using the CBC mode.
+///
+/// `cipher` must be a decryption key.
+///
+/// Returns an [`Err`](`Result::Err`) when the length of `data` is not a
+/// multiple of 16.
+pub fn cbc_decrypt<C: BlockCipher>(
+    cipher: &C,
+    iv: &[u8; 16],
+    data: &mut [u8],
+) -> Result<(), LenError>
+{
+    let mut prev: [u8; 16] = *iv;
+    let mut saved: [[u8; 16]; BATCH] = [[0; 16]; BATCH];
+    for batch in blocks_mut(data)?.chunks_mut(BATCH) {
+        // Unlike encryption, the blocks can be decrypted independently, as long
+        // as the ciphertext is kept around for the XOR.
+        let saved = &mut saved[..batch.len()];
+        saved.copy_from_slice(batch);
+        cipher.decrypt_chunks(batch);
+        for (block, ciphertext) in batch.iter_mut().zip(saved.iter()) {
+            xor(block, &prev);
+            prev = *ciphertext;
+        }
+    }
+    Ok(())
+}
+
+/// Encrypts or decrypts `data` in-place using the CTR mode.
+///
+/// The whole 16-byte `counter` is incremented as a big-endian integer for
+/// every block. A counter value must never be reused with the same key. Since
+/// CTR turns the cipher into a stream cipher, `data` can have any length and
+/// `cipher` must be an encryption key in both directions.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::mode::*;
+/// # use oxicrypt::sm4::*;
+/// let key = Key::with_encrypt_key(&[0x42; 16]).unwrap();
+/// let counter = [0x24; 16];
+///
+/// let mut data = *b"attack at dawn";
+/// ctr(&key, &counter, &mut data);
+/// ctr(&key, &counter, &mut data);
+/// assert_eq!(&data, b"attack at dawn");
+/// ```
+pub fn ctr<C: BlockCipher>(cipher: &C, counter: &[u8; 16], data: &mut [u8])
+{
+    ctr_masked(cipher, u128::from_be_bytes(*counter), u128::MAX, data);
+}
+
+/// Same as [`ctr`], but only the bits of the counter that are set in `mask`
+/// are incremented, wrapping around within them.
+fn ctr_masked<C: BlockCipher>(cipher: &C, mut counter: u128, mask: u128, data: &mut [u8])
+{
+    let mut keystream: [[u8; 16]; BATCH] = [[0; 16]; BATCH];
+    for chunk in data.chunks_mut(BATCH * 16) {
+        let n = (chunk.len() + 15) / 16;
+        for block in &mut keystream[..n] {
+            *block = counter.to_be_bytes();
+            counter = (counter & !mask) | (counter.wrapping_add(1) & mask);
+        }
+        cipher.encrypt_chunks(&mut keystream[..n]);
+        for (bytes, block) in chunk.chunks_mut(16).zip(keystream.iter()) {
+            xor(bytes, block);
+        }
+    }
+}
+
+/// GCM authenticated encryption.
+///
+/// The cipher must be an encryption key, since GCM only ever encrypts blocks.
+/// Nonces should be 12 bytes long, and a nonce must never be reused with the
+/// same key.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::aes::*;
+/// # use oxicrypt::mode::*;
+/// let gcm = Gcm::new(Key128::with_encrypt_key(&[0; 16]).unwrap());
+///
+/// let mut data = [0; 16];
+/// let tag = gcm.encrypt(&[0; 12], &[], &mut data).unwrap();
+/// assert_eq!(hex::encode(data), "0388dace60b6a392f328c2b971b2fe78");
+/// assert_eq!(hex::encode(tag), "ab6e47d42cec13bdf53a67b21257bddf");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gcm<C: BlockCipher>
+{
+    cipher: C,
+    h:      u128,
+}
+
+impl<C: BlockCipher> Gcm<C>
+{
+    /// Maximum length of the additional data in bytes.
+    pub const MAX_AAD_LEN: u64 = (1 << 61) - 1;
+    /// Maximum length of the data in bytes. The counter is 32 bits long and
+    /// its first two values are used by the tag and `J0`, so longer data would
+    /// reuse the keystream.
+    pub const MAX_DATA_LEN: u64 = ((1 << 32) - 2) * 16;
+    /// Tag size in bytes.
+    pub const TAG_LEN: usize = 16;
+
+    /// Creates a GCM context from an encryption key.
+    pub fn new(cipher: C) -> Self
+    {
+        let mut h: [u8; 16] = [0; 16];
+        cipher.encrypt_block(&mut h);
+        Self {
+            cipher,
+            h: u128::from_be_bytes(h),
+        }
+    }
+
+    /// Encrypts `data` in-place and returns the authentication tag, which also
+    /// covers the additional data `aad`.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when `data` is longer than
+    /// [`MAX_DATA_LEN`](`Self::MAX_DATA_LEN`) or `aad` is longer than
+    /// [`MAX_AAD_LEN`](`Self::MAX_AAD_LEN`), in which case `data` is left
+    /// untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nonce` is empty.
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], data: &mut [u8]) -> Result<[u8; 16], GcmError>
+    {
+        Self::check_len(aad.len(), data.len())?;
+        let j0: u128 = self.j0(nonce);
+        ctr_masked(&self.cipher, j0.wrapping_add(1), u32::MAX as u128, data);
+        Ok(self.tag(j0, aad, data))
+    }
+
+    /// Verifies the authentication tag and decrypts `data` in-place.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when `tag` does not match or the
+    /// lengths are out of range like for [`encrypt`](`Self::encrypt`), in
+    /// which case `data` is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nonce` is empty.
+    pub fn decrypt(
+        &self,
+        nonce: &[u8],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; 16],
+    ) -> Result<(), GcmError>
+    {
+        Self::check_len(aad.len(), data.len())?;
+        let j0: u128 = self.j0(nonce);
+        if !ct::eq(&self.tag(j0, aad, data), tag) {
+            return Err(GcmError::Tag);
+        }
+        ctr_masked(&self.cipher, j0.wrapping_add(1), u32::MAX as u128, data);
+        Ok(())
+    }
+
+    /// Checks the lengths of the additional data and the data.
+    fn check_len(aad_len: usize, data_len: usize) -> Result<(), GcmError>
+    {
+        if data_len as u64 > Self::MAX_DATA_LEN {
+            return Err(GcmError::DataLen {
+                at_most: Self::MAX_DATA_LEN,
+                got:     data_len as u64,
+            });
+        }
+        if aad_len as u64 > Self::MAX_AAD_LEN {
+            return Err(GcmError::AadLen {
+                at_most: Self::MAX_AAD_LEN,
+                got:     aad_len as u64,
+            });
+        }
+        Ok(())
+    }
+
+    /// Derives the initial counter block from the nonce.
+    fn j0(&self, nonce: &[u8]) -> u128
+    {
+        // GCM requires nonces of at least one bit.
+        assert!(!nonce.is_empty(), "nonce must not be empty");
+        if nonce.len() == 12 {
+            let mut j0: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+            j0[..12].copy_from_slice(nonce);
+            u128::from_be_bytes(j0)
+        } else {
+            let mut y: u128 = 0;
+            self.ghash(&mut y, nonce);
+            self.ghash_block(&mut y, (nonce.len() as u128) * 8);
+            y
+        }
+    }
+
+    /// Computes the tag over the additional data and the ciphertext.
+    fn tag(&self, j0: u128, aad: &[u8], ciphertext: &[u8]) -> [u8; 16]
+    {
+        let mut y: u128 = 0;
+        self.ghash(&mut y, aad);
+        self.ghash(&mut y, ciphertext);
+        self.ghash_block(
+            &mut y,
+            ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8),
+        );
+
+        let mut tag: [u8; 16] = j0.to_be_bytes();
+        self.cipher.encrypt_block(&mut tag);
+        (u128::from_be_bytes(tag) ^ y).to_be_bytes()
+    }
+
+    /// Absorbs `data` into the GHASH state `y`, padding it with zeros to a
+    /// multiple of 16 bytes.
+    fn ghash(&self, y: &mut u128, data: &[u8])
+    {
+        let (blocks, rest) = data.as_chunks::<16>();
+        for block in blocks {
+            self.ghash_block(y, u128::from_be_bytes(*block));
+        }
+        if !rest.is_empty() {
+            let mut block: [u8; 16] = [0; 16];
+            block[..rest.len()].copy_from_slice(rest);
+            self.ghash_block(y, u128::from_be_bytes(block));
+        }
+    }
+
+    #[inline(always)]
+    fn ghash_block(&self, y: &mut u128, block: u128) { *y = gf_mul(*y ^ block, self.h) }
+}
+
+/// Multiplication in GF(2^128) as defined by GCM, where the most significant
+/// bit holds the coefficient of x^0.
+///
+/// It does not branch on or index memory with any of its inputs.
+fn gf_mul(x: u128, h: u128) -> u128
+{
+    let mut z: u128 = 0;
+    let mut v: u128 = h;
+    for i in (0..128).rev() {
+        z ^= v & ((x >> i) & 1).wrapping_neg();
+        v = (v >> 1) ^ (0xe1 << 120 & (v & 1).wrapping_neg());
+    }
+    z
+}
+
+/// Error type for GCM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GcmError
+{
+    /// The authentication tag does not match.
+    Tag,
+    /// The data is too long.
+    DataLen
+    {
+        at_most: u64, got: u64
+    },
+    /// The additional data is too long.
+    AadLen
+    {
+        at_most: u64, got: u64
+    },
+}
+
+impl Display for GcmError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | GcmError::Tag => write!(f, "Authentication tag did not match"),
+            | GcmError::DataLen { at_most, got } => write!(
+                f,
+                "Length of `data` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+            | GcmError::AadLen { at_most, got } => write!(
+                f,
+                "Length of `aad` is expected to be at most {}, but got {} instead",
+                at_most, got
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for GcmError {}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::aes::Key128;
+    use crate::sm4;
+
+    /// Plaintext of the SP 800-38A examples.
+    const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    fn sp800_38a_keys() -> (Key128, Key128)
+    {
+        let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        (
+            Key128::with_encrypt_key(&key).unwrap(),
+            Key128::with_decrypt_key(&key).unwrap(),
+        )
+    }
+
+    /// SP 800-38A, F.1.1 and F.1.2
+    #[test]
+    fn ecb_aes128()
+    {
+        let (encrypt_key, decrypt_key) = sp800_38a_keys();
+        let mut data = hex::decode(PLAINTEXT).unwrap();
+        ecb_encrypt(&encrypt_key, &mut data).unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf\
+             43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4"
+        );
+        ecb_decrypt(&decrypt_key, &mut data).unwrap();
+        assert_eq!(hex::encode(&data), PLAINTEXT);
+
+        assert!(ecb_encrypt(&encrypt_key, &mut data[0..17]).is_err());
+    }
+
+    /// SP 800-38A, F.2.1 and F.2.2
+    #[test]
+    fn cbc_aes128()
+    {
+        let (encrypt_key, decrypt_key) = sp800_38a_keys();
+        let iv: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let mut data = hex::decode(PLAINTEXT).unwrap();
+        cbc_encrypt(&encrypt_key, &iv, &mut data).unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+             73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7"
+        );
+        cbc_decrypt(&decrypt_key, &iv, &mut data).unwrap();
+        assert_eq!(hex::encode(&data), PLAINTEXT);
+    }
+
+    /// SP 800-38A, F.5.1 and F.5.2
+    #[test]
+    fn ctr_aes128()
+    {
+        let (key, _) = sp800_38a_keys();
+        let counter: [u8; 16] = core::array::from_fn(|i| 0xf0 + i as u8);
+        let mut data = hex::decode(PLAINTEXT).unwrap();
+        ctr(&key, &counter, &mut data);
+        assert_eq!(
+            hex::encode(&data),
+            "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee"
+        );
+        ctr(&key, &counter, &mut data);
+        assert_eq!(hex::encode(&data), PLAINTEXT);
+    }
+
+    /// Key, IV and plaintext of the SM4 examples in draft-ribose-cfrg-sm4.
+    fn sm4_example() -> (sm4::Key, sm4::Key, [u8; 16], Vec<u8>)
+    {
+        let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+        (
+            sm4::Key::with_encrypt_key(&key).unwrap(),
+            sm4::Key::with_decrypt_key(&key).unwrap(),
+            core::array::from_fn(|i| i as u8),
+            hex::decode("aaaaaaaabbbbbbbbccccccccddddddddeeeeeeeeffffffffaaaaaaaabbbbbbbb")
+                .unwrap(),
+        )
+    }
+
+    /// CBC example of draft-ribose-cfrg-sm4
+    #[test]
+    fn cbc_sm4()
+    {
+        let (encrypt_key, decrypt_key, iv, plaintext) = sm4_example();
+        let mut data = plaintext.clone();
+        cbc_encrypt(&encrypt_key, &iv, &mut data).unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "78ebb11cc40b0a48312aaeb2040244cb4cb7016951909226979b0d15dc6a8f6d"
+        );
+        cbc_decrypt(&decrypt_key, &iv, &mut data).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    /// CTR example of draft-ribose-cfrg-sm4
+    #[test]
+    fn ctr_sm4()
+    {
+        let (key, _, counter, plaintext) = sm4_example();
+        let mut data = plaintext.clone();
+        ctr(&key, &counter, &mut data);
+        assert_eq!(
+            hex::encode(&data),
+            "ac3236cb861dd316e6413b4e3c7524b781e9e3a5bf5c03fe703bb94f3abb16a1"
+        );
+        ctr(&key, &counter, &mut data);
+        assert_eq!(data, plaintext);
+    }
+
+    /// RFC 8998, A.1
+    #[test]
+    fn gcm_sm4()
+    {
+        let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+        let gcm = Gcm::new(sm4::Key::with_encrypt_key(&key).unwrap());
+        let nonce = hex::decode("00001234567800000000abcd").unwrap();
+        let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let plaintext = hex::decode(
+            "aaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbccccccccccccccccdddddddddddddddd\
+             eeeeeeeeeeeeeeeeffffffffffffffffeeeeeeeeeeeeeeeeaaaaaaaaaaaaaaaa",
+        )
+        .unwrap();
+
+        let mut data = plaintext.clone();
+        let tag = gcm.encrypt(&nonce, &aad, &mut data).unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "17f399f08c67d5ee19d0dc9969c4bb7d5fd46fd3756489069157b282bb200735\
+             d82710ca5c22f0ccfa7cbf93d496ac15a56834cbcf98c397b4024a2691233b8d"
+        );
+        assert_eq!(hex::encode(tag), "83de3541e4c2b58177e065a9bf7b62ec");
+
+        gcm.decrypt(&nonce, &aad, &mut data, &tag).unwrap();
+        assert_eq!(data, plaintext);
+    }
+
+    /// Test cases 4, 5 and 6 from the GCM specification, which are also used
+    /// by SP 800-38D. They have additional data and nonces of 12, 8 and 60
+    /// bytes.
+    #[test]
+    fn gcm_aes128()
+    {
+        let key = hex::decode("feffe9928665731c6d6a8f9467308308").unwrap();
+        let gcm = Gcm::new(Key128::with_encrypt_key(&key).unwrap());
+        let plaintext = hex::decode(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        )
+        .unwrap();
+        let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let tests = [
+            (
+                "cafebabefacedbaddecaf888",
+                "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+                 21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+                "5bc94fbc3221a5db94fae95ae7121a47",
+            ),
+            (
+                "cafebabefacedbad",
+                "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c7423\
+                 73806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598",
+                "3612d2e79e3b0785561be14aaca2fccb",
+            ),
+            (
+                "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728\
+                 c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+                "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca7\
+                 01e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5",
+                "619cc5aefffe0bfa462af43c1699d050",
+            ),
+        ];
+
+        for (nonce, ciphertext, tag) in tests {
+            let nonce = hex::decode(nonce).unwrap();
+            let mut data = plaintext.clone();
+            let computed_tag = gcm.encrypt(&nonce, &aad, &mut data).unwrap();
+            assert_eq!(hex::encode(&data), ciphertext);
+            assert_eq!(hex::encode(computed_tag), tag);
+
+            gcm.decrypt(&nonce, &aad, &mut data, &computed_tag).unwrap();
+            assert_eq!(data, plaintext);
+        }
+    }
+
+    #[test]
+    fn gcm_tag_mismatch()
+    {
+        let gcm = Gcm::new(Key128::with_encrypt_key(&[0x42; 16]).unwrap());
+        let nonce = [0x24; 12];
+        let mut data = *b"attack at dawn";
+        let tag = gcm.encrypt(&nonce, b"header", &mut data).unwrap();
+        let ciphertext = data;
+
+        let mut bad_tag = tag;
+        bad_tag[15] ^= 1;
+        assert_eq!(
+            gcm.decrypt(&nonce, b"header", &mut data, &bad_tag),
+            Err(GcmError::Tag)
+        );
+        assert_eq!(
+            gcm.decrypt(&nonce, b"Header", &mut data, &tag),
+            Err(GcmError::Tag)
+        );
+        assert_eq!(
+            gcm.decrypt(&[0x24; 13], b"header", &mut data, &tag),
+            Err(GcmError::Tag)
+        );
+        assert_eq!(data, ciphertext);
+
+        data[0] ^= 1;
+        assert_eq!(
+            gcm.decrypt(&nonce, b"header", &mut data, &tag),
+            Err(GcmError::Tag)
+        );
+    }
+
+    #[test]
+    #[should_panic = "nonce must not be empty"]
+    fn gcm_empty_nonce()
+    {
+        let gcm = Gcm::new(Key128::with_encrypt_key(&[0x42; 16]).unwrap());
+        let _ = gcm.encrypt(&[], b"header", &mut [0; 16]);
+    }
+
+    /// The limits can't be reached in a test, so only the check is tested.
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn gcm_len()
+    {
+        type Gcm128 = Gcm<Key128>;
+        let max_data = Gcm128::MAX_DATA_LEN as usize;
+        let max_aad = Gcm128::MAX_AAD_LEN as usize;
+        assert_eq!(max_data, (u32::MAX as usize - 1) * 16);
+        assert_eq!(Gcm128::check_len(max_aad, max_data), Ok(()));
+        assert_eq!(
+            Gcm128::check_len(0, max_data + 1),
+            Err(GcmError::DataLen {
+                at_most: max_data as u64,
+                got:     max_data as u64 + 1,
+            })
+        );
+        assert_eq!(
+            Gcm128::check_len(max_aad + 1, 0),
+            Err(GcmError::AadLen {
+                at_most: max_aad as u64,
+                got:     max_aad as u64 + 1,
+            })
+        );
+    }
+}
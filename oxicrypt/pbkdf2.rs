@@ -0,0 +1,290 @@
+//! # [PBKDF2]
+//!
+//! Password-based key derivation function 2 from RFC 8018, using HMAC as the
+//! pseudorandom function. The derived key is made of blocks as long as the
+//! digest, and each block takes `rounds` HMAC calculations, which is what makes
+//! guessing passwords expensive.
+//!
+//! ```
+//! use oxicrypt::pbkdf2::*;
+//!
+//! let mut key = [0; 32];
+//! Pbkdf2Sha256::oneshot(b"password", b"salt", 4096, &mut key).unwrap();
+//! ```
+//!
+//! [PBKDF2]: https://www.rfc-editor.org/rfc/rfc8018#section-5.2
+
+use core::fmt::Display;
+
+use crate::digest::Digest;
+use crate::hmac::Hmac;
+use crate::hmac::HmacKey;
+use crate::merkle_damgard;
+
+/// PBKDF2 with HMAC-X.
+///
+/// The context holds the password as an [`HmacKey`], so deriving keys from the
+/// same password with different salts doesn't have to process the password
+/// again.
+#[derive(Debug, Clone, Copy)]
+pub struct Pbkdf2<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    key: HmacKey<D>,
+}
+
+/// PBKDF2-HMAC-MD5
+pub type Pbkdf2Md5 = Pbkdf2<merkle_damgard::Md5>;
+/// PBKDF2-HMAC-SHA-1
+pub type Pbkdf2Sha1 = Pbkdf2<merkle_damgard::Sha1>;
+/// PBKDF2-HMAC-SHA-224
+pub type Pbkdf2Sha224 = Pbkdf2<merkle_damgard::Sha224>;
+/// PBKDF2-HMAC-SHA-256
+pub type Pbkdf2Sha256 = Pbkdf2<merkle_damgard::Sha256>;
+/// PBKDF2-HMAC-SHA-384
+pub type Pbkdf2Sha384 = Pbkdf2<merkle_damgard::Sha384>;
+/// PBKDF2-HMAC-SHA-512
+pub type Pbkdf2Sha512 = Pbkdf2<merkle_damgard::Sha512>;
+/// PBKDF2-HMAC-SHA-512/224
+pub type Pbkdf2Sha512_224 = Pbkdf2<merkle_damgard::Sha512_224>;
+/// PBKDF2-HMAC-SHA-512/256
+pub type Pbkdf2Sha512_256 = Pbkdf2<merkle_damgard::Sha512_256>;
+
+impl<D> Pbkdf2<D>
+where
+    D: Digest + Copy,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    /// Create a new context using the password.
+    pub fn with_password(password: &[u8]) -> Self
+    {
+        Self {
+            key: HmacKey::new(password),
+        }
+    }
+
+    /// Derive a key from the password and `salt`, filling `dk`.
+    ///
+    /// `dk` may have any length up to `(2^32 - 1) * DIGEST_LEN` bytes, but
+    /// every block of `DIGEST_LEN` bytes costs `rounds` HMAC calculations.
+    pub fn derive(&self, salt: &[u8], rounds: u32, dk: &mut [u8]) -> Result<(), ParamError>
+    {
+        Self::check(rounds, dk)?;
+        self.derive_blocks(salt, rounds, 0, dk);
+        Ok(())
+    }
+
+    /// Oneshot PBKDF2 function.
+    pub fn oneshot(
+        password: &[u8],
+        salt: &[u8],
+        rounds: u32,
+        dk: &mut [u8],
+    ) -> Result<(), ParamError>
+    {
+        Self::with_password(password).derive(salt, rounds, dk)
+    }
+
+    fn check(rounds: u32, dk: &[u8]) -> Result<(), ParamError>
+    {
+        if rounds == 0 {
+            return Err(ParamError::Rounds);
+        }
+        if dk.len() as u64 > u32::MAX as u64 * D::DIGEST_LEN as u64 {
+            return Err(ParamError::DkLen);
+        }
+        Ok(())
+    }
+
+    /// Fills `dk` with the blocks that start at block number `first + 1`.
+    fn derive_blocks(&self, salt: &[u8], rounds: u32, first: usize, dk: &mut [u8])
+    {
+        for (i, block) in dk.chunks_mut(D::DIGEST_LEN).enumerate() {
+            let mut ctx = Hmac::from_key(&self.key);
+            ctx.update(salt);
+            ctx.update(&(first as u32 + i as u32 + 1).to_be_bytes());
+            let mut u: [u8; D::DIGEST_LEN] = [0; D::DIGEST_LEN];
+            ctx.finish_to_slice(&mut u);
+
+            let mut t: [u8; D::DIGEST_LEN] = u;
+            for _ in 1..rounds {
+                u = self.key.oneshot(&u);
+                t.iter_mut().zip(&u).for_each(|(t, u)| *t ^= u);
+            }
+            block.copy_from_slice(&t[0..block.len()]);
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl<D> Pbkdf2<D>
+where
+    D: Digest + Copy + Sync,
+    [u8; D::BLOCK_LEN]:,
+    [u8; D::DIGEST_LEN]:,
+{
+    /// Same as [`derive`](`Self::derive`), but the blocks of `dk` are computed
+    /// on separate threads, up to the available parallelism.
+    ///
+    /// This only helps when `dk` is longer than the digest. The result is the
+    /// same either way.
+    pub fn derive_parallel(&self, salt: &[u8], rounds: u32, dk: &mut [u8])
+    -> Result<(), ParamError>
+    {
+        Self::check(rounds, dk)?;
+
+        let threads: usize = std::thread::available_parallelism().map_or(1, |n| n.get());
+        self.derive_threads(salt, rounds, dk, threads);
+        Ok(())
+    }
+
+    /// Same as [`derive_parallel`](`Self::derive_parallel`), with up to
+    /// `threads` threads and without checking the parameters.
+    fn derive_threads(&self, salt: &[u8], rounds: u32, dk: &mut [u8], threads: usize)
+    {
+        let blocks: usize = (dk.len() + D::DIGEST_LEN - 1) / D::DIGEST_LEN;
+        let blocks_per_thread: usize = (blocks + threads - 1) / threads;
+        if threads <= 1 || blocks <= 1 {
+            self.derive_blocks(salt, rounds, 0, dk);
+            return;
+        }
+
+        std::thread::scope(|s| {
+            for (i, part) in dk.chunks_mut(blocks_per_thread * D::DIGEST_LEN).enumerate() {
+                s.spawn(move || self.derive_blocks(salt, rounds, i * blocks_per_thread, part));
+            }
+        });
+    }
+}
+
+/// Error type for when the parameters of PBKDF2 are not valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamError
+{
+    /// The number of rounds is zero.
+    Rounds,
+    /// The derived key is longer than `(2^32 - 1) * DIGEST_LEN` bytes.
+    DkLen,
+}
+
+impl Display for ParamError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | Self::Rounds => write!(f, "Number of rounds must be at least 1"),
+            | Self::DkLen => write!(f, "Derived key is too long"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for ParamError {}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn rfc6070()
+    {
+        let vectors: &[(&[u8], &[u8], u32, &str)] = &[
+            (
+                b"password",
+                b"salt",
+                1,
+                "0c60c80f961f0e71f3a9b524af6012062fe037a6",
+            ),
+            (
+                b"password",
+                b"salt",
+                2,
+                "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957",
+            ),
+            (
+                b"password",
+                b"salt",
+                4096,
+                "4b007901b765489abead49d926f721d065a429c1",
+            ),
+            (
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038",
+            ),
+            (
+                b"pass\0word",
+                b"sa\0lt",
+                4096,
+                "56fa6aa75548099dcc37d7f03425e0c3",
+            ),
+        ];
+        for &(password, salt, rounds, expected) in vectors {
+            let mut dk = vec![0; expected.len() / 2];
+            Pbkdf2Sha1::oneshot(password, salt, rounds, &mut dk).unwrap();
+            assert_eq!(hex::encode(dk), expected);
+        }
+    }
+
+    #[test]
+    fn rfc7914()
+    {
+        let mut dk = [0; 64];
+        Pbkdf2Sha256::oneshot(b"passwd", b"salt", 1, &mut dk).unwrap();
+        assert_eq!(
+            hex::encode(dk),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        );
+        Pbkdf2Sha256::oneshot(b"Password", b"NaCl", 80000, &mut dk).unwrap();
+        assert_eq!(
+            hex::encode(dk),
+            "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56\
+             a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn parallel()
+    {
+        let expected = "afe6c5530785b6cc6b1c6453384731bd5ee432ee549fd42fb6695779ad8a1c5b\
+                        f59de69c48f774efc4007d5298f9033c0241d5ab69305e7b64eceeb8d834cfec\
+                        6afdec3c1c23982a121f2d4be008889378a49a0dfb104f0d2856e38f44271cda\
+                        f6de434196647bc5673cd6c148611ced6e9003b65879feccc89226ecc5e22090\
+                        795445cc7314fcf414878a42ffd39cd3b90dcd41e065";
+        let ctx = Pbkdf2Sha512::with_password(b"password");
+        let mut dk = [0; 150];
+        ctx.derive(b"salt", 1000, &mut dk).unwrap();
+        assert_eq!(hex::encode(dk), expected);
+        let mut dk = [0; 150];
+        ctx.derive_parallel(b"salt", 1000, &mut dk).unwrap();
+        assert_eq!(hex::encode(dk), expected);
+        // Three blocks, split unevenly, evenly, and with an idle thread.
+        for threads in [2, 3, 4] {
+            let mut dk = [0; 150];
+            ctx.derive_threads(b"salt", 1000, &mut dk, threads);
+            assert_eq!(hex::encode(dk), expected, "threads = {threads}");
+        }
+    }
+
+    #[test]
+    fn params()
+    {
+        let mut dk = [0; 20];
+        assert_eq!(
+            Pbkdf2Sha1::oneshot(b"password", b"salt", 0, &mut dk),
+            Err(ParamError::Rounds)
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Parsing and formatting of [PHC strings], the `$id$params$salt$hash` format
+//! that password hashes are stored in, and the items shared by the password
+//! hashing modules that use them.
+//!
+//! [PHC strings]: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+/// Memory limit used when none is given, which is 1 GiB.
+///
+/// The PHC string usually comes from storage, so a limit keeps a tampered
+/// string from making the verification allocate too much memory.
+pub const DEFAULT_MAX_MEMORY: usize = 1 << 30;
+
+/// Recommended length of the salt given to `hash`, which should also be
+/// random.
+pub const SALT_LEN: usize = 16;
+
+/// Error type for when the memory needed by the parameters can't be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryError
+{
+    /// The parameters need more memory than the limit.
+    Limit
+    {
+        /// Memory limit in bytes.
+        at_most: usize,
+        /// Memory needed by the parameters in bytes.
+        got:     u128,
+    },
+    /// The memory could not be allocated.
+    Alloc,
+}
+
+impl MemoryError
+{
+    /// Check that `got` bytes of memory are within the limit of `at_most`
+    /// bytes.
+    pub(crate) fn check(got: u128, at_most: usize) -> Result<(), Self>
+    {
+        if got > at_most as u128 {
+            return Err(Self::Limit { at_most, got });
+        }
+        Ok(())
+    }
+}
+
+impl Display for MemoryError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | Self::Limit { at_most, got } => {
+                write!(
+                    f,
+                    "Parameters need {got} bytes of memory, but at most {at_most} are allowed"
+                )
+            },
+            | Self::Alloc => write!(f, "Memory allocation failed"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for MemoryError {}
+
+/// Same as `vec![value; len]`, but returns [`MemoryError::Alloc`] instead of
+/// aborting if the allocation fails.
+pub(crate) fn try_alloc<T: Clone>(value: T, len: usize) -> Result<Vec<T>, MemoryError>
+{
+    let mut v: Vec<T> = Vec::new();
+    v.try_reserve_exact(len).map_err(|_| MemoryError::Alloc)?;
+    v.resize(len, value);
+    Ok(v)
+}
+
+/// Error type for when a password doesn't match a PHC string, where `E` is the
+/// error type of the parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyError<E>
+{
+    /// The PHC string is malformed or isn't for this algorithm.
+    Phc,
+    /// The parameters in the PHC string are not valid.
+    Params(E),
+    /// The password is wrong.
+    Password,
+}
+
+impl<E> From<E> for VerifyError<E>
+{
+    fn from(e: E) -> Self { Self::Params(e) }
+}
+
+impl<E> Display for VerifyError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | Self::Phc => write!(f, "Invalid PHC string"),
+            | Self::Params(e) => e.fmt(f),
+            | Self::Password => write!(f, "Wrong password"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl<E> std::error::Error for VerifyError<E> where E: std::error::Error {}
+
+/// Alphabet of the B64 encoding, which is base64 without padding.
+const B64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Appends the B64 encoding of `data` to `out`.
+pub(crate) fn encode_b64(data: &[u8], out: &mut String)
+{
+    for chunk in data.chunks(3) {
+        let n: u32 = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(B64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+}
+
+/// Decodes B64. Returns `None` if `s` is not in the canonical encoding, which
+/// has no padding and leaves the unused bits of the last character at zero.
+pub(crate) fn decode_b64(s: &str) -> Option<Vec<u8>>
+{
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    let mut out: Vec<u8> = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.as_bytes().chunks(4) {
+        let mut n: u32 = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            n |= (B64.iter().position(|&b| b == c)? as u32) << (18 - 6 * i);
+        }
+        let len = chunk.len() - 1;
+        if n & (0xffffff >> (8 * len)) != 0 {
+            return None;
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..1 + len]);
+    }
+    Some(out)
+}
+
+/// Fields of a PHC string, which borrow from the string itself.
+///
+/// The parameters, the salt and the hash are optional in the format, but every
+/// algorithm in this crate needs them, so they are required here.
+pub(crate) struct Phc<'a>
+{
+    pub(crate) id:      &'a str,
+    pub(crate) version: Option<&'a str>,
+    pub(crate) params:  &'a str,
+    pub(crate) salt:    &'a str,
+    pub(crate) hash:    &'a str,
+}
+
+impl<'a> Phc<'a>
+{
+    /// Splits a PHC string into its fields.
+    pub(crate) fn parse(s: &'a str) -> Option<Self>
+    {
+        let mut fields = s.strip_prefix('$')?.split('$');
+        let id = fields.next()?;
+        let mut next = fields.next()?;
+        let mut version = None;
+        if let Some(v) = next.strip_prefix("v=") {
+            version = Some(v);
+            next = fields.next()?;
+        }
+        let phc = Self {
+            id,
+            version,
+            params: next,
+            salt: fields.next()?,
+            hash: fields.next()?,
+        };
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(phc)
+    }
+
+    /// Value of the parameter `name`.
+    pub(crate) fn param(&self, name: &str) -> Option<&'a str>
+    {
+        self.params
+            .split(',')
+            .find_map(|p| p.strip_prefix(name)?.strip_prefix('='))
+    }
+
+    /// Value of the parameter `name` as a decimal integer.
+    ///
+    /// As required by the format, the value must not have a sign or leading
+    /// zeros.
+    pub(crate) fn param_u32(&self, name: &str) -> Option<u32>
+    {
+        let value = self.param(name)?;
+        if !value.bytes().all(|b| b.is_ascii_digit()) || (value.starts_with('0') && value != "0") {
+            return None;
+        }
+        value.parse().ok()
+    }
+
+    /// Number of parameters.
+    pub(crate) fn param_count(&self) -> usize { self.params.split(',').count() }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn b64()
+    {
+        for (data, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg"),
+            (b"fo", "Zm8"),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg"),
+            (b"\xfb\xff", "+/8"),
+        ] {
+            let mut s = String::new();
+            encode_b64(data, &mut s);
+            assert_eq!(s, encoded);
+            assert_eq!(decode_b64(encoded).unwrap(), data);
+        }
+        assert_eq!(decode_b64("Zh"), None);
+        assert_eq!(decode_b64("Zm9vY"), None);
+        assert_eq!(decode_b64("Zm9v=="), None);
+    }
+}
@@ -10,6 +10,19 @@ pub enum Feature
     Aesni,
     /// ARM aes used for hardware accelarated aes encryption and decryption.
     ArmAes,
+    /// x86 SHA extensions used for hardware accelerated SHA-1 and SHA-256
+    /// compression.
+    Shani,
+    /// ARM SHA1 and SHA256 instructions used for hardware accelerated SHA-1 and
+    /// SHA-256 compression.
+    ArmSha2,
+    /// ARMv8.2 SHA512 instructions used for hardware accelerated SHA-512
+    /// compression.
+    ArmSha512,
+    /// x86 AVX2 used for hashing multiple messages or BLAKE3 chunks in parallel.
+    Avx2,
+    /// ARM NEON used for hashing multiple messages or BLAKE3 chunks in parallel.
+    ArmNeon,
 }
 
 impl Feature
@@ -23,6 +36,14 @@ impl Feature
             return match self {
                 | Self::Aesni => is_x86_feature_detected!("aes"),
                 | Self::ArmAes => false,
+                | Self::Shani =>
+                    is_x86_feature_detected!("sha")
+                        && is_x86_feature_detected!("ssse3")
+                        && is_x86_feature_detected!("sse4.1"),
+                | Self::ArmSha2 => false,
+                | Self::ArmSha512 => false,
+                | Self::Avx2 => is_x86_feature_detected!("avx2"),
+                | Self::ArmNeon => false,
             };
         }
 
@@ -31,6 +52,11 @@ impl Feature
             return match self {
                 | Self::Aesni => false,
                 | Self::ArmAes => is_arm_feature_detected!("aes"),
+                | Self::Shani => false,
+                | Self::ArmSha2 => is_arm_feature_detected!("sha2"),
+                | Self::ArmSha512 => false,
+                | Self::Avx2 => false,
+                | Self::ArmNeon => is_arm_feature_detected!("neon"),
             };
         }
 
@@ -39,6 +65,11 @@ impl Feature
             return match self {
                 | Self::Aesni => false,
                 | Self::ArmAes => is_aarch64_feature_detected!("aes"),
+                | Self::Shani => false,
+                | Self::ArmSha2 => is_aarch64_feature_detected!("sha2"),
+                | Self::ArmSha512 => is_aarch64_feature_detected!("sha3"),
+                | Self::Avx2 => false,
+                | Self::ArmNeon => is_aarch64_feature_detected!("neon"),
             };
         }
 
@@ -0,0 +1,424 @@
+//! # [scrypt]
+//!
+//! Memory-hard password-based key derivation function from RFC 7914. The
+//! password is stretched with PBKDF2-HMAC-SHA-256 into `p` blocks, each of
+//! which is mixed with the Salsa20/8 core through a table of `N` blocks, so
+//! guessing passwords costs both time and memory.
+//!
+//! ```
+//! use oxicrypt::scrypt::*;
+//!
+//! let params = Params {
+//!     log_n: 10,
+//!     r:     8,
+//!     p:     1,
+//! };
+//! let mut key = [0; 32];
+//! derive(b"password", b"salt", &params, &mut key).unwrap();
+//! ```
+//!
+//! # PHC strings
+//!
+//! [`hash`] stores the parameters, the salt and the derived key in a PHC
+//! string, such as `$scrypt$ln=10,r=8,p=1$c2FsdA$...`, which [`verify`] checks
+//! a password against, with a random salt of at least [`SALT_LEN`] bytes.
+//!
+//! ```
+//! use oxicrypt::scrypt::*;
+//!
+//! let params = Params {
+//!     log_n: 10,
+//!     r:     8,
+//!     p:     1,
+//! };
+//! let phc = hash(b"hunter2", b"saltysaltysalty!", &params).unwrap();
+//! assert!(verify(b"hunter2", &phc).is_ok());
+//! assert_eq!(verify(b"hunter3", &phc), Err(VerifyError::Password));
+//! ```
+//!
+//! [scrypt]: https://www.rfc-editor.org/rfc/rfc7914
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::fmt::Write;
+
+use oxicrypt_core::salsa20;
+
+use crate::ct;
+use crate::pbkdf2::Pbkdf2Sha256;
+use crate::phc;
+pub use crate::phc::DEFAULT_MAX_MEMORY;
+pub use crate::phc::MemoryError;
+use crate::phc::Phc;
+pub use crate::phc::SALT_LEN;
+pub use crate::phc::VerifyError;
+
+/// Length of the derived key in the PHC strings created by [`hash`].
+pub const HASH_LEN: usize = 32;
+
+/// Cost parameters of scrypt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Params
+{
+    /// Base 2 logarithm of the CPU/memory cost `N`.
+    pub log_n: u8,
+    /// Block size, in units of 128 bytes.
+    pub r:     u32,
+    /// Parallelization parameter.
+    pub p:     u32,
+}
+
+impl Params
+{
+    /// Parameters recommended by the scrypt paper for interactive logins, which
+    /// need 16 MiB of memory.
+    pub const INTERACTIVE: Self = Self {
+        log_n: 14,
+        r:     8,
+        p:     1,
+    };
+
+    /// Number of bytes of memory needed to derive a key with these parameters.
+    pub const fn memory_len(&self) -> u128
+    {
+        let n: u128 = if self.log_n < 128 {
+            1 << self.log_n
+        } else {
+            u128::MAX
+        };
+        let r: u128 = self.r as u128;
+        // table of N blocks, p blocks from PBKDF2 and 2 blocks of scratch space
+        (128 * r)
+            .saturating_mul(n.saturating_add(self.p as u128))
+            .saturating_add(256 * r)
+    }
+
+    /// Check whether the parameters are valid and need at most `max_memory`
+    /// bytes of memory.
+    pub fn check(&self, max_memory: usize) -> Result<(), ParamError>
+    {
+        if self.r == 0 {
+            return Err(ParamError::R);
+        }
+        if self.log_n == 0
+            || self.log_n as u32 >= usize::BITS
+            || self.log_n as u64 >= 16 * self.r as u64
+        {
+            return Err(ParamError::LogN);
+        }
+        if self.p == 0 || self.p as u64 * self.r as u64 * 128 > u32::MAX as u64 * 32 {
+            return Err(ParamError::P);
+        }
+        MemoryError::check(self.memory_len(), max_memory)?;
+        Ok(())
+    }
+}
+
+/// Derive a key from `password` and `salt`, filling `dk`.
+///
+/// The parameters may need at most [`DEFAULT_MAX_MEMORY`] bytes of memory.
+pub fn derive(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    dk: &mut [u8],
+) -> Result<(), ParamError>
+{
+    derive_with_max_memory(password, salt, params, DEFAULT_MAX_MEMORY, dk)
+}
+
+/// Same as [`derive`], but the parameters may need at most `max_memory` bytes
+/// of memory.
+///
+/// The memory is allocated up front, and a failed allocation is returned as
+/// [`MemoryError::Alloc`].
+pub fn derive_with_max_memory(
+    password: &[u8],
+    salt: &[u8],
+    params: &Params,
+    max_memory: usize,
+    dk: &mut [u8],
+) -> Result<(), ParamError>
+{
+    params.check(max_memory)?;
+    if dk.len() as u64 > u32::MAX as u64 * 32 {
+        return Err(ParamError::DkLen);
+    }
+
+    let n: usize = 1 << params.log_n;
+    let words: usize = 32 * params.r as usize;
+    let mut b: Vec<u8> = phc::try_alloc(0, 4 * words * params.p as usize)?;
+    let mut v: Vec<u32> = phc::try_alloc(0, words * n)?;
+    let mut x: Vec<u32> = phc::try_alloc(0, words)?;
+    let mut y: Vec<u32> = phc::try_alloc(0, words)?;
+
+    let pbkdf2 = Pbkdf2Sha256::with_password(password);
+    // The lengths were checked above, so PBKDF2 can't fail.
+    pbkdf2.derive(salt, 1, &mut b).unwrap();
+    for block in b.chunks_exact_mut(4 * words) {
+        ro_mix(block, n, &mut v, &mut x, &mut y);
+    }
+    pbkdf2.derive(&b, 1, dk).unwrap();
+    Ok(())
+}
+
+/// Hash `password` into a PHC string, with a derived key of [`HASH_LEN`]
+/// bytes.
+pub fn hash(password: &[u8], salt: &[u8], params: &Params) -> Result<String, ParamError>
+{
+    let mut dk: [u8; HASH_LEN] = [0; HASH_LEN];
+    derive(password, salt, params, &mut dk)?;
+
+    let mut s = String::new();
+    write!(
+        s,
+        "$scrypt$ln={},r={},p={}$",
+        params.log_n, params.r, params.p
+    )
+    .unwrap();
+    phc::encode_b64(salt, &mut s);
+    s.push('$');
+    phc::encode_b64(&dk, &mut s);
+    Ok(s)
+}
+
+/// Check `password` against a PHC string created by [`hash`].
+///
+/// The parameters in the string may need at most [`DEFAULT_MAX_MEMORY`] bytes
+/// of memory.
+pub fn verify(password: &[u8], phc: &str) -> Result<(), VerifyError<ParamError>>
+{
+    verify_with_max_memory(password, phc, DEFAULT_MAX_MEMORY)
+}
+
+/// Same as [`verify`], but the parameters in the string may need at most
+/// `max_memory` bytes of memory.
+pub fn verify_with_max_memory(
+    password: &[u8],
+    phc: &str,
+    max_memory: usize,
+) -> Result<(), VerifyError<ParamError>>
+{
+    let phc = Phc::parse(phc).ok_or(VerifyError::Phc)?;
+    if phc.id != "scrypt" || phc.version.is_some() || phc.param_count() != 3 {
+        return Err(VerifyError::Phc);
+    }
+    let log_n = phc.param_u32("ln").ok_or(VerifyError::Phc)?;
+    let params = Params {
+        log_n: u8::try_from(log_n).map_err(|_| ParamError::LogN)?,
+        r:     phc.param_u32("r").ok_or(VerifyError::Phc)?,
+        p:     phc.param_u32("p").ok_or(VerifyError::Phc)?,
+    };
+    let salt = phc::decode_b64(phc.salt).ok_or(VerifyError::Phc)?;
+    let expected = phc::decode_b64(phc.hash).ok_or(VerifyError::Phc)?;
+    if expected.is_empty() {
+        return Err(VerifyError::Phc);
+    }
+
+    let mut dk: Vec<u8> = alloc::vec![0; expected.len()];
+    derive_with_max_memory(password, &salt, &params, max_memory, &mut dk)?;
+    if !ct::eq(&dk, &expected) {
+        return Err(VerifyError::Password);
+    }
+    Ok(())
+}
+
+/// scryptROMix on one block `b` of `128 * r` bytes, using `v` as the table of
+/// `n` blocks and `x` and `y` as scratch space of `32 * r` words.
+fn ro_mix(b: &mut [u8], n: usize, v: &mut [u32], x: &mut [u32], y: &mut [u32])
+{
+    let words: usize = x.len();
+    for (x, b) in x.iter_mut().zip(b.chunks_exact(4)) {
+        *x = u32::from_le_bytes(b.try_into().unwrap());
+    }
+
+    for vi in v.chunks_exact_mut(words) {
+        vi.copy_from_slice(x);
+        block_mix(x, y);
+    }
+    for _ in 0..n {
+        // Integerify: the first 64 bits of the last 64-byte block.
+        let last: usize = words - 16;
+        let j: usize = (x[last] as u64 | (x[last + 1] as u64) << 32) as usize & (n - 1);
+        x.iter_mut()
+            .zip(&v[j * words..(j + 1) * words])
+            .for_each(|(x, v)| *x ^= v);
+        block_mix(x, y);
+    }
+
+    for (x, b) in x.iter().zip(b.chunks_exact_mut(4)) {
+        b.copy_from_slice(&x.to_le_bytes());
+    }
+}
+
+/// scryptBlockMix on `b`, which holds `2 * r` blocks of 16 words, using `y` as
+/// scratch space of the same length.
+fn block_mix(b: &mut [u32], y: &mut [u32])
+{
+    let blocks: usize = b.len() / 16;
+    let mut t: [u32; 16] = b[b.len() - 16..].try_into().unwrap();
+    for (i, bi) in b.chunks_exact(16).enumerate() {
+        t.iter_mut().zip(bi).for_each(|(t, b)| *t ^= b);
+        unsafe { salsa20::salsa20_8(t.as_mut_ptr()) };
+        // even blocks go to the first half of the output, odd ones to the second
+        let k: usize = i / 2 + (i % 2) * (blocks / 2);
+        y[k * 16..(k + 1) * 16].copy_from_slice(&t);
+    }
+    b.copy_from_slice(y);
+}
+
+/// Error type for when the parameters of scrypt are not valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamError
+{
+    /// `log_n` is 0, or `N` is not less than `2^(16 * r)` or the size of the
+    /// address space.
+    LogN,
+    /// `r` is 0.
+    R,
+    /// `p` is 0, or `p * r` is greater than `(2^32 - 1) / 4`.
+    P,
+    /// The derived key is longer than `(2^32 - 1) * 32` bytes.
+    DkLen,
+    /// The memory is over the limit or could not be allocated.
+    Memory(MemoryError),
+}
+
+impl From<MemoryError> for ParamError
+{
+    fn from(e: MemoryError) -> Self { Self::Memory(e) }
+}
+
+impl Display for ParamError
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            | Self::LogN => write!(f, "log2(N) must be at least 1 and less than 16 * r"),
+            | Self::R => write!(f, "Block size must be at least 1"),
+            | Self::P => write!(
+                f,
+                "Parallelization must be at least 1 and p * r less than 2^30"
+            ),
+            | Self::DkLen => write!(f, "Derived key is too long"),
+            | Self::Memory(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl std::error::Error for ParamError {}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn rfc7914()
+    {
+        let vectors: &[(&[u8], &[u8], Params, &str)] = &[
+            (
+                b"",
+                b"",
+                Params {
+                    log_n: 4,
+                    r:     1,
+                    p:     1,
+                },
+                "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+                 fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906",
+            ),
+            (
+                b"password",
+                b"NaCl",
+                Params {
+                    log_n: 10,
+                    r:     8,
+                    p:     16,
+                },
+                "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+                 2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640",
+            ),
+            (
+                b"pleaseletmein",
+                b"SodiumChloride",
+                Params {
+                    log_n: 14,
+                    r:     8,
+                    p:     1,
+                },
+                "7023bdcb3afd7348461c06cd81fd38ebfda8fbba904f8e3ea9b543f6545da1f2\
+                 d5432955613f0fcf62d49705242a9af9e61e85dc0d651e40dfcf017b45575887",
+            ),
+        ];
+        for &(password, salt, params, expected) in vectors {
+            let mut dk = [0; 64];
+            derive(password, salt, &params, &mut dk).unwrap();
+            assert_eq!(hex::encode(dk), expected);
+        }
+    }
+
+    #[test]
+    fn phc()
+    {
+        let params = Params {
+            log_n: 10,
+            r:     8,
+            p:     1,
+        };
+        let phc = "$scrypt$ln=10,r=8,p=1$c2FsdHlzYWx0eXNhbHR5IQ$I8zi9hizCioTKzFT/\
+                   +rEhiUjjvg7hSkT9+Il991mwyE";
+        assert_eq!(hash(b"hunter2", b"saltysaltysalty!", &params).unwrap(), phc);
+        assert_eq!(verify(b"hunter2", phc), Ok(()));
+        assert_eq!(verify(b"hunter3", phc), Err(VerifyError::Password));
+
+        // passlib
+        let phc = "$scrypt$ln=16,r=8,\
+                   p=1$aM15713r3Xsvxbi31lqr1Q$nFNh2CVHVjNldFVKDHDlm4CbdRSCdEBsjjJxD+iCs5E";
+        assert_eq!(verify(b"password", phc), Ok(()));
+        assert!(matches!(
+            verify_with_max_memory(b"password", phc, 1 << 26),
+            Err(VerifyError::Params(ParamError::Memory(
+                MemoryError::Limit { .. }
+            )))
+        ));
+
+        for phc in [
+            "$scrypt$ln=10,r=8$c2FsdA$I8zi9hizCioTKzFT",
+            "$scrypt$ln=10,r=8,p=1,x=1$c2FsdA$I8zi9hizCioTKzFT",
+            "$scrypt$ln=010,r=8,p=1$c2FsdA$I8zi9hizCioTKzFT",
+            "$scrypt$ln=10,r=8,p=1$c2FsdA$",
+            "$scrypt$ln=10,r=8,p=1$c2FsdA=$I8zi9hizCioTKzFT",
+            "$scrypt$v=1$ln=10,r=8,p=1$c2FsdA$I8zi9hizCioTKzFT",
+            "$argon2id$ln=10,r=8,p=1$c2FsdA$I8zi9hizCioTKzFT",
+            "scrypt$ln=10,r=8,p=1$c2FsdA$I8zi9hizCioTKzFT",
+        ] {
+            assert_eq!(verify(b"hunter2", phc), Err(VerifyError::Phc), "{phc}");
+        }
+    }
+
+    #[test]
+    fn params()
+    {
+        let mut dk = [0; 32];
+        for (log_n, r, p, e) in [
+            (0, 8, 1, ParamError::LogN),
+            (16, 1, 1, ParamError::LogN),
+            (14, 0, 1, ParamError::R),
+            (14, 8, 0, ParamError::P),
+            (1, 1, 1 << 30, ParamError::P),
+        ] {
+            assert_eq!(derive(b"", b"", &Params { log_n, r, p }, &mut dk), Err(e));
+        }
+        assert_eq!(
+            derive_with_max_memory(b"", b"", &Params::INTERACTIVE, 1 << 24, &mut dk),
+            Err(ParamError::Memory(MemoryError::Limit {
+                at_most: 1 << 24,
+                got:     16 * 1024 * 1024 + 1024 + 2048,
+            }))
+        );
+    }
+}
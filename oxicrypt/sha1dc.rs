@@ -0,0 +1,301 @@
+//! # SHA-1 with collision detection
+//!
+//! [`Sha1Dc`] computes the same digest as
+//! [`Sha1`](`crate::merkle_damgard::Sha1`), but it also checks every block for
+//! signs of a known collision attack, such as SHAttered or SHA-1 is a Shambles.
+//! Use it when hashing data that may have been crafted by an attacker, like Git
+//! objects.
+//!
+//! When an attack is detected, the context remembers it and, unless disabled,
+//! falls back to a safe hash, just like Git does. The safe hash compresses each
+//! offending block three times instead of once, so colliding messages end up
+//! with different digests.
+
+use core::cmp;
+use core::slice;
+
+use oxicrypt_core::md_compress;
+
+use crate::digest::DigestMeta;
+use crate::digest::FinishInternal;
+use crate::digest::Reset;
+use crate::digest::Update;
+use crate::merkle_damgard::SHA_INITIAL_H1;
+use crate::traits::New;
+
+/// SHA-1 context with collision detection.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sha1dc::*;
+/// let mut ctx = Sha1Dc::new();
+/// ctx.update(b"abc");
+/// let (digest, detected) = ctx.finish_with_detection();
+///
+/// assert!(!detected);
+/// assert_eq!(
+///     hex::encode(digest),
+///     "a9993e364706816aba3e25717850c26c9cd0d89d"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Sha1Dc
+{
+    state:     [u32; 5],
+    block:     [u8; 64],
+    index:     usize,
+    /// Number of compressed blocks, which is kept in a `u64` so that the
+    /// length doesn't overflow on 32-bit targets.
+    count:     u64,
+    safe_hash: bool,
+    detected:  bool,
+}
+
+impl Sha1Dc
+{
+    /// Creates a context that uses the safe hash once an attack is detected.
+    #[inline(always)]
+    pub const fn new() -> Self { Self::with_safe_hash(true) }
+
+    /// Creates a context that only uses the safe hash if `safe_hash` is
+    /// `true`.
+    ///
+    /// Without the safe hash, the digest is always the plain SHA-1 digest, and
+    /// attacks are only reported through
+    /// [`collision_detected`](`Self::collision_detected`).
+    #[inline(always)]
+    pub const fn with_safe_hash(safe_hash: bool) -> Self
+    {
+        Self {
+            state: SHA_INITIAL_H1,
+            block: [0; 64],
+            index: 0,
+            count: 0,
+            safe_hash,
+            detected: false,
+        }
+    }
+
+    /// Returns `true` if any of the blocks compressed so far is part of a
+    /// collision attack.
+    ///
+    /// The last block is only compressed when the digest is computed, so use
+    /// [`finish_with_detection`](`Self::finish_with_detection`) to check every
+    /// block.
+    #[inline(always)]
+    pub const fn collision_detected(&self) -> bool { self.detected }
+
+    /// Calculates the digest and returns it along with whether any of the
+    /// blocks, including the final ones, is part of a collision attack.
+    pub fn finish_with_detection(mut self) -> ([u8; 20], bool)
+    {
+        let mut digest: [u8; 20] = [0; 20];
+        // The context is consumed, so it can't be used after being finished.
+        digest.copy_from_slice(unsafe { self.finish_internal() });
+        (digest, self.detected)
+    }
+
+    #[inline(always)]
+    fn compress(&mut self, data: *const u8, blocks: usize)
+    {
+        let detected =
+            unsafe { md_compress::sha1dc(self.state.as_mut_ptr(), data, blocks, self.safe_hash) };
+        self.detected |= detected;
+    }
+
+    fn update_(&mut self, mut data: &[u8])
+    {
+        // Fill the partially filled block first, if there is one.
+        if self.index != 0 {
+            let len = cmp::min(64 - self.index, data.len());
+            self.block[self.index..self.index + len].copy_from_slice(&data[0..len]);
+            self.index += len;
+            data = &data[len..];
+
+            if self.index != 64 {
+                return;
+            }
+
+            self.compress(self.block.as_ptr(), 1);
+            self.index = 0;
+            self.count += 1;
+        }
+
+        // Compress as many blocks as possible straight from `data`.
+        let blocks = data.len() / 64;
+        if blocks != 0 {
+            self.compress(data.as_ptr(), blocks);
+            self.count += blocks as u64;
+            data = &data[blocks * 64..];
+        }
+
+        self.block[0..data.len()].copy_from_slice(data);
+        self.index = data.len();
+    }
+
+    fn finish_(&mut self)
+    {
+        let len = (self.count * 64 + self.index as u64) * 8;
+
+        self.block[self.index] = 0x80;
+        self.index += 1;
+        if self.index > 56 {
+            self.block[self.index..].fill(0);
+            self.compress(self.block.as_ptr(), 1);
+            self.index = 0;
+        }
+        self.block[self.index..56].fill(0);
+        self.block[56..].copy_from_slice(&len.to_be_bytes());
+        self.compress(self.block.as_ptr(), 1);
+
+        self.state.iter_mut().for_each(|h| *h = h.to_be());
+    }
+}
+
+impl const DigestMeta for Sha1Dc
+{
+    const BLOCK_LEN: usize = 64;
+    const DIGEST_LEN: usize = 20;
+}
+
+impl const New for Sha1Dc
+{
+    fn new() -> Self { Self::new() }
+}
+
+impl const Reset for Sha1Dc
+{
+    fn reset(&mut self)
+    {
+        self.state = SHA_INITIAL_H1;
+        self.index = 0;
+        self.count = 0;
+        self.detected = false;
+    }
+}
+
+impl Update for Sha1Dc
+{
+    fn update(&mut self, data: &[u8]) { self.update_(data); }
+}
+
+impl FinishInternal for Sha1Dc
+{
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        self.finish_();
+        unsafe { slice::from_raw_parts(self.state.as_ptr().cast(), Self::DIGEST_LEN) }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::digest::Finish;
+    use crate::digest::Oneshot;
+    use crate::merkle_damgard::Sha1;
+
+    /// First 192 bytes of both PDFs from the SHAttered attack.
+    const SHATTERED_PREFIX: &[u8] = b"%PDF-1.3\n%\xe2\xe3\xcf\xd3\n\n\n1 0 obj\n<</Width 2 0 R\
+        /Height 3 0 R/Type 4 0 R/Subtype 5 0 R/Filter 6 0 R/ColorSpace 7 0 R/Length 8 0 R\
+        /BitsPerComponent 8>>\nstream\n\xff\xd8\xff\xfe\x00\x24SHA-1 is dead!!!!!\x85\x2f\xec\
+        \x09\x23\x39\x75\x9c\x39\xb1\xa1\xc6\x3c\x4c\x97\xe1\xff\xfe\x01";
+
+    /// First 320 bytes of `shattered-1.pdf` and `shattered-2.pdf`, which end
+    /// with the two collision blocks.
+    fn shattered() -> [Vec<u8>; 2]
+    {
+        [
+            "7346dc9166b67e118f029ab621b2560ff9ca67cca8c7f85ba84c79030c2b3de2\
+             18f86db3a90901d5df45c14f26fedfb3dc38e96ac22fe7bd728f0e45bce046d2\
+             3c570feb141398bb552ef5a0a82be331fea48037b8b5d71f0e332edf93ac3500\
+             eb4ddc0decc1a864790c782c76215660dd309791d06bd0af3f98cda4bc4629b1",
+            "7f46dc93a6b67e013b029aaa1db2560b45ca67d688c7f84b8c4c791fe02b3df6\
+             14f86db1690901c56b45c1530afedfb76038e972722fe7ad728f0e4904e046c2\
+             30570fe9d41398abe12ef5bc942be33542a4802d98b5d70f2a332ec37fac3514\
+             e74ddc0f2cc1a874cd0c78305a21566461309789606bd0bf3f98cda8044629a1",
+        ]
+        .map(|blocks| [SHATTERED_PREFIX, &hex::decode(blocks).unwrap()].concat())
+    }
+
+    #[test]
+    fn shattered_detected()
+    {
+        let [message1, message2] = shattered();
+        assert_eq!(SHATTERED_PREFIX.len(), 192);
+        assert_eq!(
+            hex::encode(Sha1::oneshot(&message1)),
+            "f92d74e3874587aaf443d1db961d4e26dde13e9c"
+        );
+        assert_eq!(Sha1::oneshot(&message2), Sha1::oneshot(&message1));
+
+        // Without the safe hash, the digests still collide.
+        for message in [&message1, &message2] {
+            let mut ctx = Sha1Dc::with_safe_hash(false);
+            ctx.update(message);
+            let (digest, detected) = ctx.finish_with_detection();
+            assert!(detected);
+            assert_eq!(digest, Sha1::oneshot(&message1));
+        }
+
+        // With the safe hash, the second collision block is compressed three
+        // times.
+        let expected = [
+            "7117b3cb9225aaf0d8ef1a40e493957b0bf8693d",
+            "29f38ae9fd98e2931120fa0bf213e024250d3f6a",
+        ];
+        for (message, expected) in [message1, message2].iter().zip(expected) {
+            let mut ctx = Sha1Dc::new();
+            ctx.update(&message[0..300]);
+            // The second collision block hasn't been compressed yet.
+            assert!(!ctx.collision_detected());
+            ctx.update(&message[300..]);
+            assert!(ctx.collision_detected());
+
+            let (digest, detected) = ctx.finish_with_detection();
+            assert!(detected);
+            assert_eq!(hex::encode(digest), expected);
+            assert_eq!(hex::encode(ctx.finish()), expected);
+
+            ctx.reset();
+            assert!(!ctx.collision_detected());
+        }
+    }
+
+    /// The length counter goes past 4 GiB, which only fits in a `usize` on
+    /// 64-bit targets, where it can be compared with [`Sha1`].
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn long_message()
+    {
+        let mut ctx = Sha1Dc::new();
+        let mut expected = Sha1::new();
+        ctx.count = (1 << 26) + 3;
+        expected.count = (1 << 26) + 3;
+        ctx.update(b"abc");
+        expected.update(b"abc");
+        assert_eq!(ctx.finish(), expected.finish());
+    }
+
+    #[test]
+    fn clean()
+    {
+        let mut message = shattered()[0].clone();
+        message[200] ^= 1;
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7) as u8).collect();
+
+        for message in [b"".as_slice(), b"abc", &message, &data] {
+            let mut ctx = Sha1Dc::new();
+            ctx.update(message);
+            let (digest, detected) = ctx.finish_with_detection();
+            assert!(!detected);
+            assert_eq!(digest, Sha1::oneshot(message));
+            assert_eq!(ctx.finish(), Sha1::oneshot(message));
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! High level SM4 API.
+//!
+//! SM4 is the block cipher standardized in GB/T 32907-2016. It has a block size
+//! of 16 bytes and a key size of 16 bytes. See [`mode`](`crate::mode`) for
+//! encrypting messages longer than a single block.
+
+use core::mem::MaybeUninit;
+use core::ptr;
+
+use oxicrypt_core::sm4;
+
+pub use crate::aes::LenError;
+
+/// Expanded key to use with SM4.
+///
+/// # Note
+///
+/// SM4 uses different key schedules when encrypting or decrypting. If you
+/// created a key schedule using either one of `set_encrypt_key` functions, you
+/// cannot use the same key schedule for decrypting the data. This type does not
+/// keep track of whether it was created for encryption or decryption, so you
+/// must be careful when using it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Key
+{
+    rk: [u32; 32],
+}
+
+impl Key
+{
+    /// SM4 block size in bytes.
+    pub const BLOCK_LEN: usize = 16;
+    /// Key size in bytes.
+    pub const KEY_LEN: usize = 16;
+    /// Inner key schedule size in bytes.
+    pub const KEY_SCHEDULE_LEN: usize = 128;
+    /// Number of rounds.
+    pub const ROUNDS: usize = 32;
+
+    /// Returns the inner round keys.
+    pub const fn as_words(&self) -> &[u32; 32] { &self.rk }
+
+    /// Returns a pointer to the inner key schedule.
+    pub const fn as_ptr(&self) -> *const u32 { self.rk.as_ptr() }
+
+    /// Returns a mutable pointer to the inner key schedule.
+    pub const fn as_mut_ptr(&mut self) -> *mut u32 { self.rk.as_mut_ptr() }
+
+    /// Creates a key schedule to use in encryption mode.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when length of the `key` is not equal
+    /// to 16.
+    pub fn with_encrypt_key(key: &[u8]) -> Result<Self, LenError>
+    {
+        if key.len() != Self::KEY_LEN {
+            return Err(LenError::new("key", Self::KEY_LEN, key.len()));
+        }
+        Ok(unsafe { Self::with_encrypt_key_unchecked(key) })
+    }
+
+    /// Creates a key schedule to use in encryption mode.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `key` must be equal to 16.
+    pub unsafe fn with_encrypt_key_unchecked(key: &[u8]) -> Self
+    {
+        let mut key_schedule: MaybeUninit<Self> = MaybeUninit::uninit();
+        key_schedule
+            .assume_init_mut()
+            .set_encrypt_key_unchecked(key);
+        key_schedule.assume_init()
+    }
+
+    /// Creates a key schedule to use in decryption mode.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when length of the `key` is not equal
+    /// to 16.
+    pub fn with_decrypt_key(key: &[u8]) -> Result<Self, LenError>
+    {
+        if key.len() != Self::KEY_LEN {
+            return Err(LenError::new("key", Self::KEY_LEN, key.len()));
+        }
+        Ok(unsafe { Self::with_decrypt_key_unchecked(key) })
+    }
+
+    /// Creates a key schedule to use in decryption mode.
+    ///
+    /// # Safety
+    ///
+    /// * Length of the `key` must be equal to 16.
+    pub unsafe fn with_decrypt_key_unchecked(key: &[u8]) -> Self
+    {
+        let mut key_schedule: MaybeUninit<Self> = MaybeUninit::uninit();
+        key_schedule
+            .assume_init_mut()
+            .set_decrypt_key_unchecked(key);
+        key_schedule.assume_init()
+    }
+
+    /// Sets the encryption key.
+    ///
+    /// Note that the previous value stored in the key schedule is discarded.
+    pub fn set_encrypt_key(&mut self, key: &[u8]) -> Result<(), LenError>
+    {
+        if key.len() != Self::KEY_LEN {
+            return Err(LenError::new("key", Self::KEY_LEN, key.len()));
+        }
+        unsafe { self.set_encrypt_key_unchecked(key) };
+        Ok(())
+    }
+
+    /// Sets the decryption key.
+    ///
+    /// Note that the previous value stored in the key schedule is discarded.
+    pub fn set_decrypt_key(&mut self, key: &[u8]) -> Result<(), LenError>
+    {
+        if key.len() != Self::KEY_LEN {
+            return Err(LenError::new("key", Self::KEY_LEN, key.len()));
+        }
+        unsafe { self.set_decrypt_key_unchecked(key) };
+        Ok(())
+    }
+
+    /// Sets the encryption key.
+    ///
+    /// Note that the previous value stored in the key schedule is discarded.
+    ///
+    /// # Safety
+    ///
+    /// * Length of the `key` must be equal to 16.
+    pub unsafe fn set_encrypt_key_unchecked(&mut self, key: &[u8])
+    {
+        sm4::sm4_expand_key(key.as_ptr(), self.as_mut_ptr());
+    }
+
+    /// Sets the decryption key.
+    ///
+    /// Note that the previous value stored in the key schedule is discarded.
+    ///
+    /// # Safety
+    ///
+    /// * Length of the `key` must be equal to 16.
+    pub unsafe fn set_decrypt_key_unchecked(&mut self, key: &[u8])
+    {
+        self.set_encrypt_key_unchecked(key);
+        self.inverse_key();
+    }
+
+    /// Converts an encryption key into a decryption key.
+    ///
+    /// Since SM4 only reverses the order of the round keys, calling this
+    /// function on a decryption key turns it back into an encryption key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::sm4::*;
+    /// let key: Vec<u8> = (0u8..Key::KEY_LEN as u8).collect();
+    /// let keysched_r = Key::with_decrypt_key(&key).unwrap();
+    /// let mut keysched_l = Key::with_encrypt_key(&key).unwrap();
+    /// keysched_l.inverse_key();
+    /// assert_eq!(keysched_l, keysched_r);
+    /// ```
+    pub fn inverse_key(&mut self) { unsafe { sm4::sm4_inverse_key(self.as_mut_ptr()) } }
+
+    /// Encrypts the given block in-place.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when the length of `block` is not a
+    /// multiple of 16.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use oxicrypt::sm4::*;
+    /// let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+    /// let keysched = Key::with_encrypt_key(&key).unwrap();
+    ///
+    /// let mut block = key.clone();
+    /// keysched.encrypt(&mut block).unwrap();
+    /// assert_eq!(hex::encode(block), "681edf34d206965e86b3e94f536e4246");
+    /// ```
+    pub fn encrypt(&self, block: &mut [u8]) -> Result<(), LenError>
+    {
+        if block.len() % 16 != 0 {
+            return Err(LenError::new("block", block.len() / 16 * 16, block.len()));
+        }
+        unsafe { self.encrypt_unchecked(block) };
+        Ok(())
+    }
+
+    /// Decrypts the given block in-place.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when the length of `block` is not a
+    /// multiple of 16.
+    pub fn decrypt(&self, block: &mut [u8]) -> Result<(), LenError>
+    {
+        if block.len() % 16 != 0 {
+            return Err(LenError::new("block", block.len() / 16 * 16, block.len()));
+        }
+        unsafe { self.decrypt_unchecked(block) };
+        Ok(())
+    }
+
+    /// Encrypts the given block in-place.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `block` must be a multiple of 16.
+    pub unsafe fn encrypt_unchecked(&self, block: &mut [u8])
+    {
+        let block_ptr = block.as_mut_ptr();
+        self.encrypt_raw(block_ptr, block_ptr, block.len() / 16);
+    }
+
+    /// Encrypts a single block in-place.
+    pub fn encrypt_block(&self, block: &mut [u8; 16])
+    {
+        unsafe { sm4::sm4_encrypt1(block.as_mut_ptr(), self.as_ptr()) };
+    }
+
+    /// Encrypts the `input` block and writes the result to `output`.
+    pub fn encrypt_block_to(&self, input: &[u8; 16], output: &mut [u8; 16])
+    {
+        *output = *input;
+        self.encrypt_block(output);
+    }
+
+    /// Encrypts the given blocks in-place.
+    pub fn encrypt_chunks(&self, blocks: &mut [[u8; 16]])
+    {
+        blocks
+            .iter_mut()
+            .for_each(|block| self.encrypt_block(block));
+    }
+
+    /// Encrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when the length of `input` is not a
+    /// multiple of 16, or when the length of `output` is not equal to the
+    /// length of `input`.
+    pub fn encrypt_blocks(&self, input: &[u8], output: &mut [u8]) -> Result<(), LenError>
+    {
+        if input.len() % 16 != 0 {
+            return Err(LenError::new("input", input.len() / 16 * 16, input.len()));
+        }
+        if output.len() != input.len() {
+            return Err(LenError::new("output", input.len(), output.len()));
+        }
+        unsafe { self.encrypt_blocks_unchecked(input, output) };
+        Ok(())
+    }
+
+    /// Encrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `input` must be a multiple of 16.
+    /// * Length of `output` must be at least the length of `input`.
+    pub unsafe fn encrypt_blocks_unchecked(&self, input: &[u8], output: &mut [u8])
+    {
+        self.encrypt_raw(input.as_ptr(), output.as_mut_ptr(), input.len() / 16);
+    }
+
+    /// Encrypts `n` blocks read from `input` and writes them to `output`.
+    ///
+    /// `input` and `output` may point to the same buffer, but they must not
+    /// overlap otherwise.
+    unsafe fn encrypt_raw(&self, input: *const u8, output: *mut u8, n: usize)
+    {
+        ptr::copy(input, output, n * 16);
+        for i in 0..n {
+            sm4::sm4_encrypt1(output.add(i * 16), self.as_ptr());
+        }
+    }
+
+    /// Decrypts the given block in-place.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `block` must be a multiple of 16.
+    pub unsafe fn decrypt_unchecked(&self, block: &mut [u8])
+    {
+        let block_ptr = block.as_mut_ptr();
+        self.decrypt_raw(block_ptr, block_ptr, block.len() / 16);
+    }
+
+    /// Decrypts a single block in-place.
+    pub fn decrypt_block(&self, block: &mut [u8; 16])
+    {
+        unsafe { sm4::sm4_decrypt1(block.as_mut_ptr(), self.as_ptr()) };
+    }
+
+    /// Decrypts the `input` block and writes the result to `output`.
+    pub fn decrypt_block_to(&self, input: &[u8; 16], output: &mut [u8; 16])
+    {
+        *output = *input;
+        self.decrypt_block(output);
+    }
+
+    /// Decrypts the given blocks in-place.
+    pub fn decrypt_chunks(&self, blocks: &mut [[u8; 16]])
+    {
+        blocks
+            .iter_mut()
+            .for_each(|block| self.decrypt_block(block));
+    }
+
+    /// Decrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// Returns an [`Err`](`Result::Err`) when the length of `input` is not a
+    /// multiple of 16, or when the length of `output` is not equal to the
+    /// length of `input`.
+    pub fn decrypt_blocks(&self, input: &[u8], output: &mut [u8]) -> Result<(), LenError>
+    {
+        if input.len() % 16 != 0 {
+            return Err(LenError::new("input", input.len() / 16 * 16, input.len()));
+        }
+        if output.len() != input.len() {
+            return Err(LenError::new("output", input.len(), output.len()));
+        }
+        unsafe { self.decrypt_blocks_unchecked(input, output) };
+        Ok(())
+    }
+
+    /// Decrypts the blocks in `input` and writes the result to `output`.
+    ///
+    /// # Safety
+    ///
+    /// * Length of `input` must be a multiple of 16.
+    /// * Length of `output` must be at least the length of `input`.
+    pub unsafe fn decrypt_blocks_unchecked(&self, input: &[u8], output: &mut [u8])
+    {
+        self.decrypt_raw(input.as_ptr(), output.as_mut_ptr(), input.len() / 16);
+    }
+
+    /// Decrypts `n` blocks read from `input` and writes them to `output`.
+    ///
+    /// `input` and `output` may point to the same buffer, but they must not
+    /// overlap otherwise.
+    unsafe fn decrypt_raw(&self, input: *const u8, output: *mut u8, n: usize)
+    {
+        ptr::copy(input, output, n * 16);
+        for i in 0..n {
+            sm4::sm4_decrypt1(output.add(i * 16), self.as_ptr());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    /// Examples from GB/T 32907-2016, appendix A.
+    #[test]
+    fn gbt32907()
+    {
+        let key = hex::decode("0123456789abcdeffedcba9876543210").unwrap();
+        let encrypt_key = Key::with_encrypt_key(&key).unwrap();
+        let decrypt_key = Key::with_decrypt_key(&key).unwrap();
+
+        let mut block: [u8; 16] = key.clone().try_into().unwrap();
+        encrypt_key.encrypt_block(&mut block);
+        assert_eq!(hex::encode(block), "681edf34d206965e86b3e94f536e4246");
+        decrypt_key.decrypt_block(&mut block);
+        assert_eq!(block.as_slice(), key);
+
+        // The same block encrypted 1000000 times.
+        for _ in 0..1_000_000 {
+            encrypt_key.encrypt_block(&mut block);
+        }
+        assert_eq!(hex::encode(block), "595298c7c6fd271f0402f804c33d3f66");
+    }
+
+    #[test]
+    fn blocks()
+    {
+        let key = Key::with_encrypt_key(&[0x42; 16]).unwrap();
+        let input: Vec<u8> = (0..64).collect();
+        let mut expected = [[0; 16]; 4];
+        expected
+            .iter_mut()
+            .zip(input.chunks(16))
+            .for_each(|(block, chunk)| key.encrypt_block_to(chunk.try_into().unwrap(), block));
+
+        let mut output = [0; 64];
+        key.encrypt_blocks(&input, &mut output).unwrap();
+        assert_eq!(output.as_slice(), expected.as_flattened());
+        assert!(
+            key.encrypt_blocks(&input[0..15], &mut output[0..15])
+                .is_err()
+        );
+        assert!(key.encrypt_blocks(&input, &mut output[0..48]).is_err());
+
+        let key = Key::with_decrypt_key(&[0x42; 16]).unwrap();
+        key.decrypt(&mut output).unwrap();
+        assert_eq!(output.as_slice(), input);
+    }
+}
@@ -0,0 +1,752 @@
+//! # [SP 800-185] functions
+//!
+//! SHA-3 derived functions: cSHAKE, KMAC, TupleHash and ParallelHash. All of
+//! them are built on top of cSHAKE, which adds a function name and a
+//! customization string to SHAKE for domain separation.
+//!
+//! The 128 and 256 suffixes are the security strengths of the functions and
+//! the fixed-length variants output twice as many bits. The arbitrary-length
+//! variants, called XOF mode in the standard, are available through
+//! [`ExtendableOutput`].
+//!
+//! [SP 800-185]: https://doi.org/10.6028/NIST.SP.800-185
+
+use core::cmp;
+use core::mem;
+
+use crate::digest::DigestMeta;
+use crate::digest::ExtendableOutput;
+use crate::digest::FinishInternal;
+use crate::digest::FinishToSlice;
+use crate::digest::Reset;
+use crate::digest::SetKey;
+use crate::digest::Update;
+use crate::digest::Verify;
+use crate::digest::XofReader;
+use crate::sponge::Shake;
+use crate::sponge::Sponge;
+use crate::sponge::SpongeReader;
+use crate::traits::New;
+
+/// Encode `x` as a byte string that can be parsed from the beginning.
+///
+/// The encoding is the number of bytes of `x` followed by `x` in big endian,
+/// without leading zeros. The encoded bytes are written to `buf` and a
+/// slice of them is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::sp800_185::*;
+/// let mut buf = [0; 9];
+/// assert_eq!(left_encode(0, &mut buf), [1, 0]);
+/// assert_eq!(left_encode(168, &mut buf), [1, 168]);
+/// assert_eq!(left_encode(256, &mut buf), [2, 1, 0]);
+/// ```
+pub fn left_encode(x: u64, buf: &mut [u8; 9]) -> &[u8]
+{
+    let n = encoded_len(x);
+    buf[0] = n as u8;
+    buf[1..9].copy_from_slice(&x.to_be_bytes());
+    buf.copy_within(9 - n..9, 1);
+    &buf[0..n + 1]
+}
+
+/// Encode `x` as a byte string that can be parsed from the end.
+///
+/// The encoding is `x` in big endian, without leading zeros, followed by the
+/// number of bytes of `x`. The encoded bytes are written to `buf` and a
+/// slice of them is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::sp800_185::*;
+/// let mut buf = [0; 9];
+/// assert_eq!(right_encode(0, &mut buf), [0, 1]);
+/// assert_eq!(right_encode(256, &mut buf), [1, 0, 2]);
+/// ```
+pub fn right_encode(x: u64, buf: &mut [u8; 9]) -> &[u8]
+{
+    let n = encoded_len(x);
+    buf[0..8].copy_from_slice(&x.to_be_bytes());
+    buf.copy_within(8 - n..8, 0);
+    buf[n] = n as u8;
+    &buf[0..n + 1]
+}
+
+/// Number of bytes needed to encode `x`, which is at least 1.
+fn encoded_len(x: u64) -> usize { cmp::max(1, 8 - x.leading_zeros() as usize / 8) }
+
+/// Absorb `left_encode(x)`.
+fn absorb_left_encode<const RATE: usize>(sponge: &mut Sponge<RATE>, x: u64)
+{
+    sponge.absorb(left_encode(x, &mut [0; 9]));
+}
+
+/// Absorb `right_encode(x)`.
+fn absorb_right_encode<const RATE: usize>(sponge: &mut Sponge<RATE>, x: u64)
+{
+    sponge.absorb(right_encode(x, &mut [0; 9]));
+}
+
+/// Absorb `encode_string(s)`, which is the length of `s` in bits encoded with
+/// [`left_encode`] followed by `s`.
+fn absorb_string<const RATE: usize>(sponge: &mut Sponge<RATE>, s: &[u8])
+{
+    absorb_left_encode(sponge, s.len() as u64 * 8);
+    sponge.absorb(s);
+}
+
+/// Absorb `bytepad(X, RATE)`, where `X` is whatever `f` absorbs.
+///
+/// The sponge must be at the start of a block. Padding with zeros up to the
+/// end of the block doesn't change the state, so the block is just
+/// permuted.
+fn absorb_bytepad<const RATE: usize>(sponge: &mut Sponge<RATE>, f: impl FnOnce(&mut Sponge<RATE>))
+{
+    absorb_left_encode(sponge, RATE as u64);
+    f(sponge);
+    if sponge.index != 0 {
+        sponge.permute();
+        sponge.index = 0;
+    }
+}
+
+/// cSHAKE extendable-output function.
+///
+/// cSHAKE with an empty function name and customization string is the same
+/// as SHAKE.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sp800_185::*;
+/// let mut ctx = CShake128::with_customization(b"", b"Email Signature");
+/// ctx.update(&[0, 1, 2, 3]);
+/// let mut output = [0; 32];
+/// ctx.finish_xof().read(&mut output);
+/// assert_eq!(
+///     hex::encode(output),
+///     "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CShake<const RATE: usize>
+{
+    sponge: Sponge<RATE>,
+    pad:    u8,
+}
+
+/// cSHAKE128
+pub type CShake128 = CShake<168>;
+/// cSHAKE256
+pub type CShake256 = CShake<136>;
+
+impl<const RATE: usize> CShake<RATE>
+{
+    /// Create a new context with the function name and customization string.
+    ///
+    /// The function name is reserved for functions defined by NIST, so it
+    /// should be left empty by everything else.
+    pub fn with_customization(function_name: &[u8], customization: &[u8]) -> Self
+    {
+        let mut sponge = Sponge::new();
+        if function_name.is_empty() && customization.is_empty() {
+            return Self { sponge, pad: 0x1f };
+        }
+
+        absorb_bytepad(&mut sponge, |sponge| {
+            absorb_string(sponge, function_name);
+            absorb_string(sponge, customization);
+        });
+        Self { sponge, pad: 0x04 }
+    }
+
+    /// Pad the absorbed data and return the sponge to squeeze the output from.
+    fn finish_sponge(&mut self) -> &mut Sponge<RATE>
+    {
+        self.sponge.pad(self.pad);
+        &mut self.sponge
+    }
+}
+
+impl<const RATE: usize> New for CShake<RATE>
+{
+    fn new() -> Self { Self::with_customization(b"", b"") }
+}
+
+impl<const RATE: usize> Update for CShake<RATE>
+{
+    fn update(&mut self, data: &[u8]) { self.sponge.absorb(data); }
+}
+
+impl<const RATE: usize> ExtendableOutput for CShake<RATE>
+{
+    type Reader = SpongeReader<RATE>;
+
+    fn finish_xof(mut self) -> Self::Reader { SpongeReader::new(*self.finish_sponge()) }
+}
+
+/// KMAC context with a digest length of `DIGEST_LEN` bytes.
+///
+/// The digest length is part of the input of KMAC, so reading the first
+/// `DIGEST_LEN` bytes from [`finish_xof`](`ExtendableOutput::finish_xof`)
+/// won't result in the same digest.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sp800_185::*;
+/// let key: Vec<u8> = (0x40..0x60).collect();
+/// let mut ctx = Kmac128::with_customization(&key, b"My Tagged Application");
+/// ctx.update(&[0, 1, 2, 3]);
+/// assert_eq!(
+///     hex::encode(ctx.finish()),
+///     "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Kmac<const RATE: usize, const DIGEST_LEN: usize>
+{
+    ctx:    CShake<RATE>,
+    /// State after absorbing the key, used to reset the context.
+    keyed:  CShake<RATE>,
+    /// State after absorbing the customization string, used to set a new key.
+    custom: CShake<RATE>,
+}
+
+/// KMAC128
+pub type Kmac128 = Kmac<168, 32>;
+/// KMAC256
+pub type Kmac256 = Kmac<136, 64>;
+
+impl<const RATE: usize, const DIGEST_LEN: usize> DigestMeta for Kmac<RATE, DIGEST_LEN>
+{
+    const BLOCK_LEN: usize = RATE;
+    const DIGEST_LEN: usize = DIGEST_LEN;
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Kmac<RATE, DIGEST_LEN>
+{
+    /// Create a new context using the key.
+    pub fn with_key(key: &[u8]) -> Self { Self::with_customization(key, b"") }
+
+    /// Create a new context using the key and customization string.
+    pub fn with_customization(key: &[u8], customization: &[u8]) -> Self
+    {
+        let custom = CShake::with_customization(b"KMAC", customization);
+        let mut ctx = Self {
+            ctx: custom,
+            keyed: custom,
+            custom,
+        };
+        ctx.set_key(key);
+        ctx
+    }
+
+    /// Set the key.
+    ///
+    /// This process will reset the inner state of the context as well as the
+    /// key. The customization string is kept.
+    pub fn set_key(&mut self, key: &[u8])
+    {
+        self.keyed = self.custom;
+        absorb_bytepad(&mut self.keyed.sponge, |sponge| absorb_string(sponge, key));
+        self.ctx = self.keyed;
+    }
+
+    /// Oneshot KMAC function.
+    pub fn oneshot(data: &[u8], key: &[u8]) -> [u8; DIGEST_LEN]
+    {
+        let mut ctx = Self::with_key(key);
+        ctx.update(data);
+        let mut digest = [0; DIGEST_LEN];
+        ctx.finish_to_slice(&mut digest);
+        digest
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> FinishInternal for Kmac<RATE, DIGEST_LEN>
+{
+    /// Finish the KMAC calculation and return a reference to the inner state.
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        absorb_right_encode(&mut self.ctx.sponge, DIGEST_LEN as u64 * 8);
+        self.ctx.finish_sponge().digest(DIGEST_LEN)
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Verify for Kmac<RATE, DIGEST_LEN> {}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Reset for Kmac<RATE, DIGEST_LEN>
+{
+    /// Reset the context to the state right after the key was set.
+    fn reset(&mut self) { self.ctx = self.keyed; }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> SetKey for Kmac<RATE, DIGEST_LEN>
+{
+    fn with_key(key: &[u8]) -> Self { Self::with_key(key) }
+
+    fn set_key(&mut self, key: &[u8]) { self.set_key(key); }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Update for Kmac<RATE, DIGEST_LEN>
+{
+    /// Update the inner state.
+    fn update(&mut self, data: &[u8]) { self.ctx.update(data); }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> ExtendableOutput for Kmac<RATE, DIGEST_LEN>
+{
+    type Reader = SpongeReader<RATE>;
+
+    /// Finish the calculation in XOF mode, which encodes the output length as
+    /// zero.
+    fn finish_xof(mut self) -> Self::Reader
+    {
+        absorb_right_encode(&mut self.ctx.sponge, 0);
+        self.ctx.finish_xof()
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> core::hash::Hasher for Kmac<RATE, DIGEST_LEN>
+{
+    fn finish(&self) -> u64
+    {
+        // The state is copied here, since finishing consumes the context.
+        let mut digest: [u8; 8] = [0; 8];
+        (*self).finish_to_slice(&mut digest);
+        u64::from_be_bytes(digest)
+    }
+
+    fn write(&mut self, bytes: &[u8]) { self.update(bytes); }
+}
+
+#[cfg(any(feature = "std", doc))]
+#[doc(cfg(feature = "std"))]
+impl<const RATE: usize, const DIGEST_LEN: usize> std::io::Write for Kmac<RATE, DIGEST_LEN>
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize>
+    {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()>
+    {
+        self.update(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+/// TupleHash context with a digest length of `DIGEST_LEN` bytes.
+///
+/// The elements of the tuple are encoded unambiguously, so `("ab", "c")` and
+/// `("a", "bc")` hash to different values.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sp800_185::*;
+/// let mut ctx = TupleHash128::with_customization(b"My Tuple App");
+/// ctx.push(&[0x00, 0x01, 0x02]);
+/// ctx.push(&[0x10, 0x11, 0x12, 0x13, 0x14, 0x15]);
+/// assert_eq!(
+///     hex::encode(ctx.finish()),
+///     "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TupleHash<const RATE: usize, const DIGEST_LEN: usize>
+{
+    ctx: CShake<RATE>,
+}
+
+/// TupleHash128
+pub type TupleHash128 = TupleHash<168, 32>;
+/// TupleHash256
+pub type TupleHash256 = TupleHash<136, 64>;
+
+impl<const RATE: usize, const DIGEST_LEN: usize> DigestMeta for TupleHash<RATE, DIGEST_LEN>
+{
+    const BLOCK_LEN: usize = RATE;
+    const DIGEST_LEN: usize = DIGEST_LEN;
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> TupleHash<RATE, DIGEST_LEN>
+{
+    /// Create a new context with the customization string.
+    pub fn with_customization(customization: &[u8]) -> Self
+    {
+        Self {
+            ctx: CShake::with_customization(b"TupleHash", customization),
+        }
+    }
+
+    /// Append an element to the tuple.
+    pub fn push(&mut self, element: &[u8]) { absorb_string(&mut self.ctx.sponge, element); }
+
+    /// Oneshot TupleHash function.
+    pub fn oneshot(tuple: &[&[u8]], customization: &[u8]) -> [u8; DIGEST_LEN]
+    {
+        let mut ctx = Self::with_customization(customization);
+        tuple.iter().for_each(|element| ctx.push(element));
+        let mut digest = [0; DIGEST_LEN];
+        ctx.finish_to_slice(&mut digest);
+        digest
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> New for TupleHash<RATE, DIGEST_LEN>
+{
+    fn new() -> Self { Self::with_customization(b"") }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> FinishInternal for TupleHash<RATE, DIGEST_LEN>
+{
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        absorb_right_encode(&mut self.ctx.sponge, DIGEST_LEN as u64 * 8);
+        self.ctx.finish_sponge().digest(DIGEST_LEN)
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> ExtendableOutput for TupleHash<RATE, DIGEST_LEN>
+{
+    type Reader = SpongeReader<RATE>;
+
+    fn finish_xof(mut self) -> Self::Reader
+    {
+        absorb_right_encode(&mut self.ctx.sponge, 0);
+        self.ctx.finish_xof()
+    }
+}
+
+/// ParallelHash context with a digest length of `DIGEST_LEN` bytes.
+///
+/// The input is split into blocks that are hashed independently and the
+/// chaining values of the blocks are hashed together. The blocks are hashed
+/// one after the other here, the result is the same as if they were hashed in
+/// parallel.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sp800_185::*;
+/// let data: Vec<u8> = (0x00..0x08).chain(0x10..0x18).chain(0x20..0x28).collect();
+/// let mut ctx = ParallelHash128::with_block_len(8, b"Parallel Data");
+/// ctx.update(&data);
+/// assert_eq!(
+///     hex::encode(ctx.finish()),
+///     "fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelHash<const RATE: usize, const DIGEST_LEN: usize>
+{
+    ctx:       CShake<RATE>,
+    block:     Shake<RATE>,
+    block_len: usize,
+    index:     usize,
+    blocks:    u64,
+}
+
+/// ParallelHash128
+pub type ParallelHash128 = ParallelHash<168, 32>;
+/// ParallelHash256
+pub type ParallelHash256 = ParallelHash<136, 64>;
+
+impl<const RATE: usize, const DIGEST_LEN: usize> DigestMeta for ParallelHash<RATE, DIGEST_LEN>
+{
+    const BLOCK_LEN: usize = RATE;
+    const DIGEST_LEN: usize = DIGEST_LEN;
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> ParallelHash<RATE, DIGEST_LEN>
+{
+    /// Length of the chaining values, which is twice the security strength.
+    const CHAIN_LEN: usize = 200 - RATE;
+
+    /// Create a new context that splits the input into blocks of `block_len`
+    /// bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_len` is zero.
+    pub fn with_block_len(block_len: usize, customization: &[u8]) -> Self
+    {
+        assert!(block_len != 0, "block length must not be zero");
+
+        let mut ctx = CShake::with_customization(b"ParallelHash", customization);
+        absorb_left_encode(&mut ctx.sponge, block_len as u64);
+        Self {
+            ctx,
+            block: Shake::new(),
+            block_len,
+            index: 0,
+            blocks: 0,
+        }
+    }
+
+    /// Oneshot ParallelHash function.
+    pub fn oneshot(data: &[u8], block_len: usize, customization: &[u8]) -> [u8; DIGEST_LEN]
+    {
+        let mut ctx = Self::with_block_len(block_len, customization);
+        ctx.update(data);
+        let mut digest = [0; DIGEST_LEN];
+        ctx.finish_to_slice(&mut digest);
+        digest
+    }
+
+    /// Absorb the chaining value of the current block.
+    fn finish_block(&mut self)
+    {
+        let mut chain = [0; 64];
+        mem::replace(&mut self.block, Shake::new())
+            .finish_xof()
+            .read(&mut chain[0..Self::CHAIN_LEN]);
+        self.ctx.update(&chain[0..Self::CHAIN_LEN]);
+        self.index = 0;
+        self.blocks += 1;
+    }
+
+    /// Absorb the last block, if it isn't empty, and the output length in bits.
+    fn finish_(&mut self, output_len: u64)
+    {
+        if self.index != 0 {
+            self.finish_block();
+        }
+        absorb_right_encode(&mut self.ctx.sponge, self.blocks);
+        absorb_right_encode(&mut self.ctx.sponge, output_len);
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Update for ParallelHash<RATE, DIGEST_LEN>
+{
+    fn update(&mut self, mut data: &[u8])
+    {
+        while !data.is_empty() {
+            let len = cmp::min(self.block_len - self.index, data.len());
+            self.block.update(&data[0..len]);
+            self.index += len;
+            data = &data[len..];
+
+            if self.index == self.block_len {
+                self.finish_block();
+            }
+        }
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> FinishInternal for ParallelHash<RATE, DIGEST_LEN>
+{
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        self.finish_(DIGEST_LEN as u64 * 8);
+        self.ctx.finish_sponge().digest(DIGEST_LEN)
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> ExtendableOutput for ParallelHash<RATE, DIGEST_LEN>
+{
+    type Reader = SpongeReader<RATE>;
+
+    fn finish_xof(mut self) -> Self::Reader
+    {
+        self.finish_(0);
+        self.ctx.finish_xof()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::digest::Finish;
+    use crate::hmac::HmacSha256;
+
+    const S: &[u8] = b"My Tagged Application";
+
+    fn kmac<const RATE: usize, const DIGEST_LEN: usize>(
+        data: &[u8],
+        customization: &[u8],
+    ) -> Kmac<RATE, DIGEST_LEN>
+    {
+        let key: Vec<u8> = (0x40..0x60).collect();
+        let mut ctx = Kmac::with_customization(&key, customization);
+        ctx.update(data);
+        ctx
+    }
+
+    fn xof(reader: impl XofReader, len: usize) -> String
+    {
+        let mut reader = reader;
+        let mut output = [0; 64];
+        reader.read(&mut output[0..len]);
+        hex::encode(&output[0..len])
+    }
+
+    /// Samples from the NIST examples for KMAC.
+    #[test]
+    fn kmac_samples()
+    {
+        let data: Vec<u8> = (0x00..0xc8).collect();
+
+        assert_eq!(
+            hex::encode(kmac::<168, 32>(&data[0..4], b"").finish()),
+            "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"
+        );
+        assert_eq!(
+            hex::encode(kmac::<168, 32>(&data[0..4], S).finish()),
+            "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"
+        );
+        assert_eq!(
+            hex::encode(kmac::<168, 32>(&data, S).finish()),
+            "1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230"
+        );
+        assert_eq!(
+            hex::encode(kmac::<136, 64>(&data[0..4], S).finish()),
+            "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7\
+             f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"
+        );
+        assert_eq!(
+            hex::encode(kmac::<136, 64>(&data, b"").finish()),
+            "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691\
+             589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69"
+        );
+        assert_eq!(
+            hex::encode(kmac::<136, 64>(&data, S).finish()),
+            "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9\
+             70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965"
+        );
+
+        assert_eq!(
+            xof(kmac::<168, 32>(&data[0..4], b"").finish_xof(), 32),
+            "cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35"
+        );
+        assert_eq!(
+            xof(kmac::<136, 64>(&data[0..4], S).finish_xof(), 64),
+            "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa9\
+             6faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b"
+        );
+    }
+
+    /// Setting the key and resetting keep the customization string.
+    #[test]
+    fn kmac_set_key()
+    {
+        let key: Vec<u8> = (0x40..0x60).collect();
+        let expected = kmac::<168, 32>(&[0, 1, 2, 3], S).finish();
+
+        let mut ctx = Kmac128::with_customization(b"another key", S);
+        ctx.update(b"some data");
+        ctx.set_key(&key);
+        ctx.update(&[0, 1, 2, 3]);
+        assert_eq!(ctx.finalize_reset(), expected);
+        ctx.update(&[0, 1, 2, 3]);
+        assert_eq!(ctx.finish(), expected);
+    }
+
+    /// Computes two MACs with the same context through the generic traits.
+    fn mac_twice<M>(key: &[u8]) -> ([u8; M::DIGEST_LEN], [u8; M::DIGEST_LEN])
+    where
+        M: SetKey + Reset + Update + Finish,
+    {
+        let mut ctx = M::with_key(b"wrong key");
+        ctx.update(b"ignored");
+        ctx.set_key(key);
+        ctx.update(b"first");
+        let first = ctx.finalize_reset();
+        ctx.update(b"second");
+        (first, ctx.finish())
+    }
+
+    #[test]
+    fn mac_traits()
+    {
+        assert_eq!(
+            mac_twice::<Kmac256>(b"key"),
+            (
+                Kmac256::oneshot(b"first", b"key"),
+                Kmac256::oneshot(b"second", b"key")
+            )
+        );
+        assert_eq!(
+            mac_twice::<HmacSha256>(b"key"),
+            (
+                HmacSha256::oneshot(b"first", b"key"),
+                HmacSha256::oneshot(b"second", b"key")
+            )
+        );
+    }
+
+    /// Samples from the NIST examples for TupleHash.
+    #[test]
+    fn tuple_hash_samples()
+    {
+        let tuple: [&[u8]; 3] = [
+            &[0x00, 0x01, 0x02],
+            &[0x10, 0x11, 0x12, 0x13, 0x14, 0x15],
+            &[0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28],
+        ];
+
+        assert_eq!(
+            hex::encode(TupleHash128::oneshot(&tuple[0..2], b"")),
+            "c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1"
+        );
+        assert_eq!(
+            hex::encode(TupleHash128::oneshot(&tuple[0..2], b"My Tuple App")),
+            "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb"
+        );
+        assert_eq!(
+            hex::encode(TupleHash128::oneshot(&tuple, b"My Tuple App")),
+            "e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84"
+        );
+        assert_eq!(
+            hex::encode(TupleHash256::oneshot(&tuple[0..2], b"")),
+            "cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec6073\
+             11ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194"
+        );
+    }
+
+    /// Samples from the NIST examples for ParallelHash, fed in pieces that
+    /// don't line up with the blocks.
+    #[test]
+    fn parallel_hash_samples()
+    {
+        let data: Vec<u8> = (0..6).flat_map(|i| 16 * i..16 * i + 12).collect();
+        let short: Vec<u8> = (0..3).flat_map(|i| 16 * i..16 * i + 8).collect();
+
+        assert_eq!(
+            hex::encode(ParallelHash128::oneshot(&short, 8, b"")),
+            "ba8dc1d1d979331d3f813603c67f72609ab5e44b94a0b8f9af46514454a2b4f5"
+        );
+        assert_eq!(
+            hex::encode(ParallelHash128::oneshot(&short, 8, b"Parallel Data")),
+            "fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206"
+        );
+        assert_eq!(
+            hex::encode(ParallelHash256::oneshot(&short, 8, b"")),
+            "bc1ef124da34495e948ead207dd9842235da432d2bbc54b4c110e64c45110553\
+             1b7f2a3e0ce055c02805e7c2de1fb746af97a1dd01f43b824e31b87612410429"
+        );
+
+        let mut ctx = ParallelHash128::with_block_len(12, b"Parallel Data");
+        data.chunks(5).for_each(|chunk| ctx.update(chunk));
+        assert_eq!(
+            hex::encode(ctx.finish()),
+            "f7fd5312896c6685c828af7e2adb97e393e7f8d54e3c2ea4b95e5aca3796e8fc"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! # [Sponge] construction
+//!
+//! Sponge construction absorbs the input into a state that is larger than
+//! the block size and permutes it after every block. Once all of the input is
+//! absorbed, the state is padded and an arbitrary amount of output can be
+//! squeezed out of it. The SHA-3 family of functions, standardized in
+//! [FIPS 202], use this construction with the Keccak-f\[1600\] permutation.
+//!
+//! [Sponge]: https://en.wikipedia.org/wiki/Sponge_function
+//! [FIPS 202]: https://doi.org/10.6028/NIST.FIPS.202
+
+use core::cmp;
+use core::slice;
+
+use oxicrypt_core::keccak;
+
+use crate::digest::DigestMeta;
+use crate::digest::ExtendableOutput;
+use crate::digest::FinishInternal;
+use crate::digest::Reset;
+use crate::digest::Update;
+use crate::digest::XofReader;
+use crate::traits::New;
+
+/// Generic Keccak-f\[1600\] sponge.
+///
+/// `RATE` is the number of bytes that are absorbed or squeezed between two
+/// permutations.
+#[derive(Debug, Clone, Copy)]
+pub struct Sponge<const RATE: usize>
+{
+    pub state: [u64; 25],
+    pub index: usize,
+}
+
+impl<const RATE: usize> Sponge<RATE>
+{
+    #[inline(always)]
+    pub const fn new() -> Self
+    {
+        Self {
+            state: [0; 25],
+            index: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn permute(&mut self) { unsafe { keccak::keccak_f1600(self.state.as_mut_ptr()) }; }
+
+    /// XOR `data` into the state starting from the byte at `offset`.
+    fn xor_bytes(&mut self, offset: usize, data: &[u8])
+    {
+        data.iter().enumerate().for_each(|(i, b)| {
+            self.state[(offset + i) / 8] ^= (*b as u64) << ((offset + i) % 8 * 8);
+        });
+    }
+
+    /// Absorb `data` into the state and permute it every time a block is
+    /// filled.
+    pub fn absorb(&mut self, mut data: &[u8])
+    {
+        // Fill the partially absorbed block first, if there is one.
+        if self.index != 0 {
+            let len = cmp::min(RATE - self.index, data.len());
+            self.xor_bytes(self.index, &data[0..len]);
+            self.index += len;
+            data = &data[len..];
+
+            // The block is still not full, so all of the data is read at this point.
+            if self.index != RATE {
+                return;
+            }
+
+            self.permute();
+            self.index = 0;
+        }
+
+        // Absorb full blocks a lane at a time.
+        while data.len() >= RATE {
+            self.state
+                .iter_mut()
+                .zip(data[0..RATE].chunks_exact(8))
+                .for_each(|(lane, bytes)| *lane ^= u64::from_le_bytes(bytes.try_into().unwrap()));
+            self.permute();
+            data = &data[RATE..];
+        }
+
+        self.xor_bytes(0, data);
+        self.index = data.len();
+    }
+
+    /// Pad the absorbed data and permute the state. The output can be
+    /// squeezed after calling this function.
+    ///
+    /// `pad` is the first byte of the padding, which also holds the domain
+    /// separation bits of the algorithm.
+    pub fn pad(&mut self, pad: u8)
+    {
+        self.xor_bytes(self.index, &[pad]);
+        self.xor_bytes(RATE - 1, &[0x80]);
+        self.permute();
+        self.index = 0;
+    }
+
+    /// Squeeze the next `buf.len()` bytes of output out of the state.
+    ///
+    /// [`pad`](`Self::pad`) must be called before squeezing.
+    pub fn squeeze(&mut self, mut buf: &mut [u8])
+    {
+        while !buf.is_empty() {
+            if self.index == RATE {
+                self.permute();
+                self.index = 0;
+            }
+
+            let len = cmp::min(RATE - self.index, buf.len());
+            buf[0..len].iter_mut().enumerate().for_each(|(i, b)| {
+                *b = (self.state[(self.index + i) / 8] >> ((self.index + i) % 8 * 8)) as u8;
+            });
+            self.index += len;
+            buf = &mut buf[len..];
+        }
+    }
+
+    /// Return the first `len` bytes of the state, which must not exceed the
+    /// rate.
+    ///
+    /// The lanes are converted to little endian in place, so the state can't be
+    /// squeezed any further after calling this function.
+    pub(crate) fn digest(&mut self, len: usize) -> &[u8]
+    {
+        self.state.iter_mut().for_each(|lane| *lane = lane.to_le());
+        unsafe { slice::from_raw_parts(self.state.as_ptr().cast(), len) }
+    }
+}
+
+/// SHA-3 hash function with a digest length of `DIGEST_LEN` bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sponge::*;
+/// let digest = Sha3_256::oneshot(b"abc");
+/// assert_eq!(
+///     hex::encode(digest),
+///     "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Sha3<const RATE: usize, const DIGEST_LEN: usize>
+{
+    pub sponge: Sponge<RATE>,
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Sha3<RATE, DIGEST_LEN>
+{
+    #[inline(always)]
+    pub const fn new() -> Self
+    {
+        Self {
+            sponge: Sponge::new(),
+        }
+    }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> const DigestMeta for Sha3<RATE, DIGEST_LEN>
+{
+    const BLOCK_LEN: usize = RATE;
+    const DIGEST_LEN: usize = DIGEST_LEN;
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> const New for Sha3<RATE, DIGEST_LEN>
+{
+    fn new() -> Self { Self::new() }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> const Reset for Sha3<RATE, DIGEST_LEN>
+{
+    fn reset(&mut self) { self.sponge = Sponge::new(); }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> Update for Sha3<RATE, DIGEST_LEN>
+{
+    fn update(&mut self, data: &[u8]) { self.sponge.absorb(data); }
+}
+
+impl<const RATE: usize, const DIGEST_LEN: usize> FinishInternal for Sha3<RATE, DIGEST_LEN>
+{
+    unsafe fn finish_internal(&mut self) -> &[u8]
+    {
+        self.sponge.pad(0x06);
+        self.sponge.digest(DIGEST_LEN)
+    }
+}
+
+/// SHAKE extendable-output function.
+///
+/// # Examples
+///
+/// The output can be read in pieces of any length.
+/// ```
+/// # use oxicrypt::digest::*;
+/// # use oxicrypt::sponge::*;
+/// let mut ctx = Shake128::new();
+/// ctx.update(b"abc");
+/// let mut reader = ctx.finish_xof();
+/// let mut output = [0; 40];
+/// reader.read(&mut output[0..7]);
+/// reader.read(&mut output[7..]);
+/// assert_eq!(
+///     hex::encode(output),
+///     "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc844c50af32acd3f2c"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Shake<const RATE: usize>
+{
+    pub sponge: Sponge<RATE>,
+}
+
+impl<const RATE: usize> Shake<RATE>
+{
+    #[inline(always)]
+    pub const fn new() -> Self
+    {
+        Self {
+            sponge: Sponge::new(),
+        }
+    }
+}
+
+impl<const RATE: usize> const New for Shake<RATE>
+{
+    fn new() -> Self { Self::new() }
+}
+
+impl<const RATE: usize> const Reset for Shake<RATE>
+{
+    fn reset(&mut self) { self.sponge = Sponge::new(); }
+}
+
+impl<const RATE: usize> Update for Shake<RATE>
+{
+    fn update(&mut self, data: &[u8]) { self.sponge.absorb(data); }
+}
+
+impl<const RATE: usize> ExtendableOutput for Shake<RATE>
+{
+    type Reader = SpongeReader<RATE>;
+
+    fn finish_xof(mut self) -> Self::Reader
+    {
+        self.sponge.pad(0x1f);
+        SpongeReader::new(self.sponge)
+    }
+}
+
+/// Reader that squeezes the output of a [`Sponge`].
+#[derive(Debug, Clone, Copy)]
+pub struct SpongeReader<const RATE: usize>
+{
+    sponge: Sponge<RATE>,
+}
+
+impl<const RATE: usize> SpongeReader<RATE>
+{
+    /// Create a reader from a sponge that has already been padded.
+    #[inline(always)]
+    pub const fn new(sponge: Sponge<RATE>) -> Self { Self { sponge } }
+}
+
+impl<const RATE: usize> XofReader for SpongeReader<RATE>
+{
+    fn read(&mut self, buf: &mut [u8]) { self.sponge.squeeze(buf); }
+}
+
+// Sha3<RATE, DIGEST_LEN>
+pub type Sha3_224 = Sha3<144, 28>;
+pub type Sha3_256 = Sha3<136, 32>;
+pub type Sha3_384 = Sha3<104, 48>;
+pub type Sha3_512 = Sha3<72, 64>;
+
+// Shake<RATE>
+pub type Shake128 = Shake<168>;
+pub type Shake256 = Shake<136>;
+
+#[cfg(test)]
+mod tests
+{
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::digest::Oneshot;
+
+    macro_rules! check {
+        ($md:ty, $($data:expr => $digest:literal),* $(,)?) => {$(
+            assert_eq!(hex::encode(<$md>::oneshot($data)), $digest);
+        )*};
+    }
+
+    /// The messages are the empty string, "abc", 200 bytes of 0xa3 from the
+    /// FIPS 202 examples, and messages around the rate.
+    #[test]
+    fn fips202()
+    {
+        let a3 = [0xa3; 200];
+        let data: Vec<u8> = (0..=255).collect();
+
+        check!(
+            Sha3_224,
+            b"" => "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+            b"abc" => "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+            &a3 => "9376816aba503f72f96ce7eb65ac095deee3be4bf9bbc2a1cb7e11e0",
+            &data[0..143] => "64d0e8a1be3cf30ef6727b30a6e428f7f068d44634c943d277ad8e7f",
+            &data[0..144] => "5be75e6a08f19913a1d8036c056cc4556b98dc90aeca3f2a0664dedc",
+            &data[0..145] => "90b861ac1b1598459ad8337afa9933ce2f1a6f972c57daf8fc2737e4",
+        );
+        check!(
+            Sha3_384,
+            b"" => "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2a\
+                    c3713831264adb47fb6bd1e058d5f004",
+            b"abc" => "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2\
+                       98d88cea927ac7f539f1edf228376d25",
+            &a3 => "1881de2ca7e41ef95dc4732b8f5f002b189cc1e42b74168ed1732649ce1dbcdd\
+                    76197a31fd55ee989f2d7050dd473e8f",
+            &data[0..103] => "1f91ee551ad18f268876d1fc262f137fe196580216c5193819a95ec5222537d2\
+                              a658dd129c3d8080e65ec7460f1f4704",
+            &data[0..104] => "5b8d0d5cf8b41be507be8fcbfcbdbac3a28eb368d430fed6780aaa78a93a8da4\
+                              a6c50485949ca344f228be91a96005a3",
+            &data[0..105] => "4a2f0a8f2f1f4cc4605cc2537e0be28cf8b465c30f0a54b494a7128ec54ee4e8\
+                              5706b5e47a5697344d15cbf85680cd40",
+        );
+        check!(
+            Sha3_512,
+            b"" => "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+                    15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+            b"abc" => "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+                       10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+            &a3 => "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8\
+                    1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00",
+            &data[0..71] => "3ccc850d53a1287af7b4560b2ef0d43eb5d9a80d62a0e9cf1dbc040135921104\
+                             d4395168e90bfc871773ebb34bca1bd67056e1cc7dc7a48ff7c3167d389f117c",
+            &data[0..72] => "5d63f2bbe971a983ac6847480106e4e1264ee3a0befd79954914e1d86e795b2e\
+                             18238f12fc5e46cb9cc78efdec610a93647cc04e1c23d8caaa6a58c21dd26c07",
+            &data[0..73] => "921d9b7b2b0f3066a1646dbb058c979cb3925dec0f8c269faaa7f9648e73465a\
+                             e55ec527257d5d5e1cfdbf5d6799bea1004b6186f5108c74e3b92fe924166558",
+        );
+    }
+
+    #[test]
+    fn shake256()
+    {
+        let data: Vec<u8> = (0..=255).collect();
+        for (data, expected) in [
+            (
+                &b""[..],
+                "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+                 d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+            ),
+            (
+                b"abc",
+                "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+                 d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4",
+            ),
+            (
+                &data[0..136],
+                "b7ff4073b3f5a8eabd6e17705ca7f6761a31058f9df781a6a47e3a3063b9d67a\
+                 757e8dbf043dac48d2154e46d59c0b9e8bc36ba035153691fbe83b9eff5dae4a",
+            ),
+        ] {
+            let mut ctx = Shake256::new();
+            ctx.update(data);
+            let mut output = [0; 64];
+            ctx.finish_xof().read(&mut output);
+            assert_eq!(hex::encode(output), expected);
+        }
+
+        // Output of several blocks, read in pieces that cross the rate.
+        let expected = "cd8a920ed141aa0407a22d59288652e9d9f1a7ee0c1e7c1ca699424da84a904d\
+                        2d700caae7396ece96604440577da4f3aa22aeb8857f961c4cd8e06f0ae6610b\
+                        1048a7f64e1074cd629e85ad7566048efc4fb500b486a3309a8f26724c0ed628\
+                        001a1099422468de726f1061d99eb9e93604d5aa7467d4b1bd6484582a384317\
+                        d7f47d750b8f5499512bb85a226c4243556e696f6bd072c5aa2d9b69730244b5\
+                        6853d16970ad817e213e470618178001c9fb56c54fefa5fee67d2da524bb3b0b\
+                        61ef0e9114a92cdbb6cccb98615cfe76e3510dd88d1cc28ff99287512f24bfaf\
+                        a1a76877b6f37198e3a641c68a7c42d45fa7acc10dae5f3cefb7b735f12d4e58\
+                        9f7a456e78c0f5e4c4471fffa5e4fa0514ae974d8c2648513b5db494cea84715\
+                        6d277ad0e141c24c7839064c";
+        let mut ctx = Shake256::new();
+        ctx.update(&[0xa3; 200]);
+        let mut reader = ctx.finish_xof();
+        let mut output = [0; 300];
+        for range in [0..1, 1..136, 136..272, 272..300] {
+            reader.read(&mut output[range]);
+        }
+        assert_eq!(hex::encode(output), expected);
+    }
+}
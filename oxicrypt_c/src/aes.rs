@@ -95,3 +95,113 @@ pub unsafe extern "C" fn oxi_aes256_decrypt(ctx: &Key256, block: *mut u8, blockl
 {
     ctx.decrypt_unchecked(slice::from_raw_parts_mut(block, blocklen * 16))
 }
+
+// AES ENCRYPT/DECRYPT BLOCKS
+#[no_mangle]
+pub unsafe extern "C" fn oxi_aes128_encrypt_blocks(
+    ctx: &Key128,
+    input: *const u8,
+    output: *mut u8,
+    blocklen: usize,
+)
+{
+    // A shared and a mutable slice can't point to the same memory.
+    if input == output.cast_const() {
+        ctx.encrypt_unchecked(slice::from_raw_parts_mut(output, blocklen * 16))
+    } else {
+        ctx.encrypt_blocks_unchecked(
+            slice::from_raw_parts(input, blocklen * 16),
+            slice::from_raw_parts_mut(output, blocklen * 16),
+        )
+    }
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_aes192_encrypt_blocks(
+    ctx: &Key192,
+    input: *const u8,
+    output: *mut u8,
+    blocklen: usize,
+)
+{
+    // A shared and a mutable slice can't point to the same memory.
+    if input == output.cast_const() {
+        ctx.encrypt_unchecked(slice::from_raw_parts_mut(output, blocklen * 16))
+    } else {
+        ctx.encrypt_blocks_unchecked(
+            slice::from_raw_parts(input, blocklen * 16),
+            slice::from_raw_parts_mut(output, blocklen * 16),
+        )
+    }
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_aes256_encrypt_blocks(
+    ctx: &Key256,
+    input: *const u8,
+    output: *mut u8,
+    blocklen: usize,
+)
+{
+    // A shared and a mutable slice can't point to the same memory.
+    if input == output.cast_const() {
+        ctx.encrypt_unchecked(slice::from_raw_parts_mut(output, blocklen * 16))
+    } else {
+        ctx.encrypt_blocks_unchecked(
+            slice::from_raw_parts(input, blocklen * 16),
+            slice::from_raw_parts_mut(output, blocklen * 16),
+        )
+    }
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_aes128_decrypt_blocks(
+    ctx: &Key128,
+    input: *const u8,
+    output: *mut u8,
+    blocklen: usize,
+)
+{
+    // A shared and a mutable slice can't point to the same memory.
+    if input == output.cast_const() {
+        ctx.decrypt_unchecked(slice::from_raw_parts_mut(output, blocklen * 16))
+    } else {
+        ctx.decrypt_blocks_unchecked(
+            slice::from_raw_parts(input, blocklen * 16),
+            slice::from_raw_parts_mut(output, blocklen * 16),
+        )
+    }
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_aes192_decrypt_blocks(
+    ctx: &Key192,
+    input: *const u8,
+    output: *mut u8,
+    blocklen: usize,
+)
+{
+    // A shared and a mutable slice can't point to the same memory.
+    if input == output.cast_const() {
+        ctx.decrypt_unchecked(slice::from_raw_parts_mut(output, blocklen * 16))
+    } else {
+        ctx.decrypt_blocks_unchecked(
+            slice::from_raw_parts(input, blocklen * 16),
+            slice::from_raw_parts_mut(output, blocklen * 16),
+        )
+    }
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_aes256_decrypt_blocks(
+    ctx: &Key256,
+    input: *const u8,
+    output: *mut u8,
+    blocklen: usize,
+)
+{
+    // A shared and a mutable slice can't point to the same memory.
+    if input == output.cast_const() {
+        ctx.decrypt_unchecked(slice::from_raw_parts_mut(output, blocklen * 16))
+    } else {
+        ctx.decrypt_blocks_unchecked(
+            slice::from_raw_parts(input, blocklen * 16),
+            slice::from_raw_parts_mut(output, blocklen * 16),
+        )
+    }
+}
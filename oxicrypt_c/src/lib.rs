@@ -12,3 +12,4 @@ pub mod aesni;
 pub mod digest;
 pub mod hmac;
 pub mod md_compress;
+pub mod sm4;
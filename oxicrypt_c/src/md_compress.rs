@@ -1,22 +1,59 @@
 use oxicrypt_core::md_compress;
 
 #[no_mangle]
-pub unsafe extern "C" fn oxi_digest_compress_md5(state: *mut u32, block: *const u8)
+pub unsafe extern "C" fn oxi_digest_compress_md4(state: *mut u32, block: *const u8, blocks: usize)
 {
-    md_compress::md5(state, block);
+    md_compress::md4(state, block, blocks);
 }
 #[no_mangle]
-pub unsafe extern "C" fn oxi_digest_compress_sha1(state: *mut u32, block: *const u8)
+pub unsafe extern "C" fn oxi_digest_compress_md5(state: *mut u32, block: *const u8, blocks: usize)
 {
-    md_compress::sha1(state, block);
+    md_compress::md5(state, block, blocks);
 }
 #[no_mangle]
-pub unsafe extern "C" fn oxi_digest_compress_sha256(state: *mut u32, block: *const u8)
+pub unsafe extern "C" fn oxi_digest_compress_ripemd160(
+    state: *mut u32,
+    block: *const u8,
+    blocks: usize,
+)
 {
-    md_compress::sha256(state, block);
+    md_compress::ripemd160(state, block, blocks);
 }
 #[no_mangle]
-pub unsafe extern "C" fn oxi_digest_compress_sha512(state: *mut u64, block: *const u8)
+pub unsafe extern "C" fn oxi_digest_compress_sha1(state: *mut u32, block: *const u8, blocks: usize)
 {
-    md_compress::sha512(state, block);
+    md_compress::sha1(state, block, blocks);
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_digest_compress_sha1dc(
+    state: *mut u32,
+    block: *const u8,
+    blocks: usize,
+    safe_hash: bool,
+) -> bool
+{
+    md_compress::sha1dc(state, block, blocks, safe_hash)
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_digest_compress_sha256(
+    state: *mut u32,
+    block: *const u8,
+    blocks: usize,
+)
+{
+    md_compress::sha256(state, block, blocks);
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_digest_compress_sha512(
+    state: *mut u64,
+    block: *const u8,
+    blocks: usize,
+)
+{
+    md_compress::sha512(state, block, blocks);
+}
+#[no_mangle]
+pub unsafe extern "C" fn oxi_digest_compress_sm3(state: *mut u32, block: *const u8, blocks: usize)
+{
+    md_compress::sm3(state, block, blocks);
 }
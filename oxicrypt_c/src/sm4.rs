@@ -0,0 +1,29 @@
+use oxicrypt_core::sm4;
+
+// SM4 ENCRYPT
+#[no_mangle]
+pub unsafe extern "C" fn oxi_core_sm4_encrypt1(block: *mut u8, key_schedule: *const u32)
+{
+    sm4::sm4_encrypt1(block, key_schedule);
+}
+
+// SM4 DECRYPT
+#[no_mangle]
+pub unsafe extern "C" fn oxi_core_sm4_decrypt1(block: *mut u8, key_schedule: *const u32)
+{
+    sm4::sm4_decrypt1(block, key_schedule);
+}
+
+// SM4 EXPAND KEY
+#[no_mangle]
+pub unsafe extern "C" fn oxi_core_sm4_expand_key(key: *const u8, key_schedule: *mut u32)
+{
+    sm4::sm4_expand_key(key, key_schedule);
+}
+
+// SM4 INVERSE KEY
+#[no_mangle]
+pub unsafe extern "C" fn oxi_core_sm4_inverse_key(key_schedule: *mut u32)
+{
+    sm4::sm4_inverse_key(key_schedule);
+}
@@ -4,12 +4,12 @@ use core::arch::aarch64::*;
 use core::arch::arm::*;
 
 #[inline(always)]
-unsafe fn decrypt1<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn decrypt1<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: uint8x16_t = vld1q_u8(key_schedule.add(0 * 16));
-    let mut b0: uint8x16_t = vld1q_u8(block.add(0 * 16));
+    let mut b0: uint8x16_t = vld1q_u8(input.add(0 * 16));
 
     for i in 1..ROUNDS {
         b0 = vaesdq_u8(b0, k0);
@@ -23,17 +23,17 @@ unsafe fn decrypt1<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     k0 = vld1q_u8(key_schedule.add(ROUNDS * 16));
     b0 = veorq_u8(b0, k0);
 
-    vst1q_u8(block, b0);
+    vst1q_u8(output.add(0 * 16), b0);
 }
 
 #[inline(always)]
-unsafe fn decrypt2<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn decrypt2<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: uint8x16_t = vld1q_u8(key_schedule.add(0 * 16));
-    let mut b0: uint8x16_t = vld1q_u8(block.add(0 * 16));
-    let mut b1: uint8x16_t = vld1q_u8(block.add(1 * 16));
+    let mut b0: uint8x16_t = vld1q_u8(input.add(0 * 16));
+    let mut b1: uint8x16_t = vld1q_u8(input.add(1 * 16));
 
     for i in 1..ROUNDS {
         b0 = vaesdq_u8(b0, k0);
@@ -51,20 +51,20 @@ unsafe fn decrypt2<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     b0 = veorq_u8(b0, k0);
     b1 = veorq_u8(b1, k0);
 
-    vst1q_u8(block, b0);
-    vst1q_u8(block, b1);
+    vst1q_u8(output.add(0 * 16), b0);
+    vst1q_u8(output.add(1 * 16), b1);
 }
 
 #[inline(always)]
-unsafe fn decrypt4<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn decrypt4<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: uint8x16_t = vld1q_u8(key_schedule.add(0 * 16));
-    let mut b0: uint8x16_t = vld1q_u8(block.add(0 * 16));
-    let mut b1: uint8x16_t = vld1q_u8(block.add(1 * 16));
-    let mut b2: uint8x16_t = vld1q_u8(block.add(2 * 16));
-    let mut b3: uint8x16_t = vld1q_u8(block.add(3 * 16));
+    let mut b0: uint8x16_t = vld1q_u8(input.add(0 * 16));
+    let mut b1: uint8x16_t = vld1q_u8(input.add(1 * 16));
+    let mut b2: uint8x16_t = vld1q_u8(input.add(2 * 16));
+    let mut b3: uint8x16_t = vld1q_u8(input.add(3 * 16));
 
     for i in 1..ROUNDS {
         b0 = vaesdq_u8(b0, k0);
@@ -90,26 +90,26 @@ unsafe fn decrypt4<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     b2 = veorq_u8(b2, k0);
     b3 = veorq_u8(b3, k0);
 
-    vst1q_u8(block, b0);
-    vst1q_u8(block, b1);
-    vst1q_u8(block, b2);
-    vst1q_u8(block, b3);
+    vst1q_u8(output.add(0 * 16), b0);
+    vst1q_u8(output.add(1 * 16), b1);
+    vst1q_u8(output.add(2 * 16), b2);
+    vst1q_u8(output.add(3 * 16), b3);
 }
 
 #[inline(always)]
-unsafe fn decrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn decrypt8<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: uint8x16_t = vld1q_u8(key_schedule.add(0 * 16));
-    let mut b0: uint8x16_t = vld1q_u8(block.add(0 * 16));
-    let mut b1: uint8x16_t = vld1q_u8(block.add(1 * 16));
-    let mut b2: uint8x16_t = vld1q_u8(block.add(2 * 16));
-    let mut b3: uint8x16_t = vld1q_u8(block.add(3 * 16));
-    let mut b4: uint8x16_t = vld1q_u8(block.add(4 * 16));
-    let mut b5: uint8x16_t = vld1q_u8(block.add(5 * 16));
-    let mut b6: uint8x16_t = vld1q_u8(block.add(6 * 16));
-    let mut b7: uint8x16_t = vld1q_u8(block.add(7 * 16));
+    let mut b0: uint8x16_t = vld1q_u8(input.add(0 * 16));
+    let mut b1: uint8x16_t = vld1q_u8(input.add(1 * 16));
+    let mut b2: uint8x16_t = vld1q_u8(input.add(2 * 16));
+    let mut b3: uint8x16_t = vld1q_u8(input.add(3 * 16));
+    let mut b4: uint8x16_t = vld1q_u8(input.add(4 * 16));
+    let mut b5: uint8x16_t = vld1q_u8(input.add(5 * 16));
+    let mut b6: uint8x16_t = vld1q_u8(input.add(6 * 16));
+    let mut b7: uint8x16_t = vld1q_u8(input.add(7 * 16));
 
     for i in 1..ROUNDS {
         b0 = vaesdq_u8(b0, k0);
@@ -151,14 +151,14 @@ unsafe fn decrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     b6 = veorq_u8(b6, k0);
     b7 = veorq_u8(b7, k0);
 
-    vst1q_u8(block, b0);
-    vst1q_u8(block, b1);
-    vst1q_u8(block, b2);
-    vst1q_u8(block, b3);
-    vst1q_u8(block, b4);
-    vst1q_u8(block, b5);
-    vst1q_u8(block, b6);
-    vst1q_u8(block, b7);
+    vst1q_u8(output.add(0 * 16), b0);
+    vst1q_u8(output.add(1 * 16), b1);
+    vst1q_u8(output.add(2 * 16), b2);
+    vst1q_u8(output.add(3 * 16), b3);
+    vst1q_u8(output.add(4 * 16), b4);
+    vst1q_u8(output.add(5 * 16), b5);
+    vst1q_u8(output.add(6 * 16), b6);
+    vst1q_u8(output.add(7 * 16), b7);
 }
 
 // AES128 DECRYPT
@@ -168,7 +168,15 @@ unsafe fn decrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes128_decrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt1::<10>(block, key_schedule);
+    decrypt1::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes128_decrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt1::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -176,7 +184,15 @@ pub unsafe fn aes128_decrypt1(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes128_decrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt2::<10>(block, key_schedule);
+    decrypt2::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes128_decrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt2::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -184,7 +200,15 @@ pub unsafe fn aes128_decrypt2(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes128_decrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt4::<10>(block, key_schedule);
+    decrypt4::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes128_decrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt4::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -192,7 +216,15 @@ pub unsafe fn aes128_decrypt4(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes128_decrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt8::<10>(block, key_schedule);
+    decrypt8::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes128_decrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt8::<10>(input, output, key_schedule);
 }
 
 // AES192 DECRYPT
@@ -202,7 +234,15 @@ pub unsafe fn aes128_decrypt8(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes192_decrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt1::<12>(block, key_schedule);
+    decrypt1::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes192_decrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt1::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -210,7 +250,15 @@ pub unsafe fn aes192_decrypt1(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes192_decrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt2::<12>(block, key_schedule);
+    decrypt2::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes192_decrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt2::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -218,7 +266,15 @@ pub unsafe fn aes192_decrypt2(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes192_decrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt4::<12>(block, key_schedule);
+    decrypt4::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes192_decrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt4::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -226,7 +282,15 @@ pub unsafe fn aes192_decrypt4(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes192_decrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt8::<12>(block, key_schedule);
+    decrypt8::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes192_decrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt8::<12>(input, output, key_schedule);
 }
 
 // AES256 DECRYPT
@@ -236,7 +300,15 @@ pub unsafe fn aes192_decrypt8(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes256_decrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt1::<14>(block, key_schedule);
+    decrypt1::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes256_decrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt1::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -244,7 +316,15 @@ pub unsafe fn aes256_decrypt1(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes256_decrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt2::<14>(block, key_schedule);
+    decrypt2::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes256_decrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt2::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -252,7 +332,15 @@ pub unsafe fn aes256_decrypt2(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes256_decrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt4::<14>(block, key_schedule);
+    decrypt4::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes256_decrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt4::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "neon")]
@@ -260,7 +348,15 @@ pub unsafe fn aes256_decrypt4(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
 pub unsafe fn aes256_decrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    decrypt8::<14>(block, key_schedule);
+    decrypt8::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn aes256_decrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    decrypt8::<14>(input, output, key_schedule);
 }
 
 #[cfg(test)]
@@ -339,4 +435,79 @@ mod tests
             assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
         }
     }
+
+    #[test]
+    fn aes128_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes128 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.inversed_key.as_ptr();
+            let input = vectors.ciphertext.as_ptr();
+
+            unsafe {
+                aes128_decrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes128_decrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes128_decrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes128_decrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.plaintext_chunks()[0..1]);
+            assert_eq!(block2, vectors.plaintext_chunks()[0..2]);
+            assert_eq!(block4, vectors.plaintext_chunks()[0..4]);
+            assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
+        }
+    }
+
+    #[test]
+    fn aes192_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes192 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.inversed_key.as_ptr();
+            let input = vectors.ciphertext.as_ptr();
+
+            unsafe {
+                aes192_decrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes192_decrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes192_decrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes192_decrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.plaintext_chunks()[0..1]);
+            assert_eq!(block2, vectors.plaintext_chunks()[0..2]);
+            assert_eq!(block4, vectors.plaintext_chunks()[0..4]);
+            assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
+        }
+    }
+
+    #[test]
+    fn aes256_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes256 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.inversed_key.as_ptr();
+            let input = vectors.ciphertext.as_ptr();
+
+            unsafe {
+                aes256_decrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes256_decrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes256_decrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes256_decrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.plaintext_chunks()[0..1]);
+            assert_eq!(block2, vectors.plaintext_chunks()[0..2]);
+            assert_eq!(block4, vectors.plaintext_chunks()[0..4]);
+            assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
+        }
+    }
 }
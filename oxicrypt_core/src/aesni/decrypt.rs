@@ -4,12 +4,16 @@ use core::arch::x86::*;
 use core::arch::x86_64::*;
 
 #[inline(always)]
-unsafe fn aes_decrypt1<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn aes_decrypt1<const ROUNDS: usize>(
+    input: *const u8,
+    output: *mut u8,
+    key_schedule: *const u8,
+)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
 
     b0 = _mm_xor_si128(b0, k0);
 
@@ -21,17 +25,21 @@ unsafe fn aes_decrypt1<const ROUNDS: usize>(block: *mut u8, key_schedule: *const
     k0 = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(ROUNDS));
     b0 = _mm_aesdeclast_si128(b0, k0);
 
-    _mm_storeu_si128((block.cast::<__m128i>()).add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
 }
 
 #[inline(always)]
-unsafe fn aes_decrypt2<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn aes_decrypt2<const ROUNDS: usize>(
+    input: *const u8,
+    output: *mut u8,
+    key_schedule: *const u8,
+)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
-    let mut b1: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(1));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
+    let mut b1: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(1));
 
     b0 = _mm_xor_si128(b0, k0);
     b1 = _mm_xor_si128(b1, k0);
@@ -46,20 +54,24 @@ unsafe fn aes_decrypt2<const ROUNDS: usize>(block: *mut u8, key_schedule: *const
     b0 = _mm_aesdeclast_si128(b0, k0);
     b1 = _mm_aesdeclast_si128(b1, k0);
 
-    _mm_storeu_si128((block.cast::<__m128i>()).add(0), b0);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(1), b1);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(1), b1);
 }
 
 #[inline(always)]
-unsafe fn aes_decrypt4<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn aes_decrypt4<const ROUNDS: usize>(
+    input: *const u8,
+    output: *mut u8,
+    key_schedule: *const u8,
+)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
-    let mut b1: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(1));
-    let mut b2: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(2));
-    let mut b3: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(3));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
+    let mut b1: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(1));
+    let mut b2: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(2));
+    let mut b3: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(3));
 
     b0 = _mm_xor_si128(b0, k0);
     b1 = _mm_xor_si128(b1, k0);
@@ -80,26 +92,30 @@ unsafe fn aes_decrypt4<const ROUNDS: usize>(block: *mut u8, key_schedule: *const
     b2 = _mm_aesdeclast_si128(b2, k0);
     b3 = _mm_aesdeclast_si128(b3, k0);
 
-    _mm_storeu_si128((block.cast::<__m128i>()).add(0), b0);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(1), b1);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(2), b2);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(3), b3);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(1), b1);
+    _mm_storeu_si128(output.cast::<__m128i>().add(2), b2);
+    _mm_storeu_si128(output.cast::<__m128i>().add(3), b3);
 }
 
 #[inline(always)]
-unsafe fn aes_decrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn aes_decrypt8<const ROUNDS: usize>(
+    input: *const u8,
+    output: *mut u8,
+    key_schedule: *const u8,
+)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
-    let mut b1: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(1));
-    let mut b2: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(2));
-    let mut b3: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(3));
-    let mut b4: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(4));
-    let mut b5: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(5));
-    let mut b6: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(6));
-    let mut b7: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(7));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
+    let mut b1: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(1));
+    let mut b2: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(2));
+    let mut b3: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(3));
+    let mut b4: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(4));
+    let mut b5: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(5));
+    let mut b6: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(6));
+    let mut b7: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(7));
 
     b0 = _mm_xor_si128(b0, k0);
     b1 = _mm_xor_si128(b1, k0);
@@ -132,14 +148,14 @@ unsafe fn aes_decrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const
     b6 = _mm_aesdeclast_si128(b6, k0);
     b7 = _mm_aesdeclast_si128(b7, k0);
 
-    _mm_storeu_si128((block.cast::<__m128i>()).add(0), b0);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(1), b1);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(2), b2);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(3), b3);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(4), b4);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(5), b5);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(6), b6);
-    _mm_storeu_si128((block.cast::<__m128i>()).add(7), b7);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(1), b1);
+    _mm_storeu_si128(output.cast::<__m128i>().add(2), b2);
+    _mm_storeu_si128(output.cast::<__m128i>().add(3), b3);
+    _mm_storeu_si128(output.cast::<__m128i>().add(4), b4);
+    _mm_storeu_si128(output.cast::<__m128i>().add(5), b5);
+    _mm_storeu_si128(output.cast::<__m128i>().add(6), b6);
+    _mm_storeu_si128(output.cast::<__m128i>().add(7), b7);
 }
 
 // AES128 DECRYPT
@@ -148,28 +164,56 @@ unsafe fn aes_decrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_decrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt1::<10>(block, key_schedule);
+    aes_decrypt1::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_decrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt1::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_decrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt2::<10>(block, key_schedule);
+    aes_decrypt2::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_decrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt2::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_decrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt4::<10>(block, key_schedule);
+    aes_decrypt4::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_decrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt4::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_decrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt8::<10>(block, key_schedule);
+    aes_decrypt8::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_decrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt8::<10>(input, output, key_schedule);
 }
 
 // AES192 DECRYPT
@@ -178,28 +222,56 @@ pub unsafe fn aes128_decrypt8(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_decrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt1::<12>(block, key_schedule);
+    aes_decrypt1::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_decrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt1::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_decrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt2::<12>(block, key_schedule);
+    aes_decrypt2::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_decrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt2::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_decrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt4::<12>(block, key_schedule);
+    aes_decrypt4::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_decrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt4::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_decrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt8::<12>(block, key_schedule);
+    aes_decrypt8::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_decrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt8::<12>(input, output, key_schedule);
 }
 
 // AES256 DECRYPT
@@ -208,28 +280,56 @@ pub unsafe fn aes192_decrypt8(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_decrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt1::<14>(block, key_schedule);
+    aes_decrypt1::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_decrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt1::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_decrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt2::<14>(block, key_schedule);
+    aes_decrypt2::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_decrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt2::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_decrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt4::<14>(block, key_schedule);
+    aes_decrypt4::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_decrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt4::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_decrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    aes_decrypt8::<14>(block, key_schedule);
+    aes_decrypt8::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_decrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    aes_decrypt8::<14>(input, output, key_schedule);
 }
 
 #[cfg(test)]
@@ -308,4 +408,79 @@ mod tests
             assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
         }
     }
+
+    #[test]
+    fn aes128_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes128 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.inversed_key.as_ptr();
+            let input = vectors.ciphertext.as_ptr();
+
+            unsafe {
+                aes128_decrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes128_decrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes128_decrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes128_decrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.plaintext_chunks()[0..1]);
+            assert_eq!(block2, vectors.plaintext_chunks()[0..2]);
+            assert_eq!(block4, vectors.plaintext_chunks()[0..4]);
+            assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
+        }
+    }
+
+    #[test]
+    fn aes192_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes192 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.inversed_key.as_ptr();
+            let input = vectors.ciphertext.as_ptr();
+
+            unsafe {
+                aes192_decrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes192_decrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes192_decrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes192_decrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.plaintext_chunks()[0..1]);
+            assert_eq!(block2, vectors.plaintext_chunks()[0..2]);
+            assert_eq!(block4, vectors.plaintext_chunks()[0..4]);
+            assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
+        }
+    }
+
+    #[test]
+    fn aes256_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes256 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.inversed_key.as_ptr();
+            let input = vectors.ciphertext.as_ptr();
+
+            unsafe {
+                aes256_decrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes256_decrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes256_decrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes256_decrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.plaintext_chunks()[0..1]);
+            assert_eq!(block2, vectors.plaintext_chunks()[0..2]);
+            assert_eq!(block4, vectors.plaintext_chunks()[0..4]);
+            assert_eq!(block8, vectors.plaintext_chunks()[0..8]);
+        }
+    }
 }
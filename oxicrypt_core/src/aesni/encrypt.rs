@@ -4,12 +4,12 @@ use core::arch::x86::*;
 use core::arch::x86_64::*;
 
 #[inline(always)]
-unsafe fn encrypt1<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn encrypt1<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
 
     b0 = _mm_xor_si128(b0, k0);
 
@@ -21,17 +21,17 @@ unsafe fn encrypt1<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     k0 = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(ROUNDS));
     b0 = _mm_aesenclast_si128(b0, k0);
 
-    _mm_storeu_si128(block.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
 }
 
 #[inline(always)]
-unsafe fn encrypt2<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn encrypt2<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
-    let mut b1: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(1));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
+    let mut b1: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(1));
 
     b0 = _mm_xor_si128(b0, k0);
     b1 = _mm_xor_si128(b1, k0);
@@ -46,20 +46,20 @@ unsafe fn encrypt2<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     b0 = _mm_aesenclast_si128(b0, k0);
     b1 = _mm_aesenclast_si128(b1, k0);
 
-    _mm_storeu_si128(block.cast::<__m128i>().add(0), b0);
-    _mm_storeu_si128(block.cast::<__m128i>().add(1), b1);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(1), b1);
 }
 
 #[inline(always)]
-unsafe fn encrypt4<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn encrypt4<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
-    let mut b1: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(1));
-    let mut b2: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(2));
-    let mut b3: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(3));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
+    let mut b1: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(1));
+    let mut b2: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(2));
+    let mut b3: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(3));
 
     b0 = _mm_xor_si128(b0, k0);
     b1 = _mm_xor_si128(b1, k0);
@@ -80,26 +80,26 @@ unsafe fn encrypt4<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     b2 = _mm_aesenclast_si128(b2, k0);
     b3 = _mm_aesenclast_si128(b3, k0);
 
-    _mm_storeu_si128(block.cast::<__m128i>().add(0), b0);
-    _mm_storeu_si128(block.cast::<__m128i>().add(1), b1);
-    _mm_storeu_si128(block.cast::<__m128i>().add(2), b2);
-    _mm_storeu_si128(block.cast::<__m128i>().add(3), b3);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(1), b1);
+    _mm_storeu_si128(output.cast::<__m128i>().add(2), b2);
+    _mm_storeu_si128(output.cast::<__m128i>().add(3), b3);
 }
 
 #[inline(always)]
-unsafe fn encrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
+unsafe fn encrypt8<const ROUNDS: usize>(input: *const u8, output: *mut u8, key_schedule: *const u8)
 {
     debug_assert!(ROUNDS == 10 || ROUNDS == 12 || ROUNDS == 14);
 
     let mut k0: __m128i = _mm_loadu_si128(key_schedule.cast::<__m128i>().add(0));
-    let mut b0: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(0));
-    let mut b1: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(1));
-    let mut b2: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(2));
-    let mut b3: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(3));
-    let mut b4: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(4));
-    let mut b5: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(5));
-    let mut b6: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(6));
-    let mut b7: __m128i = _mm_loadu_si128(block.cast::<__m128i>().add(7));
+    let mut b0: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(0));
+    let mut b1: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(1));
+    let mut b2: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(2));
+    let mut b3: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(3));
+    let mut b4: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(4));
+    let mut b5: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(5));
+    let mut b6: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(6));
+    let mut b7: __m128i = _mm_loadu_si128(input.cast::<__m128i>().add(7));
 
     b0 = _mm_xor_si128(b0, k0);
     b1 = _mm_xor_si128(b1, k0);
@@ -132,14 +132,14 @@ unsafe fn encrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
     b6 = _mm_aesenclast_si128(b6, k0);
     b7 = _mm_aesenclast_si128(b7, k0);
 
-    _mm_storeu_si128(block.cast::<__m128i>().add(0), b0);
-    _mm_storeu_si128(block.cast::<__m128i>().add(1), b1);
-    _mm_storeu_si128(block.cast::<__m128i>().add(2), b2);
-    _mm_storeu_si128(block.cast::<__m128i>().add(3), b3);
-    _mm_storeu_si128(block.cast::<__m128i>().add(4), b4);
-    _mm_storeu_si128(block.cast::<__m128i>().add(5), b5);
-    _mm_storeu_si128(block.cast::<__m128i>().add(6), b6);
-    _mm_storeu_si128(block.cast::<__m128i>().add(7), b7);
+    _mm_storeu_si128(output.cast::<__m128i>().add(0), b0);
+    _mm_storeu_si128(output.cast::<__m128i>().add(1), b1);
+    _mm_storeu_si128(output.cast::<__m128i>().add(2), b2);
+    _mm_storeu_si128(output.cast::<__m128i>().add(3), b3);
+    _mm_storeu_si128(output.cast::<__m128i>().add(4), b4);
+    _mm_storeu_si128(output.cast::<__m128i>().add(5), b5);
+    _mm_storeu_si128(output.cast::<__m128i>().add(6), b6);
+    _mm_storeu_si128(output.cast::<__m128i>().add(7), b7);
 }
 
 // AES128 ENCRYPT
@@ -148,28 +148,56 @@ unsafe fn encrypt8<const ROUNDS: usize>(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_encrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt1::<10>(block, key_schedule);
+    encrypt1::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_encrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt1::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_encrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt2::<10>(block, key_schedule);
+    encrypt2::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_encrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt2::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_encrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt4::<10>(block, key_schedule);
+    encrypt4::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_encrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt4::<10>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes128_encrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt8::<10>(block, key_schedule);
+    encrypt8::<10>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes128_encrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt8::<10>(input, output, key_schedule);
 }
 
 // AES192 ENCRYPT
@@ -178,28 +206,56 @@ pub unsafe fn aes128_encrypt8(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_encrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt1::<12>(block, key_schedule);
+    encrypt1::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_encrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt1::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_encrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt2::<12>(block, key_schedule);
+    encrypt2::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_encrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt2::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_encrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt4::<12>(block, key_schedule);
+    encrypt4::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_encrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt4::<12>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes192_encrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt8::<12>(block, key_schedule);
+    encrypt8::<12>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes192_encrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt8::<12>(input, output, key_schedule);
 }
 
 // AES256 ENCRYPT
@@ -208,28 +264,56 @@ pub unsafe fn aes192_encrypt8(block: *mut u8, key_schedule: *const u8)
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_encrypt1(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt1::<14>(block, key_schedule);
+    encrypt1::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_encrypt1_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt1::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_encrypt2(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt2::<14>(block, key_schedule);
+    encrypt2::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_encrypt2_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt2::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_encrypt4(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt4::<14>(block, key_schedule);
+    encrypt4::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_encrypt4_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt4::<14>(input, output, key_schedule);
 }
 
 #[target_feature(enable = "aes")]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub unsafe fn aes256_encrypt8(block: *mut u8, key_schedule: *const u8)
 {
-    encrypt8::<14>(block, key_schedule);
+    encrypt8::<14>(block, block, key_schedule);
+}
+
+#[target_feature(enable = "aes")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn aes256_encrypt8_to(input: *const u8, output: *mut u8, key_schedule: *const u8)
+{
+    encrypt8::<14>(input, output, key_schedule);
 }
 
 #[cfg(test)]
@@ -308,4 +392,79 @@ mod tests
             assert_eq!(block8, vectors.ciphertext_chunks()[0..8]);
         }
     }
+
+    #[test]
+    fn aes128_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes128 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.expanded_key.as_ptr();
+            let input = vectors.plaintext.as_ptr();
+
+            unsafe {
+                aes128_encrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes128_encrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes128_encrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes128_encrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.ciphertext_chunks()[0..1]);
+            assert_eq!(block2, vectors.ciphertext_chunks()[0..2]);
+            assert_eq!(block4, vectors.ciphertext_chunks()[0..4]);
+            assert_eq!(block8, vectors.ciphertext_chunks()[0..8]);
+        }
+    }
+
+    #[test]
+    fn aes192_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes192 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.expanded_key.as_ptr();
+            let input = vectors.plaintext.as_ptr();
+
+            unsafe {
+                aes192_encrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes192_encrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes192_encrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes192_encrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.ciphertext_chunks()[0..1]);
+            assert_eq!(block2, vectors.ciphertext_chunks()[0..2]);
+            assert_eq!(block4, vectors.ciphertext_chunks()[0..4]);
+            assert_eq!(block8, vectors.ciphertext_chunks()[0..8]);
+        }
+    }
+
+    #[test]
+    fn aes256_to()
+    {
+        for vectors in AesVectorsIterator::<{ Aes::Aes256 }>::new() {
+            let mut block1 = [[0; 16]; 1];
+            let mut block2 = [[0; 16]; 2];
+            let mut block4 = [[0; 16]; 4];
+            let mut block8 = [[0; 16]; 8];
+            let key = vectors.expanded_key.as_ptr();
+            let input = vectors.plaintext.as_ptr();
+
+            unsafe {
+                aes256_encrypt1_to(input, block1.as_mut_ptr() as _, key);
+                aes256_encrypt2_to(input, block2.as_mut_ptr() as _, key);
+                aes256_encrypt4_to(input, block4.as_mut_ptr() as _, key);
+                aes256_encrypt8_to(input, block8.as_mut_ptr() as _, key);
+            }
+
+            assert_eq!(block1, vectors.ciphertext_chunks()[0..1]);
+            assert_eq!(block2, vectors.ciphertext_chunks()[0..2]);
+            assert_eq!(block4, vectors.ciphertext_chunks()[0..4]);
+            assert_eq!(block8, vectors.ciphertext_chunks()[0..8]);
+        }
+    }
 }
@@ -0,0 +1,138 @@
+//! Compression function used by Argon2, which is built on the round function of
+//! BLAKE2b.
+
+/// BlaMka variant of the BLAKE2b G function, which adds the product of the low
+/// 32 bits of the operands to every addition.
+const fn gb(v: &mut [u64; 128], a: usize, b: usize, c: usize, d: usize)
+{
+    const fn fma(x: u64, y: u64) -> u64
+    {
+        x.wrapping_add(y)
+            .wrapping_add(2u64.wrapping_mul(x as u32 as u64 * y as u32 as u64))
+    }
+
+    v[a] = fma(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = fma(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = fma(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = fma(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// Permutation P, which is one round of BLAKE2b without the message, on the 16
+/// words of `v` at the indices `i`.
+const fn permute(v: &mut [u64; 128], i: &[usize; 16])
+{
+    gb(v, i[0], i[4], i[8], i[12]);
+    gb(v, i[1], i[5], i[9], i[13]);
+    gb(v, i[2], i[6], i[10], i[14]);
+    gb(v, i[3], i[7], i[11], i[15]);
+    gb(v, i[0], i[5], i[10], i[15]);
+    gb(v, i[1], i[6], i[11], i[12]);
+    gb(v, i[2], i[7], i[8], i[13]);
+    gb(v, i[3], i[4], i[9], i[14]);
+}
+
+/// Compression function G of Argon2.
+///
+/// The 1024-byte blocks are stored as 128 words. `x` and `y` are XORed and
+/// viewed as a matrix of 8 by 8 pairs of words, the permutation P is applied to
+/// each row and then to each column, and the result is XORed with the input. If
+/// `xor` is set, the output is XORed into `dst` instead of overwriting it.
+///
+/// You shouldn't use this function unless you want to implement the algorithms
+/// by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `dst`, `x` and `y` must point to arrays with a length of 128 (1024
+/// bytes). `dst` may overlap with `x` or `y`.
+pub const unsafe fn argon2_compress(dst: *mut u64, x: *const u64, y: *const u64, xor: bool)
+{
+    let mut r: [u64; 128] = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        r[i] = *x.add(i) ^ *y.add(i);
+        i += 1;
+    }
+
+    let mut z: [u64; 128] = r;
+    let mut row = 0;
+    while row < 8 {
+        let mut idx: [usize; 16] = [0; 16];
+        let mut k = 0;
+        while k < 16 {
+            idx[k] = 16 * row + k;
+            k += 1;
+        }
+        permute(&mut z, &idx);
+        row += 1;
+    }
+    let mut col = 0;
+    while col < 8 {
+        let mut idx: [usize; 16] = [0; 16];
+        let mut k = 0;
+        while k < 8 {
+            idx[2 * k] = 16 * k + 2 * col;
+            idx[2 * k + 1] = 16 * k + 2 * col + 1;
+            k += 1;
+        }
+        permute(&mut z, &idx);
+        col += 1;
+    }
+
+    let mut i = 0;
+    while i < 128 {
+        let out = r[i] ^ z[i];
+        *dst.add(i) = if xor { *dst.add(i) ^ out } else { out };
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn compress()
+    {
+        let x: [u64; 128] = core::array::from_fn(|i| (i as u64).wrapping_mul(0x0123456789abcdef));
+        let y: [u64; 128] = core::array::from_fn(|i| {
+            (!(i as u64))
+                .wrapping_mul(0xfedcba9876543210)
+                .wrapping_add(7)
+        });
+
+        let mut out: [u64; 128] = [0; 128];
+        unsafe { argon2_compress(out.as_mut_ptr(), x.as_ptr(), y.as_ptr(), false) };
+        assert_eq!(
+            out[0..4],
+            [
+                0xac43123fb36a4d41,
+                0xbba3cd659a3aee0c,
+                0x266e28c90b108725,
+                0xb2df42f5cefe9d34
+            ]
+        );
+        assert_eq!(out[127], 0x61b00b37b2284ce7);
+
+        let mut xored: [u64; 128] = core::array::from_fn(|i| i as u64);
+        unsafe { argon2_compress(xored.as_mut_ptr(), x.as_ptr(), y.as_ptr(), true) };
+        for (i, (xored, out)) in xored.iter().zip(&out).enumerate() {
+            assert_eq!(*xored, out ^ i as u64);
+        }
+
+        // The output may overwrite one of the inputs.
+        let ptr = out.as_mut_ptr();
+        unsafe { argon2_compress(ptr, ptr, x.as_ptr(), false) };
+        assert_eq!(out[0..2], [0xb34986fce5937aae, 0x47a574a6237d8c46]);
+
+        let zero: [u64; 128] = [0; 128];
+        unsafe { argon2_compress(out.as_mut_ptr(), zero.as_ptr(), zero.as_ptr(), false) };
+        assert_eq!(out, zero);
+    }
+}
@@ -0,0 +1,234 @@
+//! Compression functions used by the BLAKE2 family of functions.
+
+/// Initialization vector of BLAKE2b, which is the same as the one used by
+/// SHA-512.
+pub const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Initialization vector of BLAKE2s, which is the same as the one used by
+/// SHA-256.
+pub const BLAKE2S_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Message word permutations used by the rounds. BLAKE2b has 12 rounds, so the
+/// first two permutations are reused by the last two rounds.
+#[rustfmt::skip]
+const SIGMA: [[usize; 16]; 10] = [
+    [ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+    [14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
+    [11,  8, 12,  0,  5,  2, 15, 13, 10, 14,  3,  6,  7,  1,  9,  4],
+    [ 7,  9,  3,  1, 13, 12, 11, 14,  2,  6,  5, 10,  4,  0, 15,  8],
+    [ 9,  0,  5,  7,  2,  4, 10, 15, 14,  1, 11, 12,  6,  8,  3, 13],
+    [ 2, 12,  6, 10,  0, 11,  8,  3,  4, 13,  7,  5, 15, 14,  1,  9],
+    [12,  5,  1, 15, 14, 13,  4, 10,  0,  7,  6,  3,  9,  2,  8, 11],
+    [13, 11,  7, 14, 12,  1,  3,  9,  5,  0, 15,  4,  8,  6,  2, 10],
+    [ 6, 15, 14,  9, 11,  3,  0,  8, 12,  2, 13,  7,  1,  4, 10,  5],
+    [10,  2,  8,  4,  7,  6,  1,  5, 15, 11,  9, 14,  3, 12, 13,  0],
+];
+
+/// Mixing function G, which mixes the words `a`, `b`, `c` and `d` of `v` with
+/// the message words `x` and `y`.
+macro_rules! blake2_g {
+    ($v:ident, $a:expr, $b:expr, $c:expr, $d:expr, $x:expr, $y:expr, $r1:expr, $r2:expr, $r3:expr, $r4:expr) => {{
+        $v[$a] = $v[$a].wrapping_add($v[$b]).wrapping_add($x);
+        $v[$d] = ($v[$d] ^ $v[$a]).rotate_right($r1);
+        $v[$c] = $v[$c].wrapping_add($v[$d]);
+        $v[$b] = ($v[$b] ^ $v[$c]).rotate_right($r2);
+        $v[$a] = $v[$a].wrapping_add($v[$b]).wrapping_add($y);
+        $v[$d] = ($v[$d] ^ $v[$a]).rotate_right($r3);
+        $v[$c] = $v[$c].wrapping_add($v[$d]);
+        $v[$b] = ($v[$b] ^ $v[$c]).rotate_right($r4);
+    }};
+}
+
+/// One round of BLAKE2, which mixes the columns and then the diagonals of `v`.
+macro_rules! blake2_round {
+    ($v:ident, $m:ident, $s:expr, $r1:expr, $r2:expr, $r3:expr, $r4:expr) => {{
+        let s: &[usize; 16] = $s;
+        blake2_g!($v, 0, 4, 8, 12, $m[s[0]], $m[s[1]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 1, 5, 9, 13, $m[s[2]], $m[s[3]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 2, 6, 10, 14, $m[s[4]], $m[s[5]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 3, 7, 11, 15, $m[s[6]], $m[s[7]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 0, 5, 10, 15, $m[s[8]], $m[s[9]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 1, 6, 11, 12, $m[s[10]], $m[s[11]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 2, 7, 8, 13, $m[s[12]], $m[s[13]], $r1, $r2, $r3, $r4);
+        blake2_g!($v, 3, 4, 9, 14, $m[s[14]], $m[s[15]], $r1, $r2, $r3, $r4);
+    }};
+}
+
+/// Compression function used by BLAKE2b.
+///
+/// `counter` is the number of bytes hashed so far, including the bytes of this
+/// block. `last_block` must be set for the final block and `last_node` must
+/// be set for the final block of the last node of a level in tree hashing
+/// mode.
+///
+/// You shouldn't use this function unless you want to implement the
+/// algorithms by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 8 (64 bytes).
+/// `block` must point to an array with a length of 128 bytes.
+pub const unsafe fn blake2b(
+    state: *mut u64,
+    block: *const u8,
+    counter: u128,
+    last_block: bool,
+    last_node: bool,
+)
+{
+    let mut m: [u64; 16] = [0; 16];
+    let mut v: [u64; 16] = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        m[i] = u64::from_le_bytes(*(block as *const [u8; 8]).add(i));
+        i += 1;
+    }
+    let mut i = 0;
+    while i < 8 {
+        v[i] = *state.add(i);
+        v[i + 8] = BLAKE2B_IV[i];
+        i += 1;
+    }
+
+    v[12] ^= counter as u64;
+    v[13] ^= (counter >> 64) as u64;
+    if last_block {
+        v[14] = !v[14];
+    }
+    if last_node {
+        v[15] = !v[15];
+    }
+
+    let mut round = 0;
+    while round < 12 {
+        blake2_round!(v, m, &SIGMA[round % 10], 32, 24, 16, 63);
+        round += 1;
+    }
+
+    let mut i = 0;
+    while i < 8 {
+        *state.add(i) ^= v[i] ^ v[i + 8];
+        i += 1;
+    }
+}
+
+/// Compression function used by BLAKE2s.
+///
+/// `counter` is the number of bytes hashed so far, including the bytes of this
+/// block. `last_block` must be set for the final block and `last_node` must
+/// be set for the final block of the last node of a level in tree hashing
+/// mode.
+///
+/// You shouldn't use this function unless you want to implement the
+/// algorithms by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 8 (32 bytes).
+/// `block` must point to an array with a length of 64 bytes.
+pub const unsafe fn blake2s(
+    state: *mut u32,
+    block: *const u8,
+    counter: u64,
+    last_block: bool,
+    last_node: bool,
+)
+{
+    let mut m: [u32; 16] = [0; 16];
+    let mut v: [u32; 16] = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        m[i] = u32::from_le_bytes(*(block as *const [u8; 4]).add(i));
+        i += 1;
+    }
+    let mut i = 0;
+    while i < 8 {
+        v[i] = *state.add(i);
+        v[i + 8] = BLAKE2S_IV[i];
+        i += 1;
+    }
+
+    v[12] ^= counter as u32;
+    v[13] ^= (counter >> 32) as u32;
+    if last_block {
+        v[14] = !v[14];
+    }
+    if last_node {
+        v[15] = !v[15];
+    }
+
+    let mut round = 0;
+    while round < 10 {
+        blake2_round!(v, m, &SIGMA[round], 16, 12, 8, 7);
+        round += 1;
+    }
+
+    let mut i = 0;
+    while i < 8 {
+        *state.add(i) ^= v[i] ^ v[i + 8];
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn blake2b_abc()
+    {
+        // Sequential mode parameter block with a digest length of 64 bytes.
+        let mut state: [u64; 8] = BLAKE2B_IV;
+        state[0] ^= 0x01010040;
+        let mut block: [u8; 128] = [0; 128];
+        block[0..3].copy_from_slice(b"abc");
+
+        unsafe { blake2b(state.as_mut_ptr(), block.as_ptr(), 3, true, false) };
+        assert_eq!(
+            state,
+            [
+                0x0d4d1c983fa580ba,
+                0xe9f6129fb697276a,
+                0xb7c45a68142f214c,
+                0xd1a2ffdb6fbb124b,
+                0x2d79ab2a39c5877d,
+                0x95cc3345ded552c2,
+                0x5a92f1dba88ad318,
+                0x239900d4ed8623b9,
+            ]
+        );
+    }
+
+    #[test]
+    fn blake2s_abc()
+    {
+        // Sequential mode parameter block with a digest length of 32 bytes.
+        let mut state: [u32; 8] = BLAKE2S_IV;
+        state[0] ^= 0x01010020;
+        let mut block: [u8; 64] = [0; 64];
+        block[0..3].copy_from_slice(b"abc");
+
+        unsafe { blake2s(state.as_mut_ptr(), block.as_ptr(), 3, true, false) };
+        assert_eq!(
+            state,
+            [
+                0x8c5e8c50, 0xe2147c32, 0xa32ba7e1, 0x2f45eb4e, 0x208b4537, 0x293ad69e, 0x4c9b994d,
+                0x82596786,
+            ]
+        );
+    }
+}
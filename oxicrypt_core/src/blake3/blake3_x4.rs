@@ -0,0 +1,210 @@
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+#[cfg(target_arch = "arm")]
+use core::arch::arm::*;
+
+use super::BLOCK_LEN;
+use super::CHUNK_END;
+use super::CHUNK_START;
+use super::IV;
+use super::MSG_SCHEDULE;
+use super::PARENT;
+
+macro_rules! rotr {
+    ($x:expr, 16) => {
+        vreinterpretq_u32_u16(vrev32q_u16(vreinterpretq_u16_u32($x)))
+    };
+    ($x:expr, $n:literal) => {
+        vorrq_u32(vshrq_n_u32($x, $n), vshlq_n_u32($x, 32 - $n))
+    };
+}
+
+/// Transposes a 4x4 matrix of 32-bit words, where each vector is a row.
+macro_rules! transpose {
+    ($r:expr) => {{
+        let r: &mut [uint32x4_t; 4] = $r;
+        let t01: uint32x4x2_t = vtrnq_u32(r[0], r[1]);
+        let t23: uint32x4x2_t = vtrnq_u32(r[2], r[3]);
+
+        r[0] = vcombine_u32(vget_low_u32(t01.0), vget_low_u32(t23.0));
+        r[1] = vcombine_u32(vget_low_u32(t01.1), vget_low_u32(t23.1));
+        r[2] = vcombine_u32(vget_high_u32(t01.0), vget_high_u32(t23.0));
+        r[3] = vcombine_u32(vget_high_u32(t01.1), vget_high_u32(t23.1));
+    }};
+}
+
+/// Mixing function G on four lanes.
+macro_rules! g {
+    ($v:ident, $a:literal, $b:literal, $c:literal, $d:literal, $x:expr, $y:expr) => {{
+        $v[$a] = vaddq_u32(vaddq_u32($v[$a], $v[$b]), $x);
+        $v[$d] = rotr!(veorq_u32($v[$d], $v[$a]), 16);
+        $v[$c] = vaddq_u32($v[$c], $v[$d]);
+        $v[$b] = rotr!(veorq_u32($v[$b], $v[$c]), 12);
+        $v[$a] = vaddq_u32(vaddq_u32($v[$a], $v[$b]), $y);
+        $v[$d] = rotr!(veorq_u32($v[$d], $v[$a]), 8);
+        $v[$c] = vaddq_u32($v[$c], $v[$d]);
+        $v[$b] = rotr!(veorq_u32($v[$b], $v[$c]), 7);
+    }};
+}
+
+/// Compression function used by BLAKE3 that hashes four independent inputs
+/// in parallel using NEON.
+///
+/// Every input is made up of `blocks` full blocks that are compressed into a
+/// chaining value starting from `key`, with `counters[i]` as the counter of
+/// the i-th input. Unless `flags` contains [`PARENT`](`super::PARENT`), the
+/// first block of every input is compressed with
+/// [`CHUNK_START`](`super::CHUNK_START`) and the last one with
+/// [`CHUNK_END`](`super::CHUNK_END`), so that whole chunks can be hashed.
+///
+/// Each of the four lanes produces the same result as calling
+/// [`blake3`](`super::blake3`) on the blocks of `inputs[i]` one after the
+/// other.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `key` must point to an array with a length of 8 (32 bytes). Every
+/// pointer in `inputs` must point to `blocks` consecutive blocks, each with a
+/// length of 64 bytes. Every pointer in `outs` must point to an array with a
+/// length of 8 (32 bytes). The CPU must support the `neon` feature.
+#[target_feature(enable = "neon")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn blake3_x4(
+    inputs: [*const u8; 4],
+    blocks: usize,
+    key: *const u32,
+    counters: [u64; 4],
+    flags: u32,
+    outs: [*mut u32; 4],
+)
+{
+    // `h[i]` holds the i-th word of the chaining value of every lane.
+    let mut h: [uint32x4_t; 8] = [vdupq_n_u32(0); 8];
+    for (i, h) in h.iter_mut().enumerate() {
+        *h = vdupq_n_u32(*key.add(i));
+    }
+    let counter_lo: [u32; 4] = counters.map(|c| c as u32);
+    let counter_hi: [u32; 4] = counters.map(|c| (c >> 32) as u32);
+    let counter_lo: uint32x4_t = vld1q_u32(counter_lo.as_ptr());
+    let counter_hi: uint32x4_t = vld1q_u32(counter_hi.as_ptr());
+
+    for block in 0..blocks {
+        let mut block_flags: u32 = flags;
+        if flags & PARENT == 0 && block == 0 {
+            block_flags |= CHUNK_START;
+        }
+        if flags & PARENT == 0 && block == blocks - 1 {
+            block_flags |= CHUNK_END;
+        }
+
+        // Likewise, `m[i]` holds the i-th message word of every lane.
+        let mut m: [uint32x4_t; 16] = [vdupq_n_u32(0); 16];
+        for quarter in 0..4 {
+            let mut r: [uint32x4_t; 4] = [vdupq_n_u32(0); 4];
+            for (r, input) in r.iter_mut().zip(inputs) {
+                *r = vreinterpretq_u32_u8(vld1q_u8(input.add(block * BLOCK_LEN + quarter * 16)));
+            }
+            transpose!(&mut r);
+            m[quarter * 4..quarter * 4 + 4].copy_from_slice(&r);
+        }
+
+        let mut v: [uint32x4_t; 16] = [
+            h[0],
+            h[1],
+            h[2],
+            h[3],
+            h[4],
+            h[5],
+            h[6],
+            h[7],
+            vdupq_n_u32(IV[0]),
+            vdupq_n_u32(IV[1]),
+            vdupq_n_u32(IV[2]),
+            vdupq_n_u32(IV[3]),
+            counter_lo,
+            counter_hi,
+            vdupq_n_u32(BLOCK_LEN as u32),
+            vdupq_n_u32(block_flags),
+        ];
+
+        for s in MSG_SCHEDULE.iter() {
+            g!(v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g!(v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g!(v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g!(v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+            g!(v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g!(v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g!(v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g!(v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+
+        for (i, h) in h.iter_mut().enumerate() {
+            *h = veorq_u32(v[i], v[i + 8]);
+        }
+    }
+
+    let mut lo: [uint32x4_t; 4] = [h[0], h[1], h[2], h[3]];
+    let mut hi: [uint32x4_t; 4] = [h[4], h[5], h[6], h[7]];
+    transpose!(&mut lo);
+    transpose!(&mut hi);
+    for ((lo, hi), out) in lo.iter().zip(hi.iter()).zip(outs) {
+        vst1q_u32(out.add(0), *lo);
+        vst1q_u32(out.add(4), *hi);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::blake3;
+
+    #[test]
+    fn blake3_x4_compress()
+    {
+        let mut input = [0u8; 1024 * 4];
+        input
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, b)| *b = (i % 251) as u8);
+        let key: [u32; 8] = core::array::from_fn(|i| IV[i] ^ (i as u32 * 0x01010101));
+
+        for (blocks, flags) in [(16, blake3::KEYED_HASH), (1, PARENT), (3, 0)] {
+            let inputs: [*const u8; 4] =
+                core::array::from_fn(|i| input[i * blocks * 64..].as_ptr());
+            let counters: [u64; 4] = core::array::from_fn(|i| 1 << 32 | (i as u64 * 3));
+            let mut cvs_generic: [[u32; 8]; 4] = [key; 4];
+            let mut cvs_neon: [[u32; 8]; 4] = [[0; 8]; 4];
+            unsafe {
+                for i in 0..4 {
+                    for block in 0..blocks {
+                        let mut block_flags: u32 = flags;
+                        if flags & PARENT == 0 && block == 0 {
+                            block_flags |= CHUNK_START;
+                        }
+                        if flags & PARENT == 0 && block == blocks - 1 {
+                            block_flags |= CHUNK_END;
+                        }
+                        blake3::blake3(
+                            cvs_generic[i].as_mut_ptr(),
+                            inputs[i].add(block * 64),
+                            64,
+                            counters[i],
+                            block_flags,
+                        );
+                    }
+                }
+                blake3_x4(
+                    inputs,
+                    blocks,
+                    key.as_ptr(),
+                    counters,
+                    flags,
+                    core::array::from_fn(|i| cvs_neon[i].as_mut_ptr()),
+                );
+            }
+            assert_eq!(cvs_generic, cvs_neon);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::BLOCK_LEN;
+use super::CHUNK_END;
+use super::CHUNK_START;
+use super::IV;
+use super::MSG_SCHEDULE;
+use super::PARENT;
+
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        _mm256_or_si256(_mm256_srli_epi32($x, $n), _mm256_slli_epi32($x, 32 - $n))
+    };
+}
+
+/// Transposes an 8x8 matrix of 32-bit words, where each vector is a row.
+macro_rules! transpose {
+    ($r:expr) => {{
+        let r: &mut [__m256i; 8] = $r;
+        let t0: __m256i = _mm256_unpacklo_epi32(r[0], r[1]);
+        let t1: __m256i = _mm256_unpackhi_epi32(r[0], r[1]);
+        let t2: __m256i = _mm256_unpacklo_epi32(r[2], r[3]);
+        let t3: __m256i = _mm256_unpackhi_epi32(r[2], r[3]);
+        let t4: __m256i = _mm256_unpacklo_epi32(r[4], r[5]);
+        let t5: __m256i = _mm256_unpackhi_epi32(r[4], r[5]);
+        let t6: __m256i = _mm256_unpacklo_epi32(r[6], r[7]);
+        let t7: __m256i = _mm256_unpackhi_epi32(r[6], r[7]);
+
+        let u0: __m256i = _mm256_unpacklo_epi64(t0, t2);
+        let u1: __m256i = _mm256_unpackhi_epi64(t0, t2);
+        let u2: __m256i = _mm256_unpacklo_epi64(t1, t3);
+        let u3: __m256i = _mm256_unpackhi_epi64(t1, t3);
+        let u4: __m256i = _mm256_unpacklo_epi64(t4, t6);
+        let u5: __m256i = _mm256_unpackhi_epi64(t4, t6);
+        let u6: __m256i = _mm256_unpacklo_epi64(t5, t7);
+        let u7: __m256i = _mm256_unpackhi_epi64(t5, t7);
+
+        r[0] = _mm256_permute2x128_si256(u0, u4, 0x20);
+        r[1] = _mm256_permute2x128_si256(u1, u5, 0x20);
+        r[2] = _mm256_permute2x128_si256(u2, u6, 0x20);
+        r[3] = _mm256_permute2x128_si256(u3, u7, 0x20);
+        r[4] = _mm256_permute2x128_si256(u0, u4, 0x31);
+        r[5] = _mm256_permute2x128_si256(u1, u5, 0x31);
+        r[6] = _mm256_permute2x128_si256(u2, u6, 0x31);
+        r[7] = _mm256_permute2x128_si256(u3, u7, 0x31);
+    }};
+}
+
+/// Mixing function G on eight lanes. Rotations by 16 and 8 bits are byte
+/// shuffles.
+macro_rules! g {
+    ($v:ident, $a:literal, $b:literal, $c:literal, $d:literal, $x:expr, $y:expr, $rot16:ident, $rot8:ident) => {{
+        $v[$a] = _mm256_add_epi32(_mm256_add_epi32($v[$a], $v[$b]), $x);
+        $v[$d] = _mm256_shuffle_epi8(_mm256_xor_si256($v[$d], $v[$a]), $rot16);
+        $v[$c] = _mm256_add_epi32($v[$c], $v[$d]);
+        $v[$b] = rotr!(_mm256_xor_si256($v[$b], $v[$c]), 12);
+        $v[$a] = _mm256_add_epi32(_mm256_add_epi32($v[$a], $v[$b]), $y);
+        $v[$d] = _mm256_shuffle_epi8(_mm256_xor_si256($v[$d], $v[$a]), $rot8);
+        $v[$c] = _mm256_add_epi32($v[$c], $v[$d]);
+        $v[$b] = rotr!(_mm256_xor_si256($v[$b], $v[$c]), 7);
+    }};
+}
+
+/// Compression function used by BLAKE3 that hashes eight independent inputs
+/// in parallel using AVX2.
+///
+/// Every input is made up of `blocks` full blocks that are compressed into a
+/// chaining value starting from `key`, with `counters[i]` as the counter of
+/// the i-th input. Unless `flags` contains [`PARENT`](`super::PARENT`), the
+/// first block of every input is compressed with
+/// [`CHUNK_START`](`super::CHUNK_START`) and the last one with
+/// [`CHUNK_END`](`super::CHUNK_END`), so that whole chunks can be hashed.
+///
+/// Each of the eight lanes produces the same result as calling
+/// [`blake3`](`super::blake3`) on the blocks of `inputs[i]` one after the
+/// other.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `key` must point to an array with a length of 8 (32 bytes). Every
+/// pointer in `inputs` must point to `blocks` consecutive blocks, each with a
+/// length of 64 bytes. Every pointer in `outs` must point to an array with a
+/// length of 8 (32 bytes). The CPU must support the `avx2` feature.
+#[target_feature(enable = "avx2")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn blake3_x8(
+    inputs: [*const u8; 8],
+    blocks: usize,
+    key: *const u32,
+    counters: [u64; 8],
+    flags: u32,
+    outs: [*mut u32; 8],
+)
+{
+    // Rotate each 32-bit word right by 16 and 8 bits.
+    let rot16: __m256i = _mm256_set_epi64x(
+        0x0d0c0f0e09080b0a,
+        0x0504070601000302,
+        0x0d0c0f0e09080b0a,
+        0x0504070601000302,
+    );
+    let rot8: __m256i = _mm256_set_epi64x(
+        0x0c0f0e0d080b0a09,
+        0x0407060500030201,
+        0x0c0f0e0d080b0a09,
+        0x0407060500030201,
+    );
+
+    // `h[i]` holds the i-th word of the chaining value of every lane.
+    let mut h: [__m256i; 8] = [_mm256_setzero_si256(); 8];
+    for (i, h) in h.iter_mut().enumerate() {
+        *h = _mm256_set1_epi32(*key.add(i) as i32);
+    }
+    let counter_lo: [u32; 8] = counters.map(|c| c as u32);
+    let counter_hi: [u32; 8] = counters.map(|c| (c >> 32) as u32);
+    let counter_lo: __m256i = _mm256_loadu_si256(counter_lo.as_ptr().cast());
+    let counter_hi: __m256i = _mm256_loadu_si256(counter_hi.as_ptr().cast());
+
+    for block in 0..blocks {
+        let mut block_flags: u32 = flags;
+        if flags & PARENT == 0 && block == 0 {
+            block_flags |= CHUNK_START;
+        }
+        if flags & PARENT == 0 && block == blocks - 1 {
+            block_flags |= CHUNK_END;
+        }
+
+        // Likewise, `m[i]` holds the i-th message word of every lane.
+        let mut m: [__m256i; 16] = [_mm256_setzero_si256(); 16];
+        for half in 0..2 {
+            let mut r: [__m256i; 8] = [_mm256_setzero_si256(); 8];
+            for (r, input) in r.iter_mut().zip(inputs) {
+                *r = _mm256_loadu_si256(input.add(block * BLOCK_LEN).cast::<__m256i>().add(half));
+            }
+            transpose!(&mut r);
+            m[half * 8..half * 8 + 8].copy_from_slice(&r);
+        }
+
+        let mut v: [__m256i; 16] = [
+            h[0],
+            h[1],
+            h[2],
+            h[3],
+            h[4],
+            h[5],
+            h[6],
+            h[7],
+            _mm256_set1_epi32(IV[0] as i32),
+            _mm256_set1_epi32(IV[1] as i32),
+            _mm256_set1_epi32(IV[2] as i32),
+            _mm256_set1_epi32(IV[3] as i32),
+            counter_lo,
+            counter_hi,
+            _mm256_set1_epi32(BLOCK_LEN as i32),
+            _mm256_set1_epi32(block_flags as i32),
+        ];
+
+        for s in MSG_SCHEDULE.iter() {
+            g!(v, 0, 4, 8, 12, m[s[0]], m[s[1]], rot16, rot8);
+            g!(v, 1, 5, 9, 13, m[s[2]], m[s[3]], rot16, rot8);
+            g!(v, 2, 6, 10, 14, m[s[4]], m[s[5]], rot16, rot8);
+            g!(v, 3, 7, 11, 15, m[s[6]], m[s[7]], rot16, rot8);
+            g!(v, 0, 5, 10, 15, m[s[8]], m[s[9]], rot16, rot8);
+            g!(v, 1, 6, 11, 12, m[s[10]], m[s[11]], rot16, rot8);
+            g!(v, 2, 7, 8, 13, m[s[12]], m[s[13]], rot16, rot8);
+            g!(v, 3, 4, 9, 14, m[s[14]], m[s[15]], rot16, rot8);
+        }
+
+        for (i, h) in h.iter_mut().enumerate() {
+            *h = _mm256_xor_si256(v[i], v[i + 8]);
+        }
+    }
+
+    transpose!(&mut h);
+    for (h, out) in h.iter().zip(outs) {
+        _mm256_storeu_si256(out.cast::<__m256i>(), *h);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::blake3;
+
+    #[test]
+    fn blake3_x8_compress()
+    {
+        let mut input = [0u8; 1024 * 8];
+        input
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, b)| *b = (i % 251) as u8);
+        let key: [u32; 8] = core::array::from_fn(|i| IV[i] ^ (i as u32 * 0x01010101));
+
+        for (blocks, flags) in [(16, blake3::KEYED_HASH), (1, PARENT), (3, 0)] {
+            let inputs: [*const u8; 8] =
+                core::array::from_fn(|i| input[i * blocks * 64..].as_ptr());
+            let counters: [u64; 8] = core::array::from_fn(|i| 1 << 32 | (i as u64 * 3));
+            let mut cvs_generic: [[u32; 8]; 8] = [key; 8];
+            let mut cvs_avx2: [[u32; 8]; 8] = [[0; 8]; 8];
+            unsafe {
+                for i in 0..8 {
+                    for block in 0..blocks {
+                        let mut block_flags: u32 = flags;
+                        if flags & PARENT == 0 && block == 0 {
+                            block_flags |= CHUNK_START;
+                        }
+                        if flags & PARENT == 0 && block == blocks - 1 {
+                            block_flags |= CHUNK_END;
+                        }
+                        blake3::blake3(
+                            cvs_generic[i].as_mut_ptr(),
+                            inputs[i].add(block * 64),
+                            64,
+                            counters[i],
+                            block_flags,
+                        );
+                    }
+                }
+                blake3_x8(
+                    inputs,
+                    blocks,
+                    key.as_ptr(),
+                    counters,
+                    flags,
+                    core::array::from_fn(|i| cvs_avx2[i].as_mut_ptr()),
+                );
+            }
+            assert_eq!(cvs_generic, cvs_avx2);
+        }
+    }
+}
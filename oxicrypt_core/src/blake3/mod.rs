@@ -0,0 +1,206 @@
+//! Compression functions used by BLAKE3.
+//!
+//! BLAKE3 splits the input into chunks of 1024 bytes, which are made up of 16
+//! blocks of 64 bytes. The chunks are the leaves of a binary tree and every
+//! parent node compresses the chaining values of its two children as a
+//! single block. Flags passed to the compression function separate the
+//! different kinds of nodes and the modes of the algorithm.
+
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", doc))]
+mod blake3_x4;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", doc))]
+mod blake3_x8;
+
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", doc))]
+pub use blake3_x4::blake3_x4;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", doc))]
+pub use blake3_x8::blake3_x8;
+
+/// Initialization vector of BLAKE3, which is the same as the one used by
+/// SHA-256.
+pub const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Length of a block in bytes.
+pub const BLOCK_LEN: usize = 64;
+/// Length of a chunk in bytes.
+pub const CHUNK_LEN: usize = 1024;
+
+/// Flag of the first block of a chunk.
+pub const CHUNK_START: u32 = 1 << 0;
+/// Flag of the last block of a chunk.
+pub const CHUNK_END: u32 = 1 << 1;
+/// Flag of the parent nodes.
+pub const PARENT: u32 = 1 << 2;
+/// Flag of the root node, which produces the output.
+pub const ROOT: u32 = 1 << 3;
+/// Flag of the keyed hash mode.
+pub const KEYED_HASH: u32 = 1 << 4;
+/// Flag used while hashing the context string in the key derivation mode.
+pub const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+/// Flag used while hashing the key material in the key derivation mode.
+pub const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+/// Order of the message words used by each of the 7 rounds. The message words
+/// are permuted after every round, so this is the permutation applied to
+/// itself.
+#[rustfmt::skip]
+const MSG_SCHEDULE: [[usize; 16]; 7] = [
+    [ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+    [ 2,  6,  3, 10,  7,  0,  4, 13,  1, 11, 12,  5,  9, 14, 15,  8],
+    [ 3,  4, 10, 12, 13,  2,  7, 14,  6,  5,  9,  0, 11, 15,  8,  1],
+    [10,  7, 12,  9, 14,  3, 13, 15,  4,  0, 11,  2,  5,  8,  1,  6],
+    [12, 13,  9, 11, 15, 10, 14,  8,  7,  2,  5,  3,  0,  1,  6,  4],
+    [ 9, 14, 11,  5,  8, 12, 15,  1, 13,  3,  0, 10,  2,  6,  4,  7],
+    [11, 15,  5,  0,  1,  9,  8,  6, 14, 10,  2, 12,  3,  4,  7, 13],
+];
+
+/// Mixing function G, which mixes the words `a`, `b`, `c` and `d` of `v` with
+/// the message words `x` and `y`.
+macro_rules! blake3_g {
+    ($v:ident, $a:expr, $b:expr, $c:expr, $d:expr, $x:expr, $y:expr) => {{
+        $v[$a] = $v[$a].wrapping_add($v[$b]).wrapping_add($x);
+        $v[$d] = ($v[$d] ^ $v[$a]).rotate_right(16);
+        $v[$c] = $v[$c].wrapping_add($v[$d]);
+        $v[$b] = ($v[$b] ^ $v[$c]).rotate_right(12);
+        $v[$a] = $v[$a].wrapping_add($v[$b]).wrapping_add($y);
+        $v[$d] = ($v[$d] ^ $v[$a]).rotate_right(8);
+        $v[$c] = $v[$c].wrapping_add($v[$d]);
+        $v[$b] = ($v[$b] ^ $v[$c]).rotate_right(7);
+    }};
+}
+
+/// Runs the 7 rounds on the state initialized from the chaining value and the
+/// parameters of the block.
+#[inline(always)]
+const unsafe fn rounds(
+    cv: *const u32,
+    block: *const u8,
+    block_len: u32,
+    counter: u64,
+    flags: u32,
+) -> [u32; 16]
+{
+    let mut m: [u32; 16] = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        m[i] = u32::from_le_bytes(*(block as *const [u8; 4]).add(i));
+        i += 1;
+    }
+
+    #[rustfmt::skip]
+    let mut v: [u32; 16] = [
+        *cv.add(0), *cv.add(1), *cv.add(2), *cv.add(3),
+        *cv.add(4), *cv.add(5), *cv.add(6), *cv.add(7),
+        IV[0], IV[1], IV[2], IV[3],
+        counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+
+    let mut round = 0;
+    while round < 7 {
+        let s: &[usize; 16] = &MSG_SCHEDULE[round];
+        blake3_g!(v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        blake3_g!(v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        blake3_g!(v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        blake3_g!(v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        blake3_g!(v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        blake3_g!(v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        blake3_g!(v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        blake3_g!(v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        round += 1;
+    }
+
+    v
+}
+
+/// Compression function used by BLAKE3 that updates the chaining value.
+///
+/// `block_len` is the number of bytes in the block, the rest of which must be
+/// zeros. `counter` is the index of the chunk for the blocks of a chunk, or
+/// zero for parent nodes.
+///
+/// You shouldn't use this function unless you want to implement the
+/// algorithms by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `cv` must point to an array with a length of 8 (32 bytes). `block`
+/// must point to an array with a length of 64 bytes.
+pub const unsafe fn blake3(cv: *mut u32, block: *const u8, block_len: u32, counter: u64, flags: u32)
+{
+    let v: [u32; 16] = rounds(cv, block, block_len, counter, flags);
+    let mut i = 0;
+    while i < 8 {
+        *cv.add(i) = v[i] ^ v[i + 8];
+        i += 1;
+    }
+}
+
+/// Compression function used by BLAKE3 that produces 64 bytes of output.
+///
+/// This is used by the root node, where `counter` is the index of the output
+/// block instead. The first 32 bytes are the same as the chaining value that
+/// [`blake3`] produces.
+///
+/// You shouldn't use this function unless you want to implement the
+/// algorithms by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `cv` must point to an array with a length of 8 (32 bytes). `block`
+/// and `out` must point to arrays with a length of 64 bytes.
+pub const unsafe fn blake3_xof(
+    cv: *const u32,
+    block: *const u8,
+    block_len: u32,
+    counter: u64,
+    flags: u32,
+    out: *mut u8,
+)
+{
+    let v: [u32; 16] = rounds(cv, block, block_len, counter, flags);
+    let out: *mut [u8; 4] = out.cast();
+    let mut i = 0;
+    while i < 8 {
+        *out.add(i) = (v[i] ^ v[i + 8]).to_le_bytes();
+        *out.add(i + 8) = (v[i + 8] ^ *cv.add(i)).to_le_bytes();
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn blake3_abc()
+    {
+        // "abc" is a single chunk, which is also the root node.
+        let mut block: [u8; 64] = [0; 64];
+        block[0..3].copy_from_slice(b"abc");
+        let mut out: [u8; 64] = [0; 64];
+        let flags: u32 = CHUNK_START | CHUNK_END | ROOT;
+
+        unsafe { blake3_xof(IV.as_ptr(), block.as_ptr(), 3, 0, flags, out.as_mut_ptr()) };
+        assert_eq!(
+            out[0..32],
+            [
+                0x64, 0x37, 0xb3, 0xac, 0x38, 0x46, 0x51, 0x33, 0xff, 0xb6, 0x3b, 0x75, 0x27, 0x3a,
+                0x8d, 0xb5, 0x48, 0xc5, 0x58, 0x46, 0x5d, 0x79, 0xdb, 0x03, 0xfd, 0x35, 0x9c, 0x6c,
+                0xd5, 0xbd, 0x9d, 0x85,
+            ]
+        );
+
+        let mut cv: [u32; 8] = IV;
+        unsafe { blake3(cv.as_mut_ptr(), block.as_ptr(), 3, 0, flags) };
+        assert!(
+            cv.iter()
+                .zip(out.chunks_exact(4))
+                .all(|(w, b)| w.to_le_bytes() == b)
+        );
+    }
+}
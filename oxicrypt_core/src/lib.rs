@@ -20,4 +20,16 @@ pub mod aes_lut;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64", doc))]
 #[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
 pub mod aesni;
+pub mod argon2;
+pub mod blake2;
+pub mod blake3;
+pub mod keccak;
 pub mod md_compress;
+pub mod salsa20;
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", doc))]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub mod sha_arm;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", doc))]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub mod shani;
+pub mod sm4;
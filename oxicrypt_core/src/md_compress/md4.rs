@@ -0,0 +1,176 @@
+#[allow(clippy::many_single_char_names)]
+#[inline(always)]
+const fn ff<const S: u32>(a: u32, b: u32, c: u32, d: u32, w: u32) -> u32
+{
+    ((b & c) | (!b & d))
+        .wrapping_add(a)
+        .wrapping_add(w)
+        .rotate_left(S)
+}
+
+#[allow(clippy::many_single_char_names)]
+#[inline(always)]
+const fn gg<const S: u32>(a: u32, b: u32, c: u32, d: u32, w: u32) -> u32
+{
+    ((b & c) | (b & d) | (c & d))
+        .wrapping_add(a)
+        .wrapping_add(w)
+        .wrapping_add(0x5a827999)
+        .rotate_left(S)
+}
+
+#[allow(clippy::many_single_char_names)]
+#[inline(always)]
+const fn hh<const S: u32>(a: u32, b: u32, c: u32, d: u32, w: u32) -> u32
+{
+    (b ^ c ^ d)
+        .wrapping_add(a)
+        .wrapping_add(w)
+        .wrapping_add(0x6ed9eba1)
+        .rotate_left(S)
+}
+
+/// Compression function used by the MD4 algorithm.
+///
+/// MD4 is broken and should only be used to interoperate with legacy
+/// protocols. You shouldn't use this function unless you want to implement the
+/// algorithm by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 4 (16 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn md4(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(64);
+        blocks -= 1;
+    }
+}
+
+#[allow(clippy::many_single_char_names)]
+#[allow(unused_assignments)]
+#[inline(always)]
+const unsafe fn compress(state: *mut u32, block: *const u8)
+{
+    let mut a: u32 = *state.add(0);
+    let mut b: u32 = *state.add(1);
+    let mut c: u32 = *state.add(2);
+    let mut d: u32 = *state.add(3);
+
+    let w00: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(0));
+    let w01: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(1));
+    let w02: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(2));
+    let w03: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(3));
+    let w04: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(4));
+    let w05: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(5));
+    let w06: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(6));
+    let w07: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(7));
+    let w08: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(8));
+    let w09: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(9));
+    let w10: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(10));
+    let w11: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(11));
+    let w12: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(12));
+    let w13: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(13));
+    let w14: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(14));
+    let w15: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(15));
+
+    // 0 .. 4
+    a = ff::<03>(a, b, c, d, w00);
+    d = ff::<07>(d, a, b, c, w01);
+    c = ff::<11>(c, d, a, b, w02);
+    b = ff::<19>(b, c, d, a, w03);
+
+    // 4 .. 8
+    a = ff::<03>(a, b, c, d, w04);
+    d = ff::<07>(d, a, b, c, w05);
+    c = ff::<11>(c, d, a, b, w06);
+    b = ff::<19>(b, c, d, a, w07);
+
+    // 8 .. 12
+    a = ff::<03>(a, b, c, d, w08);
+    d = ff::<07>(d, a, b, c, w09);
+    c = ff::<11>(c, d, a, b, w10);
+    b = ff::<19>(b, c, d, a, w11);
+
+    // 12 .. 16
+    a = ff::<03>(a, b, c, d, w12);
+    d = ff::<07>(d, a, b, c, w13);
+    c = ff::<11>(c, d, a, b, w14);
+    b = ff::<19>(b, c, d, a, w15);
+
+    // 16 .. 20
+    a = gg::<03>(a, b, c, d, w00);
+    d = gg::<05>(d, a, b, c, w04);
+    c = gg::<09>(c, d, a, b, w08);
+    b = gg::<13>(b, c, d, a, w12);
+
+    // 20 .. 24
+    a = gg::<03>(a, b, c, d, w01);
+    d = gg::<05>(d, a, b, c, w05);
+    c = gg::<09>(c, d, a, b, w09);
+    b = gg::<13>(b, c, d, a, w13);
+
+    // 24 .. 28
+    a = gg::<03>(a, b, c, d, w02);
+    d = gg::<05>(d, a, b, c, w06);
+    c = gg::<09>(c, d, a, b, w10);
+    b = gg::<13>(b, c, d, a, w14);
+
+    // 28 .. 32
+    a = gg::<03>(a, b, c, d, w03);
+    d = gg::<05>(d, a, b, c, w07);
+    c = gg::<09>(c, d, a, b, w11);
+    b = gg::<13>(b, c, d, a, w15);
+
+    // 32 .. 36
+    a = hh::<03>(a, b, c, d, w00);
+    d = hh::<09>(d, a, b, c, w08);
+    c = hh::<11>(c, d, a, b, w04);
+    b = hh::<15>(b, c, d, a, w12);
+
+    // 36 .. 40
+    a = hh::<03>(a, b, c, d, w02);
+    d = hh::<09>(d, a, b, c, w10);
+    c = hh::<11>(c, d, a, b, w06);
+    b = hh::<15>(b, c, d, a, w14);
+
+    // 40 .. 44
+    a = hh::<03>(a, b, c, d, w01);
+    d = hh::<09>(d, a, b, c, w09);
+    c = hh::<11>(c, d, a, b, w05);
+    b = hh::<15>(b, c, d, a, w13);
+
+    // 44 .. 48
+    a = hh::<03>(a, b, c, d, w03);
+    d = hh::<09>(d, a, b, c, w11);
+    c = hh::<11>(c, d, a, b, w07);
+    b = hh::<15>(b, c, d, a, w15);
+
+    *state.add(0) = (*state.add(0)).wrapping_add(a);
+    *state.add(1) = (*state.add(1)).wrapping_add(b);
+    *state.add(2) = (*state.add(2)).wrapping_add(c);
+    *state.add(3) = (*state.add(3)).wrapping_add(d);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn md4_abc()
+    {
+        let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+        let mut block: [u8; 64] = [0; 64];
+        block[0..3].copy_from_slice(b"abc");
+        block[3] = 0x80;
+        block[56] = 24;
+
+        unsafe { md4(state.as_mut_ptr(), block.as_ptr(), 1) };
+        assert_eq!(state, [0x7a0148a4, 0x52d821af, 0xe80ac15f, 0x9d72a67a]);
+    }
+}
@@ -59,32 +59,43 @@ const fn ii<const S: u32>(a: u32, b: u32, c: u32, d: u32, w: u32, rc: u32) -> u3
 ///
 /// The caller must guarantee that the passed variables point to valid memory
 /// spaces. `state` must point to an array with a length of 4 (16 bytes).
-/// `block` must point to an array with a length of 64 (64 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn md5(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(64);
+        blocks -= 1;
+    }
+}
+
 #[allow(clippy::many_single_char_names)]
 #[allow(unused_assignments)]
-pub const unsafe fn md5(state: *mut u32, block: *const u8)
+#[inline(always)]
+const unsafe fn compress(state: *mut u32, block: *const u8)
 {
     let mut a: u32 = *state.add(0);
     let mut b: u32 = *state.add(1);
     let mut c: u32 = *state.add(2);
     let mut d: u32 = *state.add(3);
 
-    let w00: u32 = (*block.cast::<u32>().add(0)).to_le();
-    let w01: u32 = (*block.cast::<u32>().add(1)).to_le();
-    let w02: u32 = (*block.cast::<u32>().add(2)).to_le();
-    let w03: u32 = (*block.cast::<u32>().add(3)).to_le();
-    let w04: u32 = (*block.cast::<u32>().add(4)).to_le();
-    let w05: u32 = (*block.cast::<u32>().add(5)).to_le();
-    let w06: u32 = (*block.cast::<u32>().add(6)).to_le();
-    let w07: u32 = (*block.cast::<u32>().add(7)).to_le();
-    let w08: u32 = (*block.cast::<u32>().add(8)).to_le();
-    let w09: u32 = (*block.cast::<u32>().add(9)).to_le();
-    let w10: u32 = (*block.cast::<u32>().add(10)).to_le();
-    let w11: u32 = (*block.cast::<u32>().add(11)).to_le();
-    let w12: u32 = (*block.cast::<u32>().add(12)).to_le();
-    let w13: u32 = (*block.cast::<u32>().add(13)).to_le();
-    let w14: u32 = (*block.cast::<u32>().add(14)).to_le();
-    let w15: u32 = (*block.cast::<u32>().add(15)).to_le();
+    let w00: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(0));
+    let w01: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(1));
+    let w02: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(2));
+    let w03: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(3));
+    let w04: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(4));
+    let w05: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(5));
+    let w06: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(6));
+    let w07: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(7));
+    let w08: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(8));
+    let w09: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(9));
+    let w10: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(10));
+    let w11: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(11));
+    let w12: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(12));
+    let w13: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(13));
+    let w14: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(14));
+    let w15: u32 = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(15));
 
     // 0 .. 4
     a = ff::<07>(a, b, c, d, w00, 0xd76aa478);
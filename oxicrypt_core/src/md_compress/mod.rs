@@ -1,12 +1,28 @@
 //! Compression functions used by hashing algorithms that use the Merkle–Damgård
 //! construction.
 
+mod md4;
 mod md5;
+mod ripemd160;
 mod sha1;
+mod sha1dc;
 mod sha256;
 mod sha512;
+mod sm3;
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", doc))]
+mod sha256_x4;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", doc))]
+mod sha256_x8;
 
+pub use md4::md4;
 pub use md5::md5;
+pub use ripemd160::ripemd160;
 pub use sha1::sha1;
+pub use sha1dc::sha1dc;
 pub use sha256::sha256;
 pub use sha512::sha512;
+pub use sm3::sm3;
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", doc))]
+pub use sha256_x4::sha256_x4;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", doc))]
+pub use sha256_x8::sha256_x8;
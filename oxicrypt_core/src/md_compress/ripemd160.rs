@@ -0,0 +1,164 @@
+/// Message word used by each step of the left line.
+#[rustfmt::skip]
+const R_LEFT: [usize; 80] = [
+     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15,
+     7,  4, 13,  1, 10,  6, 15,  3, 12,  0,  9,  5,  2, 14, 11,  8,
+     3, 10, 14,  4,  9, 15,  8,  1,  2,  7,  0,  6, 13, 11,  5, 12,
+     1,  9, 11, 10,  0,  8, 12,  4, 13,  3,  7, 15, 14,  5,  6,  2,
+     4,  0,  5,  9,  7, 12,  2, 10, 14,  1,  3,  8, 11,  6, 15, 13,
+];
+
+/// Message word used by each step of the right line.
+#[rustfmt::skip]
+const R_RIGHT: [usize; 80] = [
+     5, 14,  7,  0,  9,  2, 11,  4, 13,  6, 15,  8,  1, 10,  3, 12,
+     6, 11,  3,  7,  0, 13,  5, 10, 14, 15,  8, 12,  4,  9,  1,  2,
+    15,  5,  1,  3,  7, 14,  6,  9, 11,  8, 12,  2, 10,  0,  4, 13,
+     8,  6,  4,  1,  3, 11, 15,  0,  5, 12,  2, 13,  9,  7, 10, 14,
+    12, 15, 10,  4,  1,  5,  8,  7,  6,  2, 13, 14,  0,  3,  9, 11,
+];
+
+/// Left rotation used by each step of the left line.
+#[rustfmt::skip]
+const S_LEFT: [u32; 80] = [
+    11, 14, 15, 12,  5,  8,  7,  9, 11, 13, 14, 15,  6,  7,  9,  8,
+     7,  6,  8, 13, 11,  9,  7, 15,  7, 12, 15,  9, 11,  7, 13, 12,
+    11, 13,  6,  7, 14,  9, 13, 15, 14,  8, 13,  6,  5, 12,  7,  5,
+    11, 12, 14, 15, 14, 15,  9,  8,  9, 14,  5,  6,  8,  6,  5, 12,
+     9, 15,  5, 11,  6,  8, 13, 12,  5, 12, 13, 14, 11,  8,  5,  6,
+];
+
+/// Left rotation used by each step of the right line.
+#[rustfmt::skip]
+const S_RIGHT: [u32; 80] = [
+     8,  9,  9, 11, 13, 15, 15,  5,  7,  7,  8, 11, 14, 14, 12,  6,
+     9, 13, 15,  7, 12,  8,  9, 11,  7,  7, 12,  7,  6, 15, 13, 11,
+     9,  7, 15, 11,  8,  6,  6, 14, 12, 13,  5, 14, 13, 13,  7,  5,
+    15,  5,  8, 11, 14, 14,  6, 14,  6,  9, 12,  9, 12,  5, 15,  8,
+     8,  5, 12,  9, 12,  5, 14,  6,  8, 13,  6,  5, 15, 13, 11, 11,
+];
+
+/// Round constants of the left line.
+const K_LEFT: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+
+/// Round constants of the right line.
+const K_RIGHT: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// Boolean function of the given round. The right line uses the rounds in
+/// reverse order.
+#[inline(always)]
+const fn f(round: usize, x: u32, y: u32, z: u32) -> u32
+{
+    match round {
+        | 0 => x ^ y ^ z,
+        | 1 => (x & y) | (!x & z),
+        | 2 => (x | !y) ^ z,
+        | 3 => (x & z) | (y & !z),
+        | _ => x ^ (y | !z),
+    }
+}
+
+/// Compression function used by the RIPEMD-160 algorithm.
+///
+/// You shouldn't use this function unless you want to implement the algorithm
+/// by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 5 (20 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn ripemd160(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(64);
+        blocks -= 1;
+    }
+}
+
+#[allow(clippy::many_single_char_names)]
+#[inline(always)]
+const unsafe fn compress(state: *mut u32, block: *const u8)
+{
+    let mut w: [u32; 16] = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        w[i] = u32::from_le_bytes(*block.cast::<[u8; 4]>().add(i));
+        i += 1;
+    }
+
+    // The block is compressed by two independent lines, which are combined at
+    // the end.
+    let mut al: u32 = *state.add(0);
+    let mut bl: u32 = *state.add(1);
+    let mut cl: u32 = *state.add(2);
+    let mut dl: u32 = *state.add(3);
+    let mut el: u32 = *state.add(4);
+    let mut ar: u32 = al;
+    let mut br: u32 = bl;
+    let mut cr: u32 = cl;
+    let mut dr: u32 = dl;
+    let mut er: u32 = el;
+
+    let mut j = 0;
+    while j < 80 {
+        let round = j / 16;
+
+        let t: u32 = al
+            .wrapping_add(f(round, bl, cl, dl))
+            .wrapping_add(w[R_LEFT[j]])
+            .wrapping_add(K_LEFT[round])
+            .rotate_left(S_LEFT[j])
+            .wrapping_add(el);
+        al = el;
+        el = dl;
+        dl = cl.rotate_left(10);
+        cl = bl;
+        bl = t;
+
+        let t: u32 = ar
+            .wrapping_add(f(4 - round, br, cr, dr))
+            .wrapping_add(w[R_RIGHT[j]])
+            .wrapping_add(K_RIGHT[round])
+            .rotate_left(S_RIGHT[j])
+            .wrapping_add(er);
+        ar = er;
+        er = dr;
+        dr = cr.rotate_left(10);
+        cr = br;
+        br = t;
+
+        j += 1;
+    }
+
+    let t: u32 = (*state.add(1)).wrapping_add(cl).wrapping_add(dr);
+    *state.add(1) = (*state.add(2)).wrapping_add(dl).wrapping_add(er);
+    *state.add(2) = (*state.add(3)).wrapping_add(el).wrapping_add(ar);
+    *state.add(3) = (*state.add(4)).wrapping_add(al).wrapping_add(br);
+    *state.add(4) = (*state.add(0)).wrapping_add(bl).wrapping_add(cr);
+    *state.add(0) = t;
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn ripemd160_abc()
+    {
+        let mut state: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut block: [u8; 64] = [0; 64];
+        block[0..3].copy_from_slice(b"abc");
+        block[3] = 0x80;
+        block[56] = 24;
+
+        unsafe { ripemd160(state.as_mut_ptr(), block.as_ptr(), 1) };
+        assert_eq!(
+            state,
+            [0xf708b28e, 0x7a985de0, 0x8e4a049b, 0x87b0c698, 0xfc0b5af1]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 macro_rules! r0 {
     ($a:ident, $b:ident, $c:ident, $d:ident, $e:ident, $block:ident, $w:ident, $i:expr) => {{
-        $w[$i] = (*($block as *const u32).add($i)).to_be();
+        $w[$i] = u32::from_be_bytes(*($block as *const [u8; 4]).add($i));
         $e = $e
             .wrapping_add(0x5a827999)
             .wrapping_add($a.rotate_left(5))
@@ -71,10 +71,21 @@ macro_rules! r4 {
 ///
 /// The caller must guarantee that the passed variables point to valid memory
 /// spaces. `state` must point to an array with a length of 5 (20 bytes).
-/// `block` must point to an array with a length of 64 (64 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn sha1(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(64);
+        blocks -= 1;
+    }
+}
+
 #[allow(clippy::many_single_char_names)]
 #[allow(unused_assignments)]
-pub const unsafe fn sha1(state: *mut u32, block: *const u8)
+#[inline(always)]
+const unsafe fn compress(state: *mut u32, block: *const u8)
 {
     let mut a: u32 = *state.add(0);
     let mut b: u32 = *state.add(1);
@@ -0,0 +1,362 @@
+//! SHA-1 compression with collision detection.
+//!
+//! Every known practical collision attack against SHA-1 follows one of a small
+//! set of disturbance vectors, which describe where the message differences of
+//! the colliding blocks are. For each of them, the block is recompressed with
+//! the message difference applied, starting from a step where the difference
+//! in the working state is expected to be zero. If the other block of such a
+//! pair leads to the same output, the block is part of a collision attack.
+//!
+//! See "Counter-cryptanalysis" by Marc Stevens (CRYPTO 2013).
+
+/// Unavoidable disturbance vectors as `(type, K, b, step)`, where `type`, `K`
+/// and `b` describe the vector I(K, b) or II(K, b), and `step` is the step the
+/// recompression starts from.
+#[rustfmt::skip]
+const DV_PARAMS: [(u8, usize, u32, usize); 32] = [
+    (1, 43, 0, 58), (1, 44, 0, 58), (1, 45, 0, 58), (1, 46, 0, 58),
+    (1, 46, 2, 58), (1, 47, 0, 58), (1, 47, 2, 58), (1, 48, 0, 58),
+    (1, 48, 2, 58), (1, 49, 0, 58), (1, 49, 2, 58), (1, 50, 0, 65),
+    (1, 50, 2, 65), (1, 51, 0, 65), (1, 51, 2, 65), (1, 52, 0, 65),
+    (2, 45, 0, 58), (2, 46, 0, 58), (2, 46, 2, 58), (2, 47, 0, 58),
+    (2, 48, 0, 58), (2, 49, 0, 58), (2, 49, 2, 58), (2, 50, 0, 65),
+    (2, 50, 2, 65), (2, 51, 0, 65), (2, 51, 2, 65), (2, 52, 0, 65),
+    (2, 53, 0, 65), (2, 54, 0, 65), (2, 55, 0, 65), (2, 56, 0, 65),
+];
+
+#[derive(Clone, Copy)]
+struct DisturbanceVector
+{
+    /// Step the recompression starts from.
+    step: usize,
+    /// Difference of the expanded message words.
+    dm:   [u32; 80],
+}
+
+const DVS: [DisturbanceVector; 32] = {
+    let mut dvs = [DisturbanceVector {
+        step: 0,
+        dm:   [0; 80],
+    }; 32];
+    let mut i = 0;
+    while i < 32 {
+        let (kind, k, b, step) = DV_PARAMS[i];
+        dvs[i] = DisturbanceVector {
+            step,
+            dm: message_difference(kind, k, b),
+        };
+        i += 1;
+    }
+    dvs
+};
+
+/// Expands the disturbance vector I(K, b) or II(K, b) and returns the message
+/// difference of the local collisions it describes.
+const fn message_difference(kind: u8, k: usize, b: u32) -> [u32; 80]
+{
+    // The vector is zero in the 16 steps starting at `K`, except for the bits
+    // set below. Since it follows the message expansion, the rest of it can be
+    // computed in both directions. `v[i + 5]` holds step `i`, which leaves room
+    // for the local collisions that started before step 0.
+    let mut v: [u32; 85] = [0; 85];
+    v[k + 15 + 5] = 1 << b;
+    if kind == 2 {
+        v[k + 1 + 5] = (1_u32 << b).rotate_left(31);
+        v[k + 3 + 5] = (1_u32 << b).rotate_left(31);
+    }
+
+    let mut j = k + 16 + 5;
+    while j < 85 {
+        v[j] = (v[j - 3] ^ v[j - 8] ^ v[j - 14] ^ v[j - 16]).rotate_left(1);
+        j += 1;
+    }
+    let mut j = k + 15 + 5;
+    while j >= 16 {
+        v[j - 16] = v[j].rotate_right(1) ^ v[j - 3] ^ v[j - 8] ^ v[j - 14];
+        j -= 1;
+    }
+
+    // Each disturbance is corrected in the next five steps.
+    let mut dm: [u32; 80] = [0; 80];
+    let mut i = 0;
+    while i < 80 {
+        dm[i] = v[i + 5]
+            ^ v[i + 4].rotate_left(5)
+            ^ v[i + 3]
+            ^ (v[i + 2] ^ v[i + 1] ^ v[i]).rotate_left(30);
+        i += 1;
+    }
+    dm
+}
+
+#[inline(always)]
+const fn f(i: usize, b: u32, c: u32, d: u32) -> u32
+{
+    match i / 20 {
+        | 0 => (b & c) | (!b & d),
+        | 2 => (b & c) | (b & d) | (c & d),
+        | _ => b ^ c ^ d,
+    }
+}
+
+#[inline(always)]
+const fn k(i: usize) -> u32
+{
+    match i / 20 {
+        | 0 => 0x5a827999,
+        | 1 => 0x6ed9eba1,
+        | 2 => 0x8f1bbcdc,
+        | _ => 0xca62c1d6,
+    }
+}
+
+/// Runs step `i` on the working state.
+#[inline(always)]
+const fn step(s: [u32; 5], i: usize, w: u32) -> [u32; 5]
+{
+    let [a, b, c, d, e] = s;
+    let t: u32 = a
+        .rotate_left(5)
+        .wrapping_add(f(i, b, c, d))
+        .wrapping_add(e)
+        .wrapping_add(k(i))
+        .wrapping_add(w);
+    [t, a, b.rotate_left(30), c, d]
+}
+
+/// Undoes step `i` on the working state.
+#[inline(always)]
+const fn step_back(s: [u32; 5], i: usize, w: u32) -> [u32; 5]
+{
+    let [t, a, b, c, d] = s;
+    let b: u32 = b.rotate_right(30);
+    let e: u32 = t
+        .wrapping_sub(a.rotate_left(5))
+        .wrapping_sub(f(i, b, c, d))
+        .wrapping_sub(k(i))
+        .wrapping_sub(w);
+    [a, b, c, d, e]
+}
+
+#[inline(always)]
+const fn add(x: [u32; 5], y: [u32; 5]) -> [u32; 5]
+{
+    [
+        x[0].wrapping_add(y[0]),
+        x[1].wrapping_add(y[1]),
+        x[2].wrapping_add(y[2]),
+        x[3].wrapping_add(y[3]),
+        x[4].wrapping_add(y[4]),
+    ]
+}
+
+/// Compression function used by the SHA-1 algorithm, which also checks every
+/// block for signs of a collision attack.
+///
+/// Returns `true` when at least one of the blocks is part of a collision
+/// attack. If `safe_hash` is `true`, such a block is compressed two more times,
+/// so that the colliding messages no longer share the same digest. This is
+/// what Git does.
+///
+/// The check recompresses each block once for every disturbance vector, which
+/// makes it about 30 times slower than [`sha1`](`super::sha1`).
+///
+/// You shouldn't use this function unless you want to implement the algorithm
+/// by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 5 (20 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn sha1dc(
+    state: *mut u32,
+    mut block: *const u8,
+    mut blocks: usize,
+    safe_hash: bool,
+) -> bool
+{
+    let mut detected = false;
+    while blocks != 0 {
+        if compress(state.cast(), block, safe_hash) {
+            detected = true;
+        }
+        block = block.add(64);
+        blocks -= 1;
+    }
+    detected
+}
+
+#[inline(always)]
+const unsafe fn compress(state: *mut [u32; 5], block: *const u8, safe_hash: bool) -> bool
+{
+    let mut w: [u32; 80] = [0; 80];
+    let mut i = 0;
+    while i < 16 {
+        w[i] = u32::from_be_bytes(*block.cast::<[u8; 4]>().add(i));
+        i += 1;
+    }
+    while i < 80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        i += 1;
+    }
+
+    // Keep the working states the recompression starts from.
+    let ihv_in: [u32; 5] = *state;
+    let mut s58: [u32; 5] = [0; 5];
+    let mut s65: [u32; 5] = [0; 5];
+    let mut s: [u32; 5] = ihv_in;
+    let mut i = 0;
+    while i < 80 {
+        if i == 58 {
+            s58 = s;
+        } else if i == 65 {
+            s65 = s;
+        }
+        s = step(s, i, w[i]);
+        i += 1;
+    }
+    let ihv_out: [u32; 5] = add(ihv_in, s);
+    *state = ihv_out;
+
+    let mut d = 0;
+    while d < DVS.len() {
+        let dv: &DisturbanceVector = &DVS[d];
+        let mut w2: [u32; 80] = w;
+        let mut i = 0;
+        while i < 80 {
+            w2[i] ^= dv.dm[i];
+            i += 1;
+        }
+
+        // The other block has the same working state at `dv.step`, so going
+        // back from there gives its input and going forward gives its output.
+        let mid: [u32; 5] = if dv.step == 58 { s58 } else { s65 };
+        let mut s: [u32; 5] = mid;
+        let mut i = dv.step;
+        while i > 0 {
+            i -= 1;
+            s = step_back(s, i, w2[i]);
+        }
+        let ihv2_in: [u32; 5] = s;
+
+        let mut s: [u32; 5] = mid;
+        let mut i = dv.step;
+        while i < 80 {
+            s = step(s, i, w2[i]);
+            i += 1;
+        }
+        let ihv2_out: [u32; 5] = add(ihv2_in, s);
+
+        let mut diff: u32 = 0;
+        let mut i = 0;
+        while i < 5 {
+            diff |= ihv2_out[i] ^ ihv_out[i];
+            i += 1;
+        }
+        if diff == 0 {
+            if safe_hash {
+                let mut n = 0;
+                while n < 2 {
+                    let mut s: [u32; 5] = *state;
+                    let mut i = 0;
+                    while i < 80 {
+                        s = step(s, i, w[i]);
+                        i += 1;
+                    }
+                    *state = add(*state, s);
+                    n += 1;
+                }
+            }
+            return true;
+        }
+        d += 1;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn sha1dc_abc()
+    {
+        let mut state: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut block: [u8; 64] = [0; 64];
+        block[0..3].copy_from_slice(b"abc");
+        block[3] = 0x80;
+        block[63] = 24;
+
+        assert!(!unsafe { sha1dc(state.as_mut_ptr(), block.as_ptr(), 1, true) });
+        assert_eq!(
+            state,
+            [0xa9993e36, 0x4706816a, 0xba3e2571, 0x7850c26c, 0x9cd0d89d]
+        );
+    }
+
+    /// Collision blocks of `shattered-1.pdf` from the SHAttered attack.
+    #[rustfmt::skip]
+    const SHATTERED_1: [u8; 128] = [
+        0x73, 0x46, 0xdc, 0x91, 0x66, 0xb6, 0x7e, 0x11, 0x8f, 0x02, 0x9a, 0xb6, 0x21, 0xb2, 0x56, 0x0f,
+        0xf9, 0xca, 0x67, 0xcc, 0xa8, 0xc7, 0xf8, 0x5b, 0xa8, 0x4c, 0x79, 0x03, 0x0c, 0x2b, 0x3d, 0xe2,
+        0x18, 0xf8, 0x6d, 0xb3, 0xa9, 0x09, 0x01, 0xd5, 0xdf, 0x45, 0xc1, 0x4f, 0x26, 0xfe, 0xdf, 0xb3,
+        0xdc, 0x38, 0xe9, 0x6a, 0xc2, 0x2f, 0xe7, 0xbd, 0x72, 0x8f, 0x0e, 0x45, 0xbc, 0xe0, 0x46, 0xd2,
+        0x3c, 0x57, 0x0f, 0xeb, 0x14, 0x13, 0x98, 0xbb, 0x55, 0x2e, 0xf5, 0xa0, 0xa8, 0x2b, 0xe3, 0x31,
+        0xfe, 0xa4, 0x80, 0x37, 0xb8, 0xb5, 0xd7, 0x1f, 0x0e, 0x33, 0x2e, 0xdf, 0x93, 0xac, 0x35, 0x00,
+        0xeb, 0x4d, 0xdc, 0x0d, 0xec, 0xc1, 0xa8, 0x64, 0x79, 0x0c, 0x78, 0x2c, 0x76, 0x21, 0x56, 0x60,
+        0xdd, 0x30, 0x97, 0x91, 0xd0, 0x6b, 0xd0, 0xaf, 0x3f, 0x98, 0xcd, 0xa4, 0xbc, 0x46, 0x29, 0xb1,
+    ];
+
+    /// Collision blocks of `shattered-2.pdf` from the SHAttered attack.
+    #[rustfmt::skip]
+    const SHATTERED_2: [u8; 128] = [
+        0x7f, 0x46, 0xdc, 0x93, 0xa6, 0xb6, 0x7e, 0x01, 0x3b, 0x02, 0x9a, 0xaa, 0x1d, 0xb2, 0x56, 0x0b,
+        0x45, 0xca, 0x67, 0xd6, 0x88, 0xc7, 0xf8, 0x4b, 0x8c, 0x4c, 0x79, 0x1f, 0xe0, 0x2b, 0x3d, 0xf6,
+        0x14, 0xf8, 0x6d, 0xb1, 0x69, 0x09, 0x01, 0xc5, 0x6b, 0x45, 0xc1, 0x53, 0x0a, 0xfe, 0xdf, 0xb7,
+        0x60, 0x38, 0xe9, 0x72, 0x72, 0x2f, 0xe7, 0xad, 0x72, 0x8f, 0x0e, 0x49, 0x04, 0xe0, 0x46, 0xc2,
+        0x30, 0x57, 0x0f, 0xe9, 0xd4, 0x13, 0x98, 0xab, 0xe1, 0x2e, 0xf5, 0xbc, 0x94, 0x2b, 0xe3, 0x35,
+        0x42, 0xa4, 0x80, 0x2d, 0x98, 0xb5, 0xd7, 0x0f, 0x2a, 0x33, 0x2e, 0xc3, 0x7f, 0xac, 0x35, 0x14,
+        0xe7, 0x4d, 0xdc, 0x0f, 0x2c, 0xc1, 0xa8, 0x74, 0xcd, 0x0c, 0x78, 0x30, 0x5a, 0x21, 0x56, 0x64,
+        0x61, 0x30, 0x97, 0x89, 0x60, 0x6b, 0xd0, 0xbf, 0x3f, 0x98, 0xcd, 0xa8, 0x04, 0x46, 0x29, 0xa1,
+    ];
+
+    /// State after the first three blocks, which are shared by both files.
+    const SHATTERED_H: [u32; 5] = [0x4ea96269, 0x7c876e26, 0x74d107f0, 0xfec67984, 0x14f5bf45];
+
+    /// The collision is only detected in the second block. With the safe hash,
+    /// that block is compressed three times in total, so the expected states
+    /// are plain SHA-1 compressions of the first block once and the second
+    /// block three times.
+    #[test]
+    fn sha1dc_shattered()
+    {
+        let mut state1: [u32; 5] = SHATTERED_H;
+        let mut state2: [u32; 5] = SHATTERED_H;
+        assert!(unsafe { sha1dc(state1.as_mut_ptr(), SHATTERED_1.as_ptr(), 2, false) });
+        assert!(unsafe { sha1dc(state2.as_mut_ptr(), SHATTERED_2.as_ptr(), 2, false) });
+        assert_eq!(
+            state1,
+            [0x1eacb25e, 0xd5970d10, 0xf1736963, 0x5771bc3a, 0x17b48ac5]
+        );
+        assert_eq!(state2, state1);
+
+        let mut state: [u32; 5] = SHATTERED_H;
+        assert!(!unsafe { sha1dc(state.as_mut_ptr(), SHATTERED_1.as_ptr(), 1, true) });
+
+        let mut state1: [u32; 5] = SHATTERED_H;
+        let mut state2: [u32; 5] = SHATTERED_H;
+        assert!(unsafe { sha1dc(state1.as_mut_ptr(), SHATTERED_1.as_ptr(), 2, true) });
+        assert!(unsafe { sha1dc(state2.as_mut_ptr(), SHATTERED_2.as_ptr(), 2, true) });
+        assert_eq!(
+            state1,
+            [0x76456a72, 0xf9669737, 0x0d01257e, 0x99b289c2, 0xf3a27754]
+        );
+        assert_eq!(
+            state2,
+            [0xe1d20a40, 0x748fdcce, 0xa8ac3b1a, 0xd3152963, 0x3a9dfb34]
+        );
+    }
+}
@@ -30,10 +30,21 @@ macro_rules! sha2_32_f {
 ///
 /// The caller must guarantee that the passed variables point to valid memory
 /// spaces. `state` must point to an array with a length of 8 (32 bytes).
-/// `block` must point to an array with a length of 64 (64 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn sha256(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(64);
+        blocks -= 1;
+    }
+}
+
 #[allow(clippy::many_single_char_names)]
 #[allow(unused_assignments)]
-pub const unsafe fn sha256(state: *mut u32, block: *const u8)
+#[inline(always)]
+const unsafe fn compress(state: *mut u32, block: *const u8)
 {
     let mut a: u32 = *state.add(0);
     let mut b: u32 = *state.add(1);
@@ -44,22 +55,22 @@ pub const unsafe fn sha256(state: *mut u32, block: *const u8)
     let mut g: u32 = *state.add(6);
     let mut h: u32 = *state.add(7);
 
-    let mut w00: u32 = (*(block as *const u32).add(0)).to_be();
-    let mut w01: u32 = (*(block as *const u32).add(1)).to_be();
-    let mut w02: u32 = (*(block as *const u32).add(2)).to_be();
-    let mut w03: u32 = (*(block as *const u32).add(3)).to_be();
-    let mut w04: u32 = (*(block as *const u32).add(4)).to_be();
-    let mut w05: u32 = (*(block as *const u32).add(5)).to_be();
-    let mut w06: u32 = (*(block as *const u32).add(6)).to_be();
-    let mut w07: u32 = (*(block as *const u32).add(7)).to_be();
-    let mut w08: u32 = (*(block as *const u32).add(8)).to_be();
-    let mut w09: u32 = (*(block as *const u32).add(9)).to_be();
-    let mut w10: u32 = (*(block as *const u32).add(10)).to_be();
-    let mut w11: u32 = (*(block as *const u32).add(11)).to_be();
-    let mut w12: u32 = (*(block as *const u32).add(12)).to_be();
-    let mut w13: u32 = (*(block as *const u32).add(13)).to_be();
-    let mut w14: u32 = (*(block as *const u32).add(14)).to_be();
-    let mut w15: u32 = (*(block as *const u32).add(15)).to_be();
+    let mut w00: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(0));
+    let mut w01: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(1));
+    let mut w02: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(2));
+    let mut w03: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(3));
+    let mut w04: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(4));
+    let mut w05: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(5));
+    let mut w06: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(6));
+    let mut w07: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(7));
+    let mut w08: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(8));
+    let mut w09: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(9));
+    let mut w10: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(10));
+    let mut w11: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(11));
+    let mut w12: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(12));
+    let mut w13: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(13));
+    let mut w14: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(14));
+    let mut w15: u32 = u32::from_be_bytes(*(block as *const [u8; 4]).add(15));
 
     sha2_32_f!(a, b, c, d, e, f, g, h, w00, w14, w09, w01, 0x428a2f98);
     sha2_32_f!(h, a, b, c, d, e, f, g, w01, w15, w10, w02, 0x71374491);
@@ -0,0 +1,175 @@
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+#[cfg(target_arch = "arm")]
+use core::arch::arm::*;
+
+/// Round constants used by SHA-224 and SHA-256.
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        vorrq_u32(vshrq_n_u32($x, $n), vshlq_n_u32($x, 32 - $n))
+    };
+}
+
+/// Transposes a 4x4 matrix of 32-bit words, where each vector is a row.
+///
+/// This is a macro rather than a function, since the NEON types can't appear in
+/// signatures when the documentation is built for other architectures.
+macro_rules! transpose {
+    ($r:expr) => {{
+        let r: &mut [uint32x4_t; 4] = $r;
+        let t01: uint32x4x2_t = vtrnq_u32(r[0], r[1]);
+        let t23: uint32x4x2_t = vtrnq_u32(r[2], r[3]);
+
+        r[0] = vcombine_u32(vget_low_u32(t01.0), vget_low_u32(t23.0));
+        r[1] = vcombine_u32(vget_low_u32(t01.1), vget_low_u32(t23.1));
+        r[2] = vcombine_u32(vget_high_u32(t01.0), vget_high_u32(t23.0));
+        r[3] = vcombine_u32(vget_high_u32(t01.1), vget_high_u32(t23.1));
+    }};
+}
+
+/// Compression function used by the SHA-2 family of functions, namely SHA-224
+/// and SHA-256, that compresses four independent states in parallel using
+/// NEON.
+///
+/// Each of the four lanes produces the same result as calling
+/// [`sha256`](`crate::md_compress::sha256`) with `states[i]`, `blocks[i]` and
+/// `n`.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. Every pointer in `states` must point to an array with a length of 8
+/// (32 bytes) and the arrays must not overlap. Every pointer in `blocks` must
+/// point to `n` consecutive blocks, each with a length of 64 bytes. The CPU
+/// must support the `neon` feature.
+#[target_feature(enable = "neon")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+#[allow(clippy::many_single_char_names)]
+pub unsafe fn sha256_x4(states: [*mut u32; 4], mut blocks: [*const u8; 4], mut n: usize)
+{
+    let mut abcd: [uint32x4_t; 4] = [vdupq_n_u32(0); 4];
+    let mut efgh: [uint32x4_t; 4] = [vdupq_n_u32(0); 4];
+    for ((abcd, efgh), state) in abcd.iter_mut().zip(efgh.iter_mut()).zip(states) {
+        *abcd = vld1q_u32(state.add(0));
+        *efgh = vld1q_u32(state.add(4));
+    }
+    transpose!(&mut abcd);
+    transpose!(&mut efgh);
+
+    // After the transpose `s[i]` holds the i-th word of the state of every lane.
+    let mut s: [uint32x4_t; 8] = [
+        abcd[0], abcd[1], abcd[2], abcd[3], efgh[0], efgh[1], efgh[2], efgh[3],
+    ];
+
+    while n != 0 {
+        let s_save: [uint32x4_t; 8] = s;
+
+        // Likewise, `w[i]` holds the i-th message word of every lane.
+        let mut w: [uint32x4_t; 16] = [vdupq_n_u32(0); 16];
+        for quarter in 0..4 {
+            let mut r: [uint32x4_t; 4] = [vdupq_n_u32(0); 4];
+            for (r, block) in r.iter_mut().zip(blocks) {
+                *r = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(quarter * 16))));
+            }
+            transpose!(&mut r);
+            w[quarter * 4..quarter * 4 + 4].copy_from_slice(&r);
+        }
+
+        // Compiler is able to unroll this loop.
+        for i in 0..64 {
+            // Message schedule for rounds 16-63.
+            if i >= 16 {
+                let w2: uint32x4_t = w[(i - 2) % 16];
+                let w15: uint32x4_t = w[(i - 15) % 16];
+                let sigma1: uint32x4_t =
+                    veorq_u32(veorq_u32(rotr!(w2, 17), rotr!(w2, 19)), vshrq_n_u32(w2, 10));
+                let sigma0: uint32x4_t = veorq_u32(
+                    veorq_u32(rotr!(w15, 7), rotr!(w15, 18)),
+                    vshrq_n_u32(w15, 3),
+                );
+                w[i % 16] = vaddq_u32(
+                    vaddq_u32(w[i % 16], sigma0),
+                    vaddq_u32(w[(i - 7) % 16], sigma1),
+                );
+            }
+
+            let [a, b, c, d, e, f, g, h] = s;
+
+            let e_rho: uint32x4_t = veorq_u32(veorq_u32(rotr!(e, 6), rotr!(e, 11)), rotr!(e, 25));
+            let ch: uint32x4_t = veorq_u32(vandq_u32(e, f), vbicq_u32(g, e));
+            let t1: uint32x4_t = vaddq_u32(
+                vaddq_u32(vaddq_u32(h, e_rho), vaddq_u32(ch, w[i % 16])),
+                vdupq_n_u32(K[i]),
+            );
+
+            let a_rho: uint32x4_t = veorq_u32(veorq_u32(rotr!(a, 2), rotr!(a, 13)), rotr!(a, 22));
+            let maj: uint32x4_t = vorrq_u32(vandq_u32(a, b), vandq_u32(vorrq_u32(a, b), c));
+            let t2: uint32x4_t = vaddq_u32(a_rho, maj);
+
+            s = [vaddq_u32(t1, t2), a, b, c, vaddq_u32(d, t1), e, f, g];
+        }
+
+        for (s, s_save) in s.iter_mut().zip(s_save) {
+            *s = vaddq_u32(*s, s_save);
+        }
+        for block in blocks.iter_mut() {
+            *block = block.add(64);
+        }
+        n -= 1;
+    }
+
+    abcd = [s[0], s[1], s[2], s[3]];
+    efgh = [s[4], s[5], s[6], s[7]];
+    transpose!(&mut abcd);
+    transpose!(&mut efgh);
+    for ((abcd, efgh), state) in abcd.into_iter().zip(efgh).zip(states) {
+        vst1q_u32(state.add(0), abcd);
+        vst1q_u32(state.add(4), efgh);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha256_x4_compress()
+    {
+        let mut states_generic: [[u32; 8]; 4] = [[
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ]; 4];
+        let mut states_neon = states_generic;
+        let mut blocks = [[0u8; 64 * 16]; 4];
+        blocks.iter_mut().enumerate().for_each(|(i, lane)| {
+            lane.iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 131 + j * 7 + j / 64 * 31) as u8)
+        });
+        unsafe {
+            for i in 0..4 {
+                md_compress::sha256(states_generic[i].as_mut_ptr(), blocks[i].as_ptr(), 16);
+            }
+            sha256_x4(
+                core::array::from_fn(|i| states_neon[i].as_mut_ptr()),
+                core::array::from_fn(|i| blocks[i].as_ptr()),
+                16,
+            );
+        }
+        assert_eq!(states_generic, states_neon);
+    }
+}
@@ -0,0 +1,206 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Round constants used by SHA-224 and SHA-256.
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        _mm256_or_si256(_mm256_srli_epi32($x, $n), _mm256_slli_epi32($x, 32 - $n))
+    };
+}
+
+/// Transposes an 8x8 matrix of 32-bit words, where each vector is a row.
+#[inline(always)]
+unsafe fn transpose(r: &mut [__m256i; 8])
+{
+    let t0: __m256i = _mm256_unpacklo_epi32(r[0], r[1]);
+    let t1: __m256i = _mm256_unpackhi_epi32(r[0], r[1]);
+    let t2: __m256i = _mm256_unpacklo_epi32(r[2], r[3]);
+    let t3: __m256i = _mm256_unpackhi_epi32(r[2], r[3]);
+    let t4: __m256i = _mm256_unpacklo_epi32(r[4], r[5]);
+    let t5: __m256i = _mm256_unpackhi_epi32(r[4], r[5]);
+    let t6: __m256i = _mm256_unpacklo_epi32(r[6], r[7]);
+    let t7: __m256i = _mm256_unpackhi_epi32(r[6], r[7]);
+
+    let u0: __m256i = _mm256_unpacklo_epi64(t0, t2);
+    let u1: __m256i = _mm256_unpackhi_epi64(t0, t2);
+    let u2: __m256i = _mm256_unpacklo_epi64(t1, t3);
+    let u3: __m256i = _mm256_unpackhi_epi64(t1, t3);
+    let u4: __m256i = _mm256_unpacklo_epi64(t4, t6);
+    let u5: __m256i = _mm256_unpackhi_epi64(t4, t6);
+    let u6: __m256i = _mm256_unpacklo_epi64(t5, t7);
+    let u7: __m256i = _mm256_unpackhi_epi64(t5, t7);
+
+    r[0] = _mm256_permute2x128_si256(u0, u4, 0x20);
+    r[1] = _mm256_permute2x128_si256(u1, u5, 0x20);
+    r[2] = _mm256_permute2x128_si256(u2, u6, 0x20);
+    r[3] = _mm256_permute2x128_si256(u3, u7, 0x20);
+    r[4] = _mm256_permute2x128_si256(u0, u4, 0x31);
+    r[5] = _mm256_permute2x128_si256(u1, u5, 0x31);
+    r[6] = _mm256_permute2x128_si256(u2, u6, 0x31);
+    r[7] = _mm256_permute2x128_si256(u3, u7, 0x31);
+}
+
+/// Compression function used by the SHA-2 family of functions, namely SHA-224
+/// and SHA-256, that compresses eight independent states in parallel using
+/// AVX2.
+///
+/// Each of the eight lanes produces the same result as calling
+/// [`sha256`](`crate::md_compress::sha256`) with `states[i]`, `blocks[i]` and
+/// `n`.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. Every pointer in `states` must point to an array with a length of 8
+/// (32 bytes) and the arrays must not overlap. Every pointer in `blocks` must
+/// point to `n` consecutive blocks, each with a length of 64 bytes. The CPU
+/// must support the `avx2` feature.
+#[target_feature(enable = "avx2")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+#[allow(clippy::many_single_char_names)]
+pub unsafe fn sha256_x8(states: [*mut u32; 8], mut blocks: [*const u8; 8], mut n: usize)
+{
+    // Swaps the bytes of each 32-bit word.
+    let mask: __m256i = _mm256_set_epi64x(
+        0x0c0d0e0f08090a0b,
+        0x0405060700010203,
+        0x0c0d0e0f08090a0b,
+        0x0405060700010203,
+    );
+
+    // After the transpose `s[i]` holds the i-th word of the state of every lane.
+    let mut s: [__m256i; 8] = [_mm256_setzero_si256(); 8];
+    for (s, state) in s.iter_mut().zip(states) {
+        *s = _mm256_loadu_si256(state.cast::<__m256i>());
+    }
+    transpose(&mut s);
+
+    while n != 0 {
+        let s_save: [__m256i; 8] = s;
+
+        // Likewise, `w[i]` holds the i-th message word of every lane.
+        let mut w: [__m256i; 16] = [_mm256_setzero_si256(); 16];
+        for half in 0..2 {
+            let mut r: [__m256i; 8] = [_mm256_setzero_si256(); 8];
+            for (r, block) in r.iter_mut().zip(blocks) {
+                *r = _mm256_shuffle_epi8(
+                    _mm256_loadu_si256(block.cast::<__m256i>().add(half)),
+                    mask,
+                );
+            }
+            transpose(&mut r);
+            w[half * 8..half * 8 + 8].copy_from_slice(&r);
+        }
+
+        // Compiler is able to unroll this loop.
+        for i in 0..64 {
+            // Message schedule for rounds 16-63.
+            if i >= 16 {
+                let w2: __m256i = w[(i - 2) % 16];
+                let w15: __m256i = w[(i - 15) % 16];
+                let sigma1: __m256i = _mm256_xor_si256(
+                    _mm256_xor_si256(rotr!(w2, 17), rotr!(w2, 19)),
+                    _mm256_srli_epi32(w2, 10),
+                );
+                let sigma0: __m256i = _mm256_xor_si256(
+                    _mm256_xor_si256(rotr!(w15, 7), rotr!(w15, 18)),
+                    _mm256_srli_epi32(w15, 3),
+                );
+                w[i % 16] = _mm256_add_epi32(
+                    _mm256_add_epi32(w[i % 16], sigma0),
+                    _mm256_add_epi32(w[(i - 7) % 16], sigma1),
+                );
+            }
+
+            let [a, b, c, d, e, f, g, h] = s;
+
+            let e_rho: __m256i =
+                _mm256_xor_si256(_mm256_xor_si256(rotr!(e, 6), rotr!(e, 11)), rotr!(e, 25));
+            let ch: __m256i = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+            let t1: __m256i = _mm256_add_epi32(
+                _mm256_add_epi32(_mm256_add_epi32(h, e_rho), _mm256_add_epi32(ch, w[i % 16])),
+                _mm256_set1_epi32(K[i] as i32),
+            );
+
+            let a_rho: __m256i =
+                _mm256_xor_si256(_mm256_xor_si256(rotr!(a, 2), rotr!(a, 13)), rotr!(a, 22));
+            let maj: __m256i = _mm256_or_si256(
+                _mm256_and_si256(a, b),
+                _mm256_and_si256(_mm256_or_si256(a, b), c),
+            );
+            let t2: __m256i = _mm256_add_epi32(a_rho, maj);
+
+            s = [
+                _mm256_add_epi32(t1, t2),
+                a,
+                b,
+                c,
+                _mm256_add_epi32(d, t1),
+                e,
+                f,
+                g,
+            ];
+        }
+
+        for (s, s_save) in s.iter_mut().zip(s_save) {
+            *s = _mm256_add_epi32(*s, s_save);
+        }
+        for block in blocks.iter_mut() {
+            *block = block.add(64);
+        }
+        n -= 1;
+    }
+
+    transpose(&mut s);
+    for (s, state) in s.into_iter().zip(states) {
+        _mm256_storeu_si256(state.cast::<__m256i>(), s);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha256_x8_compress()
+    {
+        let mut states_generic: [[u32; 8]; 8] = [[
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ]; 8];
+        let mut states_avx2 = states_generic;
+        let mut blocks = [[0u8; 64 * 16]; 8];
+        blocks.iter_mut().enumerate().for_each(|(i, lane)| {
+            lane.iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 131 + j * 7 + j / 64 * 31) as u8)
+        });
+        unsafe {
+            for i in 0..8 {
+                md_compress::sha256(states_generic[i].as_mut_ptr(), blocks[i].as_ptr(), 16);
+            }
+            sha256_x8(
+                core::array::from_fn(|i| states_avx2[i].as_mut_ptr()),
+                core::array::from_fn(|i| blocks[i].as_ptr()),
+                16,
+            );
+        }
+        assert_eq!(states_generic, states_avx2);
+    }
+}
@@ -30,11 +30,22 @@ macro_rules! sha2_64_f {
 ///
 /// The caller must guarantee that the passed variables point to valid memory
 /// spaces. `state` must point to an array with a length of 8 (64 bytes).
-/// `block` must point to an array with a length of 128 (128 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 128
+/// bytes.
+pub const unsafe fn sha512(state: *mut u64, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(128);
+        blocks -= 1;
+    }
+}
+
 #[allow(clippy::many_single_char_names)]
 #[allow(unused_assignments)]
 #[rustfmt::skip]
-pub const unsafe fn sha512(state: *mut u64, block: *const u8)
+#[inline(always)]
+const unsafe fn compress(state: *mut u64, block: *const u8)
 {
     let mut a: u64 = *state.add(0);
     let mut b: u64 = *state.add(1);
@@ -45,22 +56,22 @@ pub const unsafe fn sha512(state: *mut u64, block: *const u8)
     let mut g: u64 = *state.add(6);
     let mut h: u64 = *state.add(7);
 
-    let mut w00: u64 = (*(block as *const u64).add(0)).to_be();
-    let mut w01: u64 = (*(block as *const u64).add(1)).to_be();
-    let mut w02: u64 = (*(block as *const u64).add(2)).to_be();
-    let mut w03: u64 = (*(block as *const u64).add(3)).to_be();
-    let mut w04: u64 = (*(block as *const u64).add(4)).to_be();
-    let mut w05: u64 = (*(block as *const u64).add(5)).to_be();
-    let mut w06: u64 = (*(block as *const u64).add(6)).to_be();
-    let mut w07: u64 = (*(block as *const u64).add(7)).to_be();
-    let mut w08: u64 = (*(block as *const u64).add(8)).to_be();
-    let mut w09: u64 = (*(block as *const u64).add(9)).to_be();
-    let mut w10: u64 = (*(block as *const u64).add(10)).to_be();
-    let mut w11: u64 = (*(block as *const u64).add(11)).to_be();
-    let mut w12: u64 = (*(block as *const u64).add(12)).to_be();
-    let mut w13: u64 = (*(block as *const u64).add(13)).to_be();
-    let mut w14: u64 = (*(block as *const u64).add(14)).to_be();
-    let mut w15: u64 = (*(block as *const u64).add(15)).to_be();
+    let mut w00: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(0));
+    let mut w01: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(1));
+    let mut w02: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(2));
+    let mut w03: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(3));
+    let mut w04: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(4));
+    let mut w05: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(5));
+    let mut w06: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(6));
+    let mut w07: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(7));
+    let mut w08: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(8));
+    let mut w09: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(9));
+    let mut w10: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(10));
+    let mut w11: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(11));
+    let mut w12: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(12));
+    let mut w13: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(13));
+    let mut w14: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(14));
+    let mut w15: u64 = u64::from_be_bytes(*(block as *const [u8; 8]).add(15));
 
     sha2_64_f!(a, b, c, d, e, f, g, h, w00, w14, w09, w01, 0x428a2f98d728ae22);
     sha2_64_f!(h, a, b, c, d, e, f, g, w01, w15, w10, w02, 0x7137449123ef65cd);
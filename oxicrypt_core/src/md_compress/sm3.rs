@@ -0,0 +1,98 @@
+#[inline(always)]
+const fn p0(x: u32) -> u32 { x ^ x.rotate_left(9) ^ x.rotate_left(17) }
+
+#[inline(always)]
+const fn p1(x: u32) -> u32 { x ^ x.rotate_left(15) ^ x.rotate_left(23) }
+
+/// Compression function used by the SM3 algorithm.
+///
+/// You shouldn't use this function unless you want to implement the algorithm
+/// by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 8 (32 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes.
+pub const unsafe fn sm3(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    while blocks != 0 {
+        compress(state, block);
+        block = block.add(64);
+        blocks -= 1;
+    }
+}
+
+#[allow(clippy::many_single_char_names)]
+#[inline(always)]
+const unsafe fn compress(state: *mut u32, block: *const u8)
+{
+    // Message expansion.
+    let mut w: [u32; 68] = [0; 68];
+    let mut j = 0;
+    while j < 16 {
+        w[j] = u32::from_be_bytes(*block.cast::<[u8; 4]>().add(j));
+        j += 1;
+    }
+    while j < 68 {
+        w[j] = p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15))
+            ^ w[j - 13].rotate_left(7)
+            ^ w[j - 6];
+        j += 1;
+    }
+
+    let mut a: u32 = *state.add(0);
+    let mut b: u32 = *state.add(1);
+    let mut c: u32 = *state.add(2);
+    let mut d: u32 = *state.add(3);
+    let mut e: u32 = *state.add(4);
+    let mut f: u32 = *state.add(5);
+    let mut g: u32 = *state.add(6);
+    let mut h: u32 = *state.add(7);
+
+    let mut j = 0;
+    while j < 64 {
+        let (t, ff, gg) = if j < 16 {
+            (0x79cc4519_u32, a ^ b ^ c, e ^ f ^ g)
+        } else {
+            (
+                0x7a879d8a_u32,
+                (a & b) | (a & c) | (b & c),
+                (e & f) | (!e & g),
+            )
+        };
+
+        let ss1: u32 = a
+            .rotate_left(12)
+            .wrapping_add(e)
+            .wrapping_add(t.rotate_left(j as u32 % 32))
+            .rotate_left(7);
+        let ss2: u32 = ss1 ^ a.rotate_left(12);
+        let tt1: u32 = ff
+            .wrapping_add(d)
+            .wrapping_add(ss2)
+            .wrapping_add(w[j] ^ w[j + 4]);
+        let tt2: u32 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+
+        d = c;
+        c = b.rotate_left(9);
+        b = a;
+        a = tt1;
+        h = g;
+        g = f.rotate_left(19);
+        f = e;
+        e = p0(tt2);
+
+        j += 1;
+    }
+
+    *state.add(0) ^= a;
+    *state.add(1) ^= b;
+    *state.add(2) ^= c;
+    *state.add(3) ^= d;
+    *state.add(4) ^= e;
+    *state.add(5) ^= f;
+    *state.add(6) ^= g;
+    *state.add(7) ^= h;
+}
@@ -0,0 +1,85 @@
+//! Salsa20 core used by the scrypt key derivation function.
+
+/// Applies the Salsa20 quarter-round to the words at indices `a`, `b`, `c` and
+/// `d` of the state.
+const fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize)
+{
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
+
+/// Salsa20/8 core, which is the Salsa20 core reduced to 8 rounds.
+///
+/// The 64-byte block is stored as 16 words that were read in little-endian
+/// byte order. The rounds are applied to a copy of the block, which is then
+/// added to the block word by word.
+///
+/// You shouldn't use this function unless you want to implement the algorithms
+/// by yourself.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variable points to a valid memory
+/// space. `block` must point to an array with a length of 16 (64 bytes).
+pub const unsafe fn salsa20_8(block: *mut u32)
+{
+    let b: &mut [u32; 16] = &mut *block.cast::<[u32; 16]>();
+    let mut x: [u32; 16] = *b;
+
+    let mut round = 0;
+    while round < 8 {
+        // columns
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 5, 9, 13, 1);
+        quarter_round(&mut x, 10, 14, 2, 6);
+        quarter_round(&mut x, 15, 3, 7, 11);
+        // rows
+        quarter_round(&mut x, 0, 1, 2, 3);
+        quarter_round(&mut x, 5, 6, 7, 4);
+        quarter_round(&mut x, 10, 11, 8, 9);
+        quarter_round(&mut x, 15, 12, 13, 14);
+        round += 2;
+    }
+
+    let mut i = 0;
+    while i < 16 {
+        b[i] = b[i].wrapping_add(x[i]);
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn rfc7914()
+    {
+        let input: [u8; 64] = [
+            0x7e, 0x87, 0x9a, 0x21, 0x4f, 0x3e, 0xc9, 0x86, 0x7c, 0xa9, 0x40, 0xe6, 0x41, 0x71,
+            0x8f, 0x26, 0xba, 0xee, 0x55, 0x5b, 0x8c, 0x61, 0xc1, 0xb5, 0x0d, 0xf8, 0x46, 0x11,
+            0x6d, 0xcd, 0x3b, 0x1d, 0xee, 0x24, 0xf3, 0x19, 0xdf, 0x9b, 0x3d, 0x85, 0x14, 0x12,
+            0x1e, 0x4b, 0x5a, 0xc5, 0xaa, 0x32, 0x76, 0x02, 0x1d, 0x29, 0x09, 0xc7, 0x48, 0x29,
+            0xed, 0xeb, 0xc6, 0x8d, 0xb8, 0xb8, 0xc2, 0x5e,
+        ];
+        let expected: [u8; 64] = [
+            0xa4, 0x1f, 0x85, 0x9c, 0x66, 0x08, 0xcc, 0x99, 0x3b, 0x81, 0xca, 0xcb, 0x02, 0x0c,
+            0xef, 0x05, 0x04, 0x4b, 0x21, 0x81, 0xa2, 0xfd, 0x33, 0x7d, 0xfd, 0x7b, 0x1c, 0x63,
+            0x96, 0x68, 0x2f, 0x29, 0xb4, 0x39, 0x31, 0x68, 0xe3, 0xc9, 0xe6, 0xbc, 0xfe, 0x6b,
+            0xc5, 0xb7, 0xa0, 0x6d, 0x96, 0xba, 0xe4, 0x24, 0xcc, 0x10, 0x2c, 0x91, 0x74, 0x5c,
+            0x24, 0xad, 0x67, 0x3d, 0xc7, 0x61, 0x8f, 0x81,
+        ];
+
+        let mut block: [u32; 16] = [0; 16];
+        for (w, b) in block.iter_mut().zip(input.chunks_exact(4)) {
+            *w = u32::from_le_bytes(b.try_into().unwrap());
+        }
+        unsafe { salsa20_8(block.as_mut_ptr()) };
+        for (w, b) in block.iter().zip(expected.chunks_exact(4)) {
+            assert_eq!(w.to_le_bytes(), b);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+#[cfg(not(any(target_arch = "arm", target_arch = "aarch64", doc)))]
+compile_error!("`oxicrypt_core::sha_arm` is only available for \"arm\" and \"aarch64\"");
+
+mod sha1;
+mod sha256;
+#[cfg(any(target_arch = "aarch64", doc))]
+mod sha512;
+
+pub use sha1::sha1;
+pub use sha256::sha256;
+#[cfg(any(target_arch = "aarch64", doc))]
+pub use sha512::sha512;
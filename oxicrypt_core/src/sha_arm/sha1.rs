@@ -0,0 +1,117 @@
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+#[cfg(target_arch = "arm")]
+use core::arch::arm::*;
+
+/// Round constants used by SHA-1, one for each group of 20 rounds.
+const K: [u32; 4] = [0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xca62c1d6];
+
+/// Compression function used by the SHA-1 algorithm, implemented using the
+/// ARMv8 SHA1 instructions.
+///
+/// This function produces the same result as
+/// [`md_compress::sha1`](`crate::md_compress::sha1`).
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 5 (20 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes. The CPU must support the `neon` and `sha2` features.
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "sha2")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn sha1(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    let mut abcd: uint32x4_t = vld1q_u32(state);
+    let mut e0: u32 = *state.add(4);
+
+    while blocks != 0 {
+        let abcd_save: uint32x4_t = abcd;
+        let e0_save: u32 = e0;
+
+        // Message words are big endian.
+        let mut w: [uint32x4_t; 4] = [
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(0 * 16)))),
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(1 * 16)))),
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(2 * 16)))),
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(3 * 16)))),
+        ];
+
+        // Compiler is able to unroll this loop.
+        for i in 0..20 {
+            // Message schedule for rounds 16-79.
+            if i >= 4 {
+                w[i % 4] = vsha1su1q_u32(
+                    vsha1su0q_u32(w[i % 4], w[(i + 1) % 4], w[(i + 2) % 4]),
+                    w[(i + 3) % 4],
+                );
+            }
+
+            let wk: uint32x4_t = vaddq_u32(w[i % 4], vdupq_n_u32(K[i / 5]));
+            let e1: u32 = vsha1h_u32(vgetq_lane_u32(abcd, 0));
+            abcd = match i / 5 {
+                | 0 => vsha1cq_u32(abcd, e0, wk),
+                | 2 => vsha1mq_u32(abcd, e0, wk),
+                | _ => vsha1pq_u32(abcd, e0, wk),
+            };
+            e0 = e1;
+        }
+
+        abcd = vaddq_u32(abcd, abcd_save);
+        e0 = e0.wrapping_add(e0_save);
+
+        block = block.add(64);
+        blocks -= 1;
+    }
+
+    vst1q_u32(state, abcd);
+    *state.add(4) = e0;
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha1_compress()
+    {
+        let mut state_generic: [u32; 5] =
+            [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut state_arm = state_generic;
+        let mut block = [0u8; 64];
+        for i in 0..256 {
+            block
+                .iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 31 + j * 7) as u8 ^ state_generic[j % 5] as u8);
+            unsafe {
+                md_compress::sha1(state_generic.as_mut_ptr(), block.as_ptr(), 1);
+                sha1(state_arm.as_mut_ptr(), block.as_ptr(), 1);
+            }
+            assert_eq!(state_generic, state_arm);
+        }
+    }
+
+    #[test]
+    fn sha1_compress_blocks()
+    {
+        let mut state_generic: [u32; 5] =
+            [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut state_arm = state_generic;
+        let mut blocks = [0u8; 64 * 16];
+        blocks
+            .iter_mut()
+            .enumerate()
+            .for_each(|(j, b)| *b = (j * 7 + j / 64 * 31) as u8);
+        unsafe {
+            blocks
+                .chunks_exact(64)
+                .for_each(|block| md_compress::sha1(state_generic.as_mut_ptr(), block.as_ptr(), 1));
+            sha1(state_arm.as_mut_ptr(), blocks.as_ptr(), 16);
+        }
+        assert_eq!(state_generic, state_arm);
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+#[cfg(target_arch = "arm")]
+use core::arch::arm::*;
+
+/// Round constants used by SHA-224 and SHA-256.
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Compression function used by the SHA-2 family of functions, namely SHA-224
+/// and SHA-256, implemented using the ARMv8 SHA256 instructions.
+///
+/// This function produces the same result as
+/// [`md_compress::sha256`](`crate::md_compress::sha256`).
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 8 (32 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes. The CPU must support the `neon` and `sha2` features.
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "sha2")]
+#[doc(cfg(any(target_arch = "arm", target_arch = "aarch64")))]
+pub unsafe fn sha256(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    let mut abcd: uint32x4_t = vld1q_u32(state.add(0));
+    let mut efgh: uint32x4_t = vld1q_u32(state.add(4));
+
+    while blocks != 0 {
+        let abcd_save: uint32x4_t = abcd;
+        let efgh_save: uint32x4_t = efgh;
+
+        // Message words are big endian.
+        let mut w: [uint32x4_t; 4] = [
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(0 * 16)))),
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(1 * 16)))),
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(2 * 16)))),
+            vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.add(3 * 16)))),
+        ];
+
+        // Compiler is able to unroll this loop.
+        for i in 0..16 {
+            // Message schedule for rounds 16-63.
+            if i >= 4 {
+                w[i % 4] = vsha256su1q_u32(
+                    vsha256su0q_u32(w[i % 4], w[(i + 1) % 4]),
+                    w[(i + 2) % 4],
+                    w[(i + 3) % 4],
+                );
+            }
+
+            let wk: uint32x4_t = vaddq_u32(w[i % 4], vld1q_u32(K.as_ptr().add(i * 4)));
+            let tmp: uint32x4_t = abcd;
+            abcd = vsha256hq_u32(abcd, efgh, wk);
+            efgh = vsha256h2q_u32(efgh, tmp, wk);
+        }
+
+        abcd = vaddq_u32(abcd, abcd_save);
+        efgh = vaddq_u32(efgh, efgh_save);
+
+        block = block.add(64);
+        blocks -= 1;
+    }
+
+    vst1q_u32(state.add(0), abcd);
+    vst1q_u32(state.add(4), efgh);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha256_compress()
+    {
+        let mut state_generic: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut state_arm = state_generic;
+        let mut block = [0u8; 64];
+        for i in 0..256 {
+            block
+                .iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 31 + j * 7) as u8 ^ state_generic[j % 8] as u8);
+            unsafe {
+                md_compress::sha256(state_generic.as_mut_ptr(), block.as_ptr(), 1);
+                sha256(state_arm.as_mut_ptr(), block.as_ptr(), 1);
+            }
+            assert_eq!(state_generic, state_arm);
+        }
+    }
+
+    #[test]
+    fn sha256_compress_blocks()
+    {
+        let mut state_generic: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut state_arm = state_generic;
+        let mut blocks = [0u8; 64 * 16];
+        blocks
+            .iter_mut()
+            .enumerate()
+            .for_each(|(j, b)| *b = (j * 7 + j / 64 * 31) as u8);
+        unsafe {
+            blocks.chunks_exact(64).for_each(|block| {
+                md_compress::sha256(state_generic.as_mut_ptr(), block.as_ptr(), 1)
+            });
+            sha256(state_arm.as_mut_ptr(), blocks.as_ptr(), 16);
+        }
+        assert_eq!(state_generic, state_arm);
+    }
+}
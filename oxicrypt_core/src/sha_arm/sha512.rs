@@ -0,0 +1,173 @@
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+
+/// Round constants used by the SHA-512 family of functions.
+#[rustfmt::skip]
+const K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+/// Compression function used by the SHA-2 family of functions, namely SHA-384,
+/// SHA-512, SHA-512/224 and SHA-512/256, implemented using the ARMv8.2 SHA512
+/// instructions.
+///
+/// This function produces the same result as
+/// [`md_compress::sha512`](`crate::md_compress::sha512`).
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 8 (64 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 128
+/// bytes. The CPU must support the `neon` and `sha3` features, the latter of
+/// which includes the SHA512 instructions.
+#[target_feature(enable = "neon")]
+#[target_feature(enable = "sha3")]
+#[doc(cfg(target_arch = "aarch64"))]
+pub unsafe fn sha512(state: *mut u64, mut block: *const u8, mut blocks: usize)
+{
+    // The state is kept as the pairs AB, CD, EF and GH. Each round pair below
+    // produces a new pair, so the roles of the four registers rotate.
+    let mut s: [uint64x2_t; 4] = [
+        vld1q_u64(state.add(0)),
+        vld1q_u64(state.add(2)),
+        vld1q_u64(state.add(4)),
+        vld1q_u64(state.add(6)),
+    ];
+
+    while blocks != 0 {
+        let s_save: [uint64x2_t; 4] = s;
+
+        // Message words are big endian.
+        let mut w: [uint64x2_t; 8] = [
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(0 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(1 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(2 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(3 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(4 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(5 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(6 * 16)))),
+            vreinterpretq_u64_u8(vrev64q_u8(vld1q_u8(block.add(7 * 16)))),
+        ];
+
+        // Compiler is able to unroll this loop.
+        for i in 0..40 {
+            // Message schedule for rounds 16-79.
+            if i >= 8 {
+                w[i % 8] = vsha512su1q_u64(
+                    vsha512su0q_u64(w[i % 8], w[(i + 1) % 8]),
+                    w[(i + 7) % 8],
+                    vextq_u64(w[(i + 4) % 8], w[(i + 5) % 8], 1),
+                );
+            }
+
+            // Indices of the pairs currently holding GH, EF, CD and AB.
+            let h: usize = (7 - i % 4) % 4;
+            let f: usize = (h + 3) % 4;
+            let d: usize = (h + 2) % 4;
+            let b: usize = (h + 1) % 4;
+
+            let wk: uint64x2_t = vaddq_u64(w[i % 8], vld1q_u64(K.as_ptr().add(i * 2)));
+            let sum: uint64x2_t = vaddq_u64(vextq_u64(wk, wk, 1), s[h]);
+            let tmp: uint64x2_t =
+                vsha512hq_u64(sum, vextq_u64(s[f], s[h], 1), vextq_u64(s[d], s[f], 1));
+            s[h] = vsha512h2q_u64(tmp, s[d], s[b]);
+            s[d] = vaddq_u64(s[d], tmp);
+        }
+
+        s[0] = vaddq_u64(s[0], s_save[0]);
+        s[1] = vaddq_u64(s[1], s_save[1]);
+        s[2] = vaddq_u64(s[2], s_save[2]);
+        s[3] = vaddq_u64(s[3], s_save[3]);
+
+        block = block.add(128);
+        blocks -= 1;
+    }
+
+    vst1q_u64(state.add(0), s[0]);
+    vst1q_u64(state.add(2), s[1]);
+    vst1q_u64(state.add(4), s[2]);
+    vst1q_u64(state.add(6), s[3]);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha512_compress()
+    {
+        let mut state_generic: [u64; 8] = [
+            0x6a09e667f3bcc908,
+            0xbb67ae8584caa73b,
+            0x3c6ef372fe94f82b,
+            0xa54ff53a5f1d36f1,
+            0x510e527fade682d1,
+            0x9b05688c2b3e6c1f,
+            0x1f83d9abfb41bd6b,
+            0x5be0cd19137e2179,
+        ];
+        let mut state_arm = state_generic;
+        let mut block = [0u8; 128];
+        for i in 0..256 {
+            block
+                .iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 31 + j * 7) as u8 ^ state_generic[j % 8] as u8);
+            unsafe {
+                md_compress::sha512(state_generic.as_mut_ptr(), block.as_ptr(), 1);
+                sha512(state_arm.as_mut_ptr(), block.as_ptr(), 1);
+            }
+            assert_eq!(state_generic, state_arm);
+        }
+    }
+
+    #[test]
+    fn sha512_compress_blocks()
+    {
+        let mut state_generic: [u64; 8] = [
+            0x6a09e667f3bcc908,
+            0xbb67ae8584caa73b,
+            0x3c6ef372fe94f82b,
+            0xa54ff53a5f1d36f1,
+            0x510e527fade682d1,
+            0x9b05688c2b3e6c1f,
+            0x1f83d9abfb41bd6b,
+            0x5be0cd19137e2179,
+        ];
+        let mut state_arm = state_generic;
+        let mut blocks = [0u8; 128 * 16];
+        blocks
+            .iter_mut()
+            .enumerate()
+            .for_each(|(j, b)| *b = (j * 7 + j / 128 * 31) as u8);
+        unsafe {
+            blocks.chunks_exact(128).for_each(|block| {
+                md_compress::sha512(state_generic.as_mut_ptr(), block.as_ptr(), 1)
+            });
+            sha512(state_arm.as_mut_ptr(), blocks.as_ptr(), 16);
+        }
+        assert_eq!(state_generic, state_arm);
+    }
+}
@@ -0,0 +1,8 @@
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", doc)))]
+compile_error!("`oxicrypt_core::shani` is only available for \"x86\" and \"x86_64\"");
+
+mod sha1;
+mod sha256;
+
+pub use sha1::sha1;
+pub use sha256::sha256;
@@ -0,0 +1,123 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Compression function used by the SHA-1 algorithm, implemented using the
+/// x86 SHA extensions.
+///
+/// This function produces the same result as
+/// [`md_compress::sha1`](`crate::md_compress::sha1`).
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 5 (20 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes. The CPU must support the `sha`, `ssse3` and `sse4.1` features.
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn sha1(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    // Reverses the byte order of the whole 128-bit lane, which converts four
+    // big-endian words into the word order expected by the SHA instructions.
+    let mask: __m128i = _mm_set_epi64x(0x0001020304050607, 0x08090a0b0c0d0e0f);
+
+    let mut abcd: __m128i = _mm_loadu_si128(state.cast::<__m128i>());
+    abcd = _mm_shuffle_epi32(abcd, 0x1b);
+    let mut e0: __m128i = _mm_set_epi32(*state.add(4) as i32, 0, 0, 0);
+
+    while blocks != 0 {
+        let abcd_save: __m128i = abcd;
+        let e0_save: __m128i = e0;
+
+        let mut w: [__m128i; 4] = [
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(0)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(1)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(2)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(3)), mask),
+        ];
+
+        // rounds 0-3
+        e0 = _mm_add_epi32(e0, w[0]);
+        let mut e1: __m128i = abcd;
+        abcd = _mm_sha1rnds4_epu32(abcd, e0, 0);
+
+        // Compiler is able to unroll this loop.
+        for i in 1..20 {
+            // Message schedule for rounds 16-79.
+            if i >= 4 {
+                w[i % 4] = _mm_sha1msg2_epu32(
+                    _mm_xor_si128(_mm_sha1msg1_epu32(w[i % 4], w[(i + 1) % 4]), w[(i + 2) % 4]),
+                    w[(i + 3) % 4],
+                );
+            }
+
+            e0 = _mm_sha1nexte_epu32(e1, w[i % 4]);
+            e1 = abcd;
+            abcd = match i / 5 {
+                | 0 => _mm_sha1rnds4_epu32(abcd, e0, 0),
+                | 1 => _mm_sha1rnds4_epu32(abcd, e0, 1),
+                | 2 => _mm_sha1rnds4_epu32(abcd, e0, 2),
+                | _ => _mm_sha1rnds4_epu32(abcd, e0, 3),
+            };
+        }
+
+        e0 = _mm_sha1nexte_epu32(e1, e0_save);
+        abcd = _mm_add_epi32(abcd, abcd_save);
+
+        block = block.add(64);
+        blocks -= 1;
+    }
+
+    abcd = _mm_shuffle_epi32(abcd, 0x1b);
+    _mm_storeu_si128(state.cast::<__m128i>(), abcd);
+    *state.add(4) = _mm_extract_epi32(e0, 3) as u32;
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha1_compress()
+    {
+        let mut state_generic: [u32; 5] =
+            [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut state_shani = state_generic;
+        let mut block = [0u8; 64];
+        for i in 0..256 {
+            block
+                .iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 31 + j * 7) as u8 ^ state_generic[j % 5] as u8);
+            unsafe {
+                md_compress::sha1(state_generic.as_mut_ptr(), block.as_ptr(), 1);
+                sha1(state_shani.as_mut_ptr(), block.as_ptr(), 1);
+            }
+            assert_eq!(state_generic, state_shani);
+        }
+    }
+
+    #[test]
+    fn sha1_compress_blocks()
+    {
+        let mut state_generic: [u32; 5] =
+            [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut state_shani = state_generic;
+        let mut blocks = [0u8; 64 * 16];
+        blocks
+            .iter_mut()
+            .enumerate()
+            .for_each(|(j, b)| *b = (j * 7 + j / 64 * 31) as u8);
+        unsafe {
+            blocks
+                .chunks_exact(64)
+                .for_each(|block| md_compress::sha1(state_generic.as_mut_ptr(), block.as_ptr(), 1));
+            sha1(state_shani.as_mut_ptr(), blocks.as_ptr(), 16);
+        }
+        assert_eq!(state_generic, state_shani);
+    }
+}
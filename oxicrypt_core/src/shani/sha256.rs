@@ -0,0 +1,144 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Round constants used by SHA-224 and SHA-256.
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Compression function used by the SHA-2 family of functions, namely SHA-224
+/// and SHA-256, implemented using the x86 SHA extensions.
+///
+/// This function produces the same result as
+/// [`md_compress::sha256`](`crate::md_compress::sha256`).
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `state` must point to an array with a length of 8 (32 bytes).
+/// `block` must point to `blocks` consecutive blocks, each with a length of 64
+/// bytes. The CPU must support the `sha`, `ssse3` and `sse4.1` features.
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+#[doc(cfg(any(target_arch = "x86", target_arch = "x86_64")))]
+pub unsafe fn sha256(state: *mut u32, mut block: *const u8, mut blocks: usize)
+{
+    // Swaps the bytes of each 32-bit word.
+    let mask: __m128i = _mm_set_epi64x(0x0c0d0e0f08090a0b, 0x0405060700010203);
+
+    // The SHA instructions expect the state to be split into ABEF and CDGH.
+    let mut tmp: __m128i = _mm_loadu_si128(state.cast::<__m128i>().add(0));
+    let mut state1: __m128i = _mm_loadu_si128(state.cast::<__m128i>().add(1));
+    tmp = _mm_shuffle_epi32(tmp, 0xb1); // CDAB
+    state1 = _mm_shuffle_epi32(state1, 0x1b); // EFGH
+    let mut state0: __m128i = _mm_alignr_epi8(tmp, state1, 8); // ABEF
+    state1 = _mm_blend_epi16(state1, tmp, 0xf0); // CDGH
+
+    while blocks != 0 {
+        let abef_save: __m128i = state0;
+        let cdgh_save: __m128i = state1;
+
+        let mut w: [__m128i; 4] = [
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(0)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(1)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(2)), mask),
+            _mm_shuffle_epi8(_mm_loadu_si128(block.cast::<__m128i>().add(3)), mask),
+        ];
+
+        // Compiler is able to unroll this loop.
+        for i in 0..16 {
+            // Message schedule for rounds 16-63.
+            if i >= 4 {
+                w[i % 4] = _mm_sha256msg2_epu32(
+                    _mm_add_epi32(
+                        _mm_sha256msg1_epu32(w[i % 4], w[(i + 1) % 4]),
+                        _mm_alignr_epi8(w[(i + 3) % 4], w[(i + 2) % 4], 4),
+                    ),
+                    w[(i + 3) % 4],
+                );
+            }
+
+            let mut msg: __m128i = _mm_add_epi32(
+                w[i % 4],
+                _mm_loadu_si128(K.as_ptr().cast::<__m128i>().add(i)),
+            );
+            state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+            msg = _mm_shuffle_epi32(msg, 0x0e);
+            state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+        }
+
+        state0 = _mm_add_epi32(state0, abef_save);
+        state1 = _mm_add_epi32(state1, cdgh_save);
+
+        block = block.add(64);
+        blocks -= 1;
+    }
+
+    tmp = _mm_shuffle_epi32(state0, 0x1b); // FEBA
+    state1 = _mm_shuffle_epi32(state1, 0xb1); // DCHG
+    state0 = _mm_blend_epi16(tmp, state1, 0xf0); // DCBA
+    state1 = _mm_alignr_epi8(state1, tmp, 8); // ABEF
+
+    _mm_storeu_si128(state.cast::<__m128i>().add(0), state0);
+    _mm_storeu_si128(state.cast::<__m128i>().add(1), state1);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::md_compress;
+
+    #[test]
+    fn sha256_compress()
+    {
+        let mut state_generic: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut state_shani = state_generic;
+        let mut block = [0u8; 64];
+        for i in 0..256 {
+            block
+                .iter_mut()
+                .enumerate()
+                .for_each(|(j, b)| *b = (i * 31 + j * 7) as u8 ^ state_generic[j % 8] as u8);
+            unsafe {
+                md_compress::sha256(state_generic.as_mut_ptr(), block.as_ptr(), 1);
+                sha256(state_shani.as_mut_ptr(), block.as_ptr(), 1);
+            }
+            assert_eq!(state_generic, state_shani);
+        }
+    }
+
+    #[test]
+    fn sha256_compress_blocks()
+    {
+        let mut state_generic: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut state_shani = state_generic;
+        let mut blocks = [0u8; 64 * 16];
+        blocks
+            .iter_mut()
+            .enumerate()
+            .for_each(|(j, b)| *b = (j * 7 + j / 64 * 31) as u8);
+        unsafe {
+            blocks.chunks_exact(64).for_each(|block| {
+                md_compress::sha256(state_generic.as_mut_ptr(), block.as_ptr(), 1)
+            });
+            sha256(state_shani.as_mut_ptr(), blocks.as_ptr(), 16);
+        }
+        assert_eq!(state_generic, state_shani);
+    }
+}
@@ -0,0 +1,183 @@
+//! SM4 block cipher using a lookup table for the S-box.
+//!
+//! SM4 has a block size of 16 bytes and a key size of 16 bytes. It is an
+//! unbalanced Feistel network with 32 rounds, each of which uses a 32-bit round
+//! key. Decryption is the same as encryption with the round keys in reverse
+//! order.
+
+#[rustfmt::skip]
+const SBOX: [u8; 256] = [
+  0xd6, 0x90, 0xe9, 0xfe, 0xcc, 0xe1, 0x3d, 0xb7, 0x16, 0xb6, 0x14, 0xc2, 0x28, 0xfb, 0x2c, 0x05,
+  0x2b, 0x67, 0x9a, 0x76, 0x2a, 0xbe, 0x04, 0xc3, 0xaa, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
+  0x9c, 0x42, 0x50, 0xf4, 0x91, 0xef, 0x98, 0x7a, 0x33, 0x54, 0x0b, 0x43, 0xed, 0xcf, 0xac, 0x62,
+  0xe4, 0xb3, 0x1c, 0xa9, 0xc9, 0x08, 0xe8, 0x95, 0x80, 0xdf, 0x94, 0xfa, 0x75, 0x8f, 0x3f, 0xa6,
+  0x47, 0x07, 0xa7, 0xfc, 0xf3, 0x73, 0x17, 0xba, 0x83, 0x59, 0x3c, 0x19, 0xe6, 0x85, 0x4f, 0xa8,
+  0x68, 0x6b, 0x81, 0xb2, 0x71, 0x64, 0xda, 0x8b, 0xf8, 0xeb, 0x0f, 0x4b, 0x70, 0x56, 0x9d, 0x35,
+  0x1e, 0x24, 0x0e, 0x5e, 0x63, 0x58, 0xd1, 0xa2, 0x25, 0x22, 0x7c, 0x3b, 0x01, 0x21, 0x78, 0x87,
+  0xd4, 0x00, 0x46, 0x57, 0x9f, 0xd3, 0x27, 0x52, 0x4c, 0x36, 0x02, 0xe7, 0xa0, 0xc4, 0xc8, 0x9e,
+  0xea, 0xbf, 0x8a, 0xd2, 0x40, 0xc7, 0x38, 0xb5, 0xa3, 0xf7, 0xf2, 0xce, 0xf9, 0x61, 0x15, 0xa1,
+  0xe0, 0xae, 0x5d, 0xa4, 0x9b, 0x34, 0x1a, 0x55, 0xad, 0x93, 0x32, 0x30, 0xf5, 0x8c, 0xb1, 0xe3,
+  0x1d, 0xf6, 0xe2, 0x2e, 0x82, 0x66, 0xca, 0x60, 0xc0, 0x29, 0x23, 0xab, 0x0d, 0x53, 0x4e, 0x6f,
+  0xd5, 0xdb, 0x37, 0x45, 0xde, 0xfd, 0x8e, 0x2f, 0x03, 0xff, 0x6a, 0x72, 0x6d, 0x6c, 0x5b, 0x51,
+  0x8d, 0x1b, 0xaf, 0x92, 0xbb, 0xdd, 0xbc, 0x7f, 0x11, 0xd9, 0x5c, 0x41, 0x1f, 0x10, 0x5a, 0xd8,
+  0x0a, 0xc1, 0x31, 0x88, 0xa5, 0xcd, 0x7b, 0xbd, 0x2d, 0x74, 0xd0, 0x12, 0xb8, 0xe5, 0xb4, 0xb0,
+  0x89, 0x69, 0x97, 0x4a, 0x0c, 0x96, 0x77, 0x7e, 0x65, 0xb9, 0xf1, 0x09, 0xc5, 0x6e, 0xc6, 0x84,
+  0x18, 0xf0, 0x7d, 0xec, 0x3a, 0xdc, 0x4d, 0x20, 0x79, 0xee, 0x5f, 0x3e, 0xd7, 0xcb, 0x39, 0x48,
+];
+
+/// System parameters that are mixed into the key.
+const FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
+
+/// Applies the S-box to each byte of `x`.
+#[inline(always)]
+const fn tau(x: u32) -> u32
+{
+    let b: [u8; 4] = x.to_be_bytes();
+    u32::from_be_bytes([
+        SBOX[b[0] as usize],
+        SBOX[b[1] as usize],
+        SBOX[b[2] as usize],
+        SBOX[b[3] as usize],
+    ])
+}
+
+/// Round function used by encryption and decryption.
+#[inline(always)]
+const fn t(x: u32) -> u32
+{
+    let b: u32 = tau(x);
+    b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
+}
+
+/// Round function used by the key expansion.
+#[inline(always)]
+const fn t_key(x: u32) -> u32
+{
+    let b: u32 = tau(x);
+    b ^ b.rotate_left(13) ^ b.rotate_left(23)
+}
+
+/// Round constant `i`, where byte `j` is `(4i + j) * 7 mod 256`.
+#[inline(always)]
+const fn ck(i: usize) -> u32
+{
+    let b: usize = 4 * i * 7;
+    u32::from_be_bytes([b as u8, (b + 7) as u8, (b + 14) as u8, (b + 21) as u8])
+}
+
+/// Expands the key into 32 round keys used for encryption.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `key` must point to an array with a length of 16 bytes.
+/// `key_schedule` must point to an array with a length of 32 (128 bytes).
+pub const unsafe fn sm4_expand_key(key: *const u8, key_schedule: *mut u32)
+{
+    let mut k: [u32; 4] = [0; 4];
+    let mut i = 0;
+    while i < 4 {
+        k[i] = u32::from_be_bytes(*key.cast::<[u8; 4]>().add(i)) ^ FK[i];
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < 32 {
+        let rk: u32 = k[i % 4] ^ t_key(k[(i + 1) % 4] ^ k[(i + 2) % 4] ^ k[(i + 3) % 4] ^ ck(i));
+        k[i % 4] = rk;
+        *key_schedule.add(i) = rk;
+        i += 1;
+    }
+}
+
+/// Converts an encryption key schedule into a decryption key schedule by
+/// reversing the order of the round keys.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `key_schedule` must point to an array with a length of 32 (128
+/// bytes).
+pub const unsafe fn sm4_inverse_key(key_schedule: *mut u32)
+{
+    let mut i = 0;
+    while i < 16 {
+        let rk: u32 = *key_schedule.add(i);
+        *key_schedule.add(i) = *key_schedule.add(31 - i);
+        *key_schedule.add(31 - i) = rk;
+        i += 1;
+    }
+}
+
+/// Encrypts a single block in-place.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `block` must point to an array with a length of 16 bytes.
+/// `key_schedule` must point to an array with a length of 32 (128 bytes).
+pub const unsafe fn sm4_encrypt1(block: *mut u8, key_schedule: *const u32)
+{
+    let block: *mut [u8; 4] = block.cast();
+    let mut x: [u32; 4] = [
+        u32::from_be_bytes(*block.add(0)),
+        u32::from_be_bytes(*block.add(1)),
+        u32::from_be_bytes(*block.add(2)),
+        u32::from_be_bytes(*block.add(3)),
+    ];
+
+    let mut i = 0;
+    while i < 32 {
+        x[i % 4] ^= t(x[(i + 1) % 4] ^ x[(i + 2) % 4] ^ x[(i + 3) % 4] ^ *key_schedule.add(i));
+        i += 1;
+    }
+
+    // The output is the last four words in reverse order.
+    *block.add(0) = x[3].to_be_bytes();
+    *block.add(1) = x[2].to_be_bytes();
+    *block.add(2) = x[1].to_be_bytes();
+    *block.add(3) = x[0].to_be_bytes();
+}
+
+/// Decrypts a single block in-place.
+///
+/// # Safety
+///
+/// The caller must guarantee that the passed variables point to valid memory
+/// spaces. `block` must point to an array with a length of 16 bytes.
+/// `key_schedule` must point to an array with a length of 32 (128 bytes) that
+/// was converted by [`sm4_inverse_key`].
+pub const unsafe fn sm4_decrypt1(block: *mut u8, key_schedule: *const u32)
+{
+    sm4_encrypt1(block, key_schedule);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn sm4_example()
+    {
+        // Example 1 from GB/T 32907-2016, where the key is also the plaintext.
+        let key: [u8; 16] = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54,
+            0x32, 0x10,
+        ];
+        let expected: [u8; 16] = [
+            0x68, 0x1e, 0xdf, 0x34, 0xd2, 0x06, 0x96, 0x5e, 0x86, 0xb3, 0xe9, 0x4f, 0x53, 0x6e,
+            0x42, 0x46,
+        ];
+        let mut key_schedule: [u32; 32] = [0; 32];
+        let mut block: [u8; 16] = key;
+
+        unsafe { sm4_expand_key(key.as_ptr(), key_schedule.as_mut_ptr()) };
+        unsafe { sm4_encrypt1(block.as_mut_ptr(), key_schedule.as_ptr()) };
+        assert_eq!(block, expected);
+
+        unsafe { sm4_inverse_key(key_schedule.as_mut_ptr()) };
+        unsafe { sm4_decrypt1(block.as_mut_ptr(), key_schedule.as_ptr()) };
+        assert_eq!(block, key);
+    }
+}
@@ -10,6 +10,7 @@ mod aesni;
 mod digest;
 mod hmac;
 mod md_compress;
+mod sm4;
 
 /// Version of the library.
 #[pyfunction]
@@ -29,6 +30,7 @@ fn oxicrypt(py: Python, m: &PyModule) -> PyResult<()>
     #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
     aes_arm::register(py, m_core)?;
     md_compress::register(py, m_core)?;
+    sm4::register(py, m_core)?;
     m.add_submodule(m_core)?;
 
     // register the digest library
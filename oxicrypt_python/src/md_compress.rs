@@ -3,12 +3,31 @@ use pyo3::prelude::*;
 use pyo3::types::PyByteArray;
 use pyo3::types::PyBytes;
 
+#[pyfunction]
+pub unsafe fn md4(state: &PyByteArray, block: &PyBytes)
+{
+    md_compress::md4(
+        state.as_bytes_mut().as_mut_ptr().cast(),
+        block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
+    )
+}
 #[pyfunction]
 pub unsafe fn md5(state: &PyByteArray, block: &PyBytes)
 {
     md_compress::md5(
         state.as_bytes_mut().as_mut_ptr().cast(),
         block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
+    )
+}
+#[pyfunction]
+pub unsafe fn ripemd160(state: &PyByteArray, block: &PyBytes)
+{
+    md_compress::ripemd160(
+        state.as_bytes_mut().as_mut_ptr().cast(),
+        block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
     )
 }
 #[pyfunction]
@@ -17,6 +36,17 @@ pub unsafe fn sha1(state: &PyByteArray, block: &PyBytes)
     md_compress::sha1(
         state.as_bytes_mut().as_mut_ptr().cast(),
         block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
+    )
+}
+#[pyfunction]
+pub unsafe fn sha1dc(state: &PyByteArray, block: &PyBytes, safe_hash: bool) -> bool
+{
+    md_compress::sha1dc(
+        state.as_bytes_mut().as_mut_ptr().cast(),
+        block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
+        safe_hash,
     )
 }
 #[pyfunction]
@@ -25,6 +55,7 @@ pub unsafe fn sha256(state: &PyByteArray, block: &PyBytes)
     md_compress::sha256(
         state.as_bytes_mut().as_mut_ptr().cast(),
         block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
     )
 }
 #[pyfunction]
@@ -33,6 +64,16 @@ pub unsafe fn sha512(state: &PyByteArray, block: &PyBytes)
     md_compress::sha512(
         state.as_bytes_mut().as_mut_ptr().cast(),
         block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 128,
+    )
+}
+#[pyfunction]
+pub unsafe fn sm3(state: &PyByteArray, block: &PyBytes)
+{
+    md_compress::sm3(
+        state.as_bytes_mut().as_mut_ptr().cast(),
+        block.as_bytes().as_ptr().cast(),
+        block.as_bytes().len() / 64,
     )
 }
 
@@ -41,10 +82,14 @@ pub fn register(py: Python, m_core: &PyModule) -> PyResult<()>
 {
     let m = PyModule::new(py, "md_compress")?;
 
+    m.add_function(wrap_pyfunction!(md4, m)?)?;
     m.add_function(wrap_pyfunction!(md5, m)?)?;
+    m.add_function(wrap_pyfunction!(ripemd160, m)?)?;
     m.add_function(wrap_pyfunction!(sha1, m)?)?;
+    m.add_function(wrap_pyfunction!(sha1dc, m)?)?;
     m.add_function(wrap_pyfunction!(sha256, m)?)?;
     m.add_function(wrap_pyfunction!(sha512, m)?)?;
+    m.add_function(wrap_pyfunction!(sm3, m)?)?;
 
     m_core.add_submodule(m)?;
 
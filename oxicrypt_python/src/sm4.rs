@@ -0,0 +1,52 @@
+use oxicrypt_core::sm4;
+use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
+use pyo3::types::PyBytes;
+
+#[pyfunction]
+unsafe fn sm4_encrypt1(block: &PyByteArray, key_schedule: &PyBytes)
+{
+    sm4::sm4_encrypt1(
+        block.as_bytes_mut().as_mut_ptr(),
+        key_schedule.as_bytes().as_ptr().cast(),
+    )
+}
+
+#[pyfunction]
+unsafe fn sm4_decrypt1(block: &PyByteArray, key_schedule: &PyBytes)
+{
+    sm4::sm4_decrypt1(
+        block.as_bytes_mut().as_mut_ptr(),
+        key_schedule.as_bytes().as_ptr().cast(),
+    )
+}
+
+#[pyfunction]
+unsafe fn sm4_inverse_key(key_schedule: &PyByteArray)
+{
+    sm4::sm4_inverse_key(key_schedule.as_bytes_mut().as_mut_ptr().cast())
+}
+
+#[pyfunction]
+unsafe fn sm4_expand_key(key: &PyBytes, key_schedule: &PyByteArray)
+{
+    sm4::sm4_expand_key(
+        key.as_bytes().as_ptr(),
+        key_schedule.as_bytes_mut().as_mut_ptr().cast(),
+    )
+}
+
+#[inline(always)]
+pub fn register(py: Python, m_core: &PyModule) -> PyResult<()>
+{
+    let m = PyModule::new(py, "sm4")?;
+
+    m.add_function(wrap_pyfunction!(sm4_encrypt1, m)?)?;
+    m.add_function(wrap_pyfunction!(sm4_decrypt1, m)?)?;
+    m.add_function(wrap_pyfunction!(sm4_inverse_key, m)?)?;
+    m.add_function(wrap_pyfunction!(sm4_expand_key, m)?)?;
+
+    m_core.add_submodule(m)?;
+
+    Ok(())
+}